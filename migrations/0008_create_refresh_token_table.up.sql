@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE "SMS_GATEWAY_USER"."REFRESH_TOKEN"
+(
+    refresh_token_id serial NOT NULL,
+    token_hash character varying(64) NOT NULL,
+    family_id character varying(64) NOT NULL,
+    user_id integer NOT NULL,
+    expires_at timestamp with time zone NOT NULL,
+    revoked boolean NOT NULL DEFAULT FALSE,
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT pk_refresh_token_id PRIMARY KEY (refresh_token_id),
+    CONSTRAINT uq_refresh_token_token_hash UNIQUE (token_hash),
+    CONSTRAINT fk_refresh_token_user_id FOREIGN KEY (user_id) REFERENCES "SMS_GATEWAY_USER"."USER" (user_id)
+);
+
+CREATE INDEX idx_refresh_token_family_id ON "SMS_GATEWAY_USER"."REFRESH_TOKEN" (family_id);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "SMS_GATEWAY_USER"."USER_TOKEN_REVOCATION" RESTRICT;
+DROP TABLE IF EXISTS "SMS_GATEWAY_USER"."REVOKED_TOKEN" RESTRICT;
\ No newline at end of file
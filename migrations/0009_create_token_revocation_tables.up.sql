@@ -0,0 +1,18 @@
+-- Add up migration script here
+CREATE TABLE "SMS_GATEWAY_USER"."REVOKED_TOKEN"
+(
+    revoked_token_id serial NOT NULL,
+    jti character varying(64) NOT NULL,
+    user_id integer NOT NULL,
+    expires_at timestamp with time zone NOT NULL,
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT pk_revoked_token_id PRIMARY KEY (revoked_token_id),
+    CONSTRAINT uq_revoked_token_jti UNIQUE (jti)
+);
+
+CREATE TABLE "SMS_GATEWAY_USER"."USER_TOKEN_REVOCATION"
+(
+    user_id integer NOT NULL,
+    revoked_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT pk_user_token_revocation_user_id PRIMARY KEY (user_id)
+);
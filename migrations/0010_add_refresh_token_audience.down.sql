@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."REFRESH_TOKEN" DROP COLUMN IF EXISTS audience;
\ No newline at end of file
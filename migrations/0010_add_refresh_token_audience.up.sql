@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."REFRESH_TOKEN" ADD COLUMN audience character varying(150);
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" RESTRICT;
\ No newline at end of file
@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN"
+(
+    password_reset_token_id serial NOT NULL,
+    token_hash character varying(64) NOT NULL,
+    user_id integer NOT NULL,
+    expires_at timestamp with time zone NOT NULL,
+    used_at timestamp with time zone,
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT pk_password_reset_token_id PRIMARY KEY (password_reset_token_id),
+    CONSTRAINT uq_password_reset_token_token_hash UNIQUE (token_hash),
+    CONSTRAINT fk_password_reset_token_user_id FOREIGN KEY (user_id) REFERENCES "SMS_GATEWAY_USER"."USER" (user_id)
+);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."USER_CODE" DROP COLUMN IF EXISTS attempts;
\ No newline at end of file
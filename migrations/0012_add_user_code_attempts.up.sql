@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."USER_CODE" ADD COLUMN attempts integer NOT NULL DEFAULT 0;
\ No newline at end of file
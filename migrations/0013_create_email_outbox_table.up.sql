@@ -0,0 +1,18 @@
+-- Add up migration script here
+CREATE TABLE "SMS_GATEWAY_USER"."EMAIL_OUTBOX"
+(
+    email_outbox_id serial NOT NULL,
+    subject character varying(255) NOT NULL,
+    recipient character varying(150) NOT NULL,
+    sender character varying(150) NOT NULL,
+    html text NOT NULL,
+    status character varying(20) NOT NULL DEFAULT 'PENDING',
+    attempts integer NOT NULL DEFAULT 0,
+    last_error text,
+    next_attempt_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    sent_at timestamp with time zone,
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT pk_email_outbox_id PRIMARY KEY (email_outbox_id)
+);
+
+CREATE INDEX idx_email_outbox_status_next_attempt_at ON "SMS_GATEWAY_USER"."EMAIL_OUTBOX" (status, next_attempt_at);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."EMAIL_OUTBOX" DROP COLUMN IF EXISTS plain_text;
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."EMAIL_OUTBOX" ADD COLUMN plain_text text NOT NULL DEFAULT '';
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."USER" DROP COLUMN IF EXISTS locale;
\ No newline at end of file
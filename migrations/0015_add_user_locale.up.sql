@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."USER" ADD COLUMN locale character varying(10) NOT NULL DEFAULT 'en';
\ No newline at end of file
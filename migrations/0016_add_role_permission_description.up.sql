@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."ROLE" ADD COLUMN description character varying(255);
+ALTER TABLE "SMS_GATEWAY_USER"."PERMISSION" ADD COLUMN description character varying(255);
\ No newline at end of file
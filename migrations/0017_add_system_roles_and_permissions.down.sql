@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."PERMISSION" DROP COLUMN IF EXISTS is_system;
+ALTER TABLE "SMS_GATEWAY_USER"."ROLE" DROP COLUMN IF EXISTS is_system;
\ No newline at end of file
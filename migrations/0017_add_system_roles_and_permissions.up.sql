@@ -0,0 +1,29 @@
+-- Add up migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."ROLE" ADD COLUMN is_system boolean NOT NULL DEFAULT false;
+ALTER TABLE "SMS_GATEWAY_USER"."PERMISSION" ADD COLUMN is_system boolean NOT NULL DEFAULT false;
+
+INSERT INTO "SMS_GATEWAY_USER"."PERMISSION" (name, is_system) VALUES
+    ('PERMISSION_READ', true),
+    ('PERMISSION_WRITE', true),
+    ('PERMISSION_UPDATE', true),
+    ('PERMISSION_DELETE', true),
+    ('ROLE_READ', true),
+    ('ROLE_WRITE', true),
+    ('ROLE_UPDATE', true),
+    ('ROLE_DELETE', true),
+    ('USER_READ', true),
+    ('USER_WRITE', true),
+    ('USER_UPDATE', true),
+    ('USER_DELETE', true),
+    ('KEY_READ', true),
+    ('KEY_UPDATE', true),
+    ('EMAIL_READ', true),
+    ('EMAIL_UPDATE', true)
+ON CONFLICT (name) DO UPDATE SET is_system = true;
+
+INSERT INTO "SMS_GATEWAY_USER"."ROLE" (name, is_system) VALUES ('SUPER_ADMIN', true)
+ON CONFLICT (name) DO UPDATE SET is_system = true;
+
+INSERT INTO "SMS_GATEWAY_USER"."ROLE_PERMISSION" (role_id, permission_id)
+SELECT r.role_id, p.permission_id FROM "SMS_GATEWAY_USER"."ROLE" r CROSS JOIN "SMS_GATEWAY_USER"."PERMISSION" p WHERE r.is_system AND p.is_system
+ON CONFLICT DO NOTHING;
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DELETE FROM "SMS_GATEWAY_USER"."ROLE_PERMISSION" rp USING "SMS_GATEWAY_USER"."ROLE" r WHERE rp.role_id = r.role_id AND r.name = 'USER' AND NOT EXISTS (SELECT 1 FROM "SMS_GATEWAY_USER"."USER" u WHERE u.role_id = r.role_id);
+DELETE FROM "SMS_GATEWAY_USER"."ROLE" r WHERE r.name = 'USER' AND NOT EXISTS (SELECT 1 FROM "SMS_GATEWAY_USER"."USER" u WHERE u.role_id = r.role_id);
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- Add up migration script here
+INSERT INTO "SMS_GATEWAY_USER"."ROLE" (name) VALUES ('USER') ON CONFLICT (name) DO NOTHING;
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."USER_CREDENTIAL" DROP COLUMN IF EXISTS password_change_required;
\ No newline at end of file
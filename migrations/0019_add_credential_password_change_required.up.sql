@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."USER_CREDENTIAL" ADD COLUMN password_change_required boolean NOT NULL DEFAULT false;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "SMS_GATEWAY_USER"."ACCOUNT_UNLOCK_TOKEN" RESTRICT;
+DROP TABLE IF EXISTS "SMS_GATEWAY_USER"."IP_LOCKOUT" RESTRICT;
+DROP TABLE IF EXISTS "SMS_GATEWAY_USER"."USER_LOCKOUT" RESTRICT;
\ No newline at end of file
@@ -0,0 +1,32 @@
+-- Add up migration script here
+CREATE TABLE "SMS_GATEWAY_USER"."USER_LOCKOUT"
+(
+    user_id integer NOT NULL,
+    failed_attempts integer NOT NULL DEFAULT 0,
+    last_failed_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_until timestamp with time zone,
+    CONSTRAINT pk_user_lockout_user_id PRIMARY KEY (user_id),
+    CONSTRAINT fk_user_lockout_user_id FOREIGN KEY (user_id) REFERENCES "SMS_GATEWAY_USER"."USER" (user_id)
+);
+
+CREATE TABLE "SMS_GATEWAY_USER"."IP_LOCKOUT"
+(
+    ip_address character varying(45) NOT NULL,
+    failed_attempts integer NOT NULL DEFAULT 0,
+    last_failed_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_until timestamp with time zone,
+    CONSTRAINT pk_ip_lockout_ip_address PRIMARY KEY (ip_address)
+);
+
+CREATE TABLE "SMS_GATEWAY_USER"."ACCOUNT_UNLOCK_TOKEN"
+(
+    account_unlock_token_id serial NOT NULL,
+    token_hash character varying(64) NOT NULL,
+    user_id integer NOT NULL,
+    expires_at timestamp with time zone NOT NULL,
+    used_at timestamp with time zone,
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT pk_account_unlock_token_id PRIMARY KEY (account_unlock_token_id),
+    CONSTRAINT uq_account_unlock_token_token_hash UNIQUE (token_hash),
+    CONSTRAINT fk_account_unlock_token_user_id FOREIGN KEY (user_id) REFERENCES "SMS_GATEWAY_USER"."USER" (user_id)
+);
\ No newline at end of file
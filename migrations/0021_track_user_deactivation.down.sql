@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS tr_user_deactivation ON "SMS_GATEWAY_USER"."USER";
+DROP FUNCTION IF EXISTS "SMS_GATEWAY_USER".record_user_deactivation();
+DROP INDEX IF EXISTS "SMS_GATEWAY_USER".ix_revoked_token_user_id;
+DROP TABLE IF EXISTS "SMS_GATEWAY_USER"."USER_DEACTIVATION" RESTRICT;
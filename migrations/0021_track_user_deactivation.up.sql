@@ -0,0 +1,23 @@
+-- Add up migration script here
+CREATE TABLE "SMS_GATEWAY_USER"."USER_DEACTIVATION"
+(
+    user_id integer NOT NULL,
+    deactivated_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT pk_user_deactivation_user_id PRIMARY KEY (user_id)
+);
+
+CREATE INDEX ix_revoked_token_user_id ON "SMS_GATEWAY_USER"."REVOKED_TOKEN" (user_id);
+
+-- recorded by the database so disabling a user through the admin binary or plain sql is seen by every server
+CREATE FUNCTION "SMS_GATEWAY_USER".record_user_deactivation() RETURNS trigger AS $$
+BEGIN
+    IF (OLD.enabled AND NOT NEW.enabled) OR (OLD.email_confirmed AND NOT NEW.email_confirmed) THEN
+        INSERT INTO "SMS_GATEWAY_USER"."USER_DEACTIVATION" (user_id) VALUES (NEW.user_id)
+        ON CONFLICT (user_id) DO UPDATE SET deactivated_at = CURRENT_TIMESTAMP;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER tr_user_deactivation AFTER UPDATE OF enabled, email_confirmed ON "SMS_GATEWAY_USER"."USER"
+    FOR EACH ROW EXECUTE FUNCTION "SMS_GATEWAY_USER".record_user_deactivation();
@@ -0,0 +1,18 @@
+-- Add down migration script here
+CREATE TABLE "SMS_GATEWAY_USER"."USER_LOCKOUT"
+(
+    user_id integer NOT NULL,
+    failed_attempts integer NOT NULL DEFAULT 0,
+    last_failed_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_until timestamp with time zone,
+    CONSTRAINT pk_user_lockout_user_id PRIMARY KEY (user_id),
+    CONSTRAINT fk_user_lockout_user_id FOREIGN KEY (user_id) REFERENCES "SMS_GATEWAY_USER"."USER" (user_id)
+);
+
+INSERT INTO "SMS_GATEWAY_USER"."USER_LOCKOUT" (user_id, failed_attempts, last_failed_at, locked_until)
+SELECT u.user_id, l.failed_attempts, l.last_failed_at, l.locked_until
+FROM "SMS_GATEWAY_USER"."EMAIL_LOCKOUT" l
+JOIN "SMS_GATEWAY_USER"."USER" u ON lower(u.email_address) = l.email_address
+ON CONFLICT (user_id) DO NOTHING;
+
+DROP TABLE IF EXISTS "SMS_GATEWAY_USER"."EMAIL_LOCKOUT" RESTRICT;
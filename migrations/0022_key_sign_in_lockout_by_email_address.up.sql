@@ -0,0 +1,18 @@
+-- Add up migration script here
+-- keyed by the submitted address rather than the user, so unknown addresses lock exactly like registered ones
+CREATE TABLE "SMS_GATEWAY_USER"."EMAIL_LOCKOUT"
+(
+    email_address character varying(150) NOT NULL,
+    failed_attempts integer NOT NULL DEFAULT 0,
+    last_failed_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_until timestamp with time zone,
+    CONSTRAINT pk_email_lockout_email_address PRIMARY KEY (email_address)
+);
+
+INSERT INTO "SMS_GATEWAY_USER"."EMAIL_LOCKOUT" (email_address, failed_attempts, last_failed_at, locked_until)
+SELECT lower(u.email_address), l.failed_attempts, l.last_failed_at, l.locked_until
+FROM "SMS_GATEWAY_USER"."USER_LOCKOUT" l
+JOIN "SMS_GATEWAY_USER"."USER" u ON u.user_id = l.user_id
+ON CONFLICT (email_address) DO NOTHING;
+
+DROP TABLE IF EXISTS "SMS_GATEWAY_USER"."USER_LOCKOUT" RESTRICT;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."USER_CODE" DROP COLUMN IF EXISTS last_sent_at;
+ALTER TABLE "SMS_GATEWAY_USER"."USER_CODE" DROP COLUMN IF EXISTS resend_count;
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."USER_CODE" ADD COLUMN resend_count integer NOT NULL DEFAULT 0;
+ALTER TABLE "SMS_GATEWAY_USER"."USER_CODE" ADD COLUMN last_sent_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP;
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "SMS_GATEWAY_USER"."SIGNING_KEY_SCHEDULE" RESTRICT;
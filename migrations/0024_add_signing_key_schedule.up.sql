@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- when each signing key starts signing and stops verifying, read by every server so rotations survive restarts
+CREATE TABLE "SMS_GATEWAY_USER"."SIGNING_KEY_SCHEDULE"
+(
+    kid character varying(255) NOT NULL,
+    activate_at timestamp with time zone,
+    retire_at timestamp with time zone,
+    updated_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT pk_signing_key_schedule_kid PRIMARY KEY (kid)
+);
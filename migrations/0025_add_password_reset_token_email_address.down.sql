@@ -0,0 +1,5 @@
+-- Add down migration script here
+DELETE FROM "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" WHERE user_id IS NULL;
+ALTER TABLE "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" ALTER COLUMN user_id SET NOT NULL;
+DROP INDEX IF EXISTS "SMS_GATEWAY_USER".ix_password_reset_token_email_address;
+ALTER TABLE "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" DROP COLUMN IF EXISTS email_address;
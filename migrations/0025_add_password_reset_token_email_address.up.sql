@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" ADD COLUMN email_address character varying(255);
+UPDATE "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" t SET email_address = u.email_address FROM "SMS_GATEWAY_USER"."USER" u WHERE u.user_id = t.user_id;
+ALTER TABLE "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" ALTER COLUMN email_address SET NOT NULL;
+ALTER TABLE "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" ALTER COLUMN user_id DROP NOT NULL;
+CREATE INDEX ix_password_reset_token_email_address ON "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" (email_address, created_at);
\ No newline at end of file
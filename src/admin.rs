@@ -0,0 +1,452 @@
+use std::{collections::HashMap, fs::{self, File}, io::{BufWriter, Write}};
+
+use argon2::Config;
+
+use crate::{bootstrap::{self, BootstrapAdmin}, dao::Database, entity::user::User, i18n::Locale, model::user::CreateUser, password_policy::{self, BreachedPasswords, PasswordPolicy}, util};
+
+pub const USAGE: &str = "Usage: admin <command> [options]
+
+Commands:
+  migrate                                                   apply pending database migrations
+  bootstrap --email <email> [--first-name <name>] [--surname <name>] [--password <password>]
+                                                            seed system data and create the first administrator
+  create-user --email <email> --first-name <name> --surname <name> --role <role> [--password <password>] [--require-password-change]
+  set-password --email <email> [--password <password>] [--require-password-change]
+  assign-role --email <email> --role <role>
+  grant-permission --role <role> --permission <permission>
+  revoke-permission --role <role> --permission <permission>
+  disable-user --email <email>
+  revoke-tokens --email <email>
+  export-users                                              print every user as csv
+  build-breach-filter --input <file> --output <file> [--false-positive-rate <rate>]
+                                                            build the PASSWORD_BREACHED_FILTER file from a list of one password per line
+
+bootstrap and set-password read the password from stdin when --password is not given.
+create-user and set-password apply the same password policy as the server.";
+
+const SWITCHES: &[&str] = &["require-password-change"];
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Migrate,
+    Bootstrap { first_name: String, surname: String, email_address: String, password: String },
+    CreateUser { first_name: String, surname: String, email_address: String, role: String, password: Option<String>, require_password_change: bool },
+    SetPassword { email_address: String, password: String, require_password_change: bool },
+    AssignRole { email_address: String, role: String },
+    GrantPermission { role: String, permission: String },
+    RevokePermission { role: String, permission: String },
+    DisableUser { email_address: String },
+    RevokeTokens { email_address: String },
+    ExportUsers,
+    BuildBreachFilter { input: String, output: String, false_positive_rate: f64 },
+}
+
+impl Command {
+
+    // read_password is only called when a command needs a password that was not passed as a flag
+    pub fn parse(args: &[String], read_password: impl FnOnce() -> Result<String, String>) -> Result<Command, String> {
+        let (name, rest) = args.split_first().ok_or_else(|| "No command was given!".to_string())?;
+        let mut flags = Flags::parse(rest)?;
+
+        let command = match name.as_str() {
+            "migrate" => Command::Migrate,
+            "bootstrap" => Command::Bootstrap {
+                email_address: flags.required("email")?,
+                first_name: flags.optional("first-name").unwrap_or_else(|| "System".to_string()),
+                surname: flags.optional("surname").unwrap_or_else(|| "Administrator".to_string()),
+                password: flags.password(read_password)?,
+            },
+            "create-user" => {
+                let email_address = flags.required("email")?;
+                let first_name = flags.required("first-name")?;
+                let surname = flags.required("surname")?;
+                let role = flags.required("role")?;
+
+                Command::CreateUser {
+                    email_address,
+                    first_name,
+                    surname,
+                    role,
+                    password: flags.optional("password"),
+                    require_password_change: flags.switch("require-password-change"),
+                }
+            },
+            "set-password" => Command::SetPassword {
+                email_address: flags.required("email")?,
+                require_password_change: flags.switch("require-password-change"),
+                password: flags.password(read_password)?,
+            },
+            "assign-role" => Command::AssignRole { email_address: flags.required("email")?, role: flags.required("role")? },
+            "grant-permission" => Command::GrantPermission { role: flags.required("role")?, permission: flags.required("permission")? },
+            "revoke-permission" => Command::RevokePermission { role: flags.required("role")?, permission: flags.required("permission")? },
+            "disable-user" => Command::DisableUser { email_address: flags.required("email")? },
+            "revoke-tokens" => Command::RevokeTokens { email_address: flags.required("email")? },
+            "export-users" => Command::ExportUsers,
+            "build-breach-filter" => {
+                let input = flags.required("input")?;
+                let output = flags.required("output")?;
+
+                let false_positive_rate = match flags.optional("false-positive-rate") {
+                    Some(rate) => rate.parse::<f64>().ok()
+                        .filter(|rate| *rate > 0.0 && *rate < 1.0)
+                        .ok_or_else(|| format!("--false-positive-rate {} should be between 0 and 1!", rate))?,
+                    None => password_policy::DEFAULT_FALSE_POSITIVE_RATE,
+                };
+
+                Command::BuildBreachFilter { input, output, false_positive_rate }
+            },
+            _ => return Err(format!("Unknown command {}!", name)),
+        };
+
+        flags.ensure_consumed()?;
+        Ok(command)
+    }
+}
+
+struct Flags {
+    values: HashMap<String, String>,
+}
+
+impl Flags {
+
+    fn parse(args: &[String]) -> Result<Flags, String> {
+        let mut values = HashMap::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let name = arg.strip_prefix("--").ok_or_else(|| format!("Unexpected argument {}!", arg))?;
+
+            let value = match SWITCHES.contains(&name) {
+                true => String::new(),
+                false => args.next().cloned().ok_or_else(|| format!("--{} needs a value!", name))?,
+            };
+
+            if values.insert(name.to_string(), value).is_some() {
+                return Err(format!("--{} was given more than once!", name));
+            }
+        }
+
+        Ok(Flags { values })
+    }
+
+    fn optional(&mut self, name: &str) -> Option<String> {
+        self.values.remove(name)
+    }
+
+    fn required(&mut self, name: &str) -> Result<String, String> {
+        self.optional(name).ok_or_else(|| format!("--{} is required!", name))
+    }
+
+    fn switch(&mut self, name: &str) -> bool {
+        self.values.remove(name).is_some()
+    }
+
+    fn password(&mut self, read_password: impl FnOnce() -> Result<String, String>) -> Result<String, String> {
+        let password = match self.optional("password") {
+            Some(password) => password,
+            None => read_password()?,
+        };
+
+        match password.is_empty() {
+            true => Err("The password cannot be empty!".to_string()),
+            false => Ok(password),
+        }
+    }
+
+    fn ensure_consumed(&self) -> Result<(), String> {
+        match self.values.keys().next() {
+            Some(name) => Err(format!("Unknown option --{}!", name)),
+            None => Ok(()),
+        }
+    }
+}
+
+pub async fn execute(db: &Database<'_>, argon_config: &Config<'_>, password_policy: &PasswordPolicy, command: Command, out: &mut dyn Write) -> Result<(), String> {
+    match command {
+        Command::Migrate => {
+            sqlx::migrate!().run(&*db.users.pool).await
+                .map_err(|error| format!("Unable to run migrations: {}", error))?;
+
+            write_line(out, "Migrations are up to date")
+        },
+        Command::Bootstrap { first_name, surname, email_address, password } => {
+            let admin = BootstrapAdmin { first_name, surname, email_address, password };
+            let report = bootstrap::run(db, argon_config, password_policy, Some(&admin)).await?;
+
+            match report.admin_id {
+                Some(admin_id) => write_line(out, &format!("Created administrator {} ({}), who must change their password on first sign-in", admin.email_address, admin_id)),
+                None => write_line(out, "System data is seeded, no administrator was created"),
+            }
+        },
+        Command::CreateUser { first_name, surname, email_address, role, password, require_password_change } => {
+            let role = db.roles.find_by_name(&role).await
+                .map_err(|error| format!("Role {} could not be found: {}", role, error))?;
+
+            if let Some(password) = &password {
+                check_password(password_policy, password, &[&first_name, &surname, &email_address])?;
+            }
+
+            let create = CreateUser {
+                first_name,
+                middle_name: None,
+                surname,
+                email_address: email_address.clone(),
+                mobile_number: None,
+                role_id: role.role_id,
+                locale: None,
+            };
+
+            let mut transaction = db.begin().await.map_err(|error| error.to_string())?;
+
+            let user = db.users.create_with(&mut transaction, &create).await
+                .map_err(|error| format!("Unable to create {}: {}", email_address, error))?;
+
+            db.users.activate_with(&mut transaction, &user.user_id).await
+                .map_err(|error| format!("Unable to activate {}: {}", email_address, error))?;
+
+            if let Some(password) = password {
+                let password = util::hash_password(&password, argon_config).await.map_err(|error| error.to_string())?;
+
+                db.user_credentials.create_for_email_address_with(&mut transaction, &user.user_id, &email_address, &password).await
+                    .map_err(|error| format!("Unable to create the credential of {}: {}", email_address, error))?;
+
+                if require_password_change {
+                    db.user_credentials.require_password_change_with(&mut transaction, &user.user_id).await
+                        .map_err(|error| format!("Unable to flag the credential of {}: {}", email_address, error))?;
+                }
+            }
+
+            transaction.commit().await.map_err(|error| error.to_string())?;
+
+            write_line(out, &format!("Created user {} ({}) with role {}", email_address, user.user_id, role.name))
+        },
+        Command::SetPassword { email_address, password, require_password_change } => {
+            let user = find_user(db, &email_address).await?;
+
+            check_password(password_policy, &password, &[&user.first_name, &user.surname, &user.email_address])?;
+
+            let password = util::hash_password(&password, argon_config).await.map_err(|error| error.to_string())?;
+
+            // users created without a credential get one
+            match db.user_credentials.update_password(&user.user_id, &password).await {
+                Err(sqlx::Error::RowNotFound) => db.user_credentials.create_for_email_address(&user.user_id, &email_address, &password).await,
+                result => result,
+            }
+            .map_err(|error| format!("Unable to set the password of {}: {}", email_address, error))?;
+
+            if require_password_change {
+                db.user_credentials.require_password_change(&user.user_id).await
+                    .map_err(|error| format!("Unable to flag the credential of {}: {}", email_address, error))?;
+            }
+
+            // same as a password reset, sessions started with the old password end
+            revoke_tokens(db, &user).await?;
+
+            write_line(out, &format!("Set the password of {}", email_address))
+        },
+        Command::AssignRole { email_address, role } => {
+            let mut user = find_user(db, &email_address).await?;
+            let role = db.roles.find_by_name(&role).await
+                .map_err(|error| format!("Role {} could not be found: {}", role, error))?;
+
+            user.role_id = role.role_id;
+            db.users.update_user(&user).await
+                .map_err(|error| format!("Unable to update {}: {}", email_address, error))?;
+
+            // tokens carry the permissions of the old role
+            revoke_tokens(db, &user).await?;
+
+            write_line(out, &format!("Assigned role {} to {}", role.name, email_address))
+        },
+        Command::GrantPermission { role, permission } => {
+            let role = db.roles.find_by_name(&role).await
+                .map_err(|error| format!("Role {} could not be found: {}", role, error))?;
+            let permission = db.permissions.find_by_name(&permission).await
+                .map_err(|error| format!("Permission {} could not be found: {}", permission, error))?;
+
+            let granted = db.role_permissions.add_role_permission(&role.role_id, &permission.permission_id).await
+                .map_err(|error| error.to_string())?;
+
+            match granted {
+                0 => write_line(out, &format!("Role {} already has permission {}", role.name, permission.name)),
+                _ => write_line(out, &format!("Granted permission {} to role {}", permission.name, role.name)),
+            }
+        },
+        Command::RevokePermission { role, permission } => {
+            let role = db.roles.find_by_name(&role).await
+                .map_err(|error| format!("Role {} could not be found: {}", role, error))?;
+            let permission = db.permissions.find_by_name(&permission).await
+                .map_err(|error| format!("Permission {} could not be found: {}", permission, error))?;
+
+            let core_permissions = db.role_permissions.find_core_permissions(&role.role_id).await
+                .map_err(|error| error.to_string())?;
+
+            if core_permissions.contains(&permission.permission_id) {
+                return Err(format!("Permission {} is a core permission of system role {} and cannot be revoked!", permission.name, role.name));
+            }
+
+            let revoked = db.role_permissions.remove_role_permission(&role.role_id, &permission.permission_id).await
+                .map_err(|error| error.to_string())?;
+
+            match revoked {
+                0 => write_line(out, &format!("Role {} does not have permission {}", role.name, permission.name)),
+                _ => write_line(out, &format!("Revoked permission {} from role {}", permission.name, role.name)),
+            }
+        },
+        Command::DisableUser { email_address } => {
+            let mut user = find_user(db, &email_address).await?;
+
+            user.enabled = false;
+            db.users.update_user(&user).await
+                .map_err(|error| format!("Unable to update {}: {}", email_address, error))?;
+
+            revoke_tokens(db, &user).await?;
+
+            write_line(out, &format!("Disabled {}", email_address))
+        },
+        Command::RevokeTokens { email_address } => {
+            let user = find_user(db, &email_address).await?;
+            revoke_tokens(db, &user).await?;
+
+            write_line(out, &format!("Revoked the tokens of {}", email_address))
+        },
+        Command::ExportUsers => {
+            let roles: HashMap<i16, String> = db.roles.find_all().await
+                .map_err(|error| error.to_string())?
+                .into_iter()
+                .map(|role| (role.role_id, role.name))
+                .collect();
+
+            let mut users = db.users.find_all().await.map_err(|error| error.to_string())?;
+            users.sort_by_key(|user| user.user_id);
+
+            write_line(out, "user_id,first_name,middle_name,surname,email_address,mobile_number,enabled,email_confirmed,role,locale,created_at")?;
+
+            for user in users {
+                let role = roles.get(&user.role_id).cloned().unwrap_or_default();
+                let fields = [
+                    user.user_id.to_string(),
+                    user.first_name,
+                    user.middle_name.unwrap_or_default(),
+                    user.surname,
+                    user.email_address,
+                    user.mobile_number.unwrap_or_default(),
+                    user.enabled.to_string(),
+                    user.email_confirmed.to_string(),
+                    role,
+                    user.locale,
+                    user.created_at.to_rfc3339(),
+                ];
+
+                write_line(out, &fields.iter().map(|field| csv_field(field)).collect::<Vec<String>>().join(","))?;
+            }
+
+            Ok(())
+        },
+        Command::BuildBreachFilter { input, output, false_positive_rate } => {
+            let passwords: Vec<String> = fs::read_to_string(&input)
+                .map_err(|error| format!("Unable to read {}: {}", input, error))?
+                .lines()
+                .filter(|password| !password.is_empty())
+                .map(String::from)
+                .collect();
+
+            let filter = BreachedPasswords::build(&passwords, false_positive_rate);
+
+            let mut file = File::create(&output)
+                .map(BufWriter::new)
+                .map_err(|error| format!("Unable to create {}: {}", output, error))?;
+
+            filter.write(&mut file)
+                .and_then(|_| file.flush())
+                .map_err(|error| format!("Unable to write {}: {}", output, error))?;
+
+            write_line(out, &format!("Wrote {} passwords to {} ({} bytes)", passwords.len(), output, filter.size()))
+        },
+    }
+}
+
+fn check_password(password_policy: &PasswordPolicy, password: &str, personal: &[&str]) -> Result<(), String> {
+    password_policy.check(password, personal)
+        .map_err(|error| error.localised_message(Locale::default()))
+}
+
+async fn find_user(db: &Database<'_>, email_address: &String) -> Result<User, String> {
+    db.users.find_by_email_address(email_address).await
+        .map_err(|error| match error {
+            sqlx::Error::RowNotFound => format!("User {} could not be found!", email_address),
+            _ => error.to_string(),
+        })
+}
+
+async fn revoke_tokens(db: &Database<'_>, user: &User) -> Result<(), String> {
+    db.user_token_revocations.upsert(&user.user_id).await.map_err(|error| error.to_string())?;
+    db.refresh_tokens.revoke_by_user_id(&user.user_id).await.map_err(|error| error.to_string())?;
+    Ok(())
+}
+
+fn write_line(out: &mut dyn Write, line: &str) -> Result<(), String> {
+    writeln!(out, "{}", line).map_err(|error| error.to_string())
+}
+
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod admin_tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_reads_flags_and_switches() {
+        let command = Command::parse(&args("create-user --email a@test.com --first-name Ann --surname Lee --role ADMIN --require-password-change"), || unreachable!());
+
+        assert_eq!(command, Ok(Command::CreateUser {
+            first_name: "Ann".to_string(),
+            surname: "Lee".to_string(),
+            email_address: "a@test.com".to_string(),
+            role: "ADMIN".to_string(),
+            password: None,
+            require_password_change: true,
+        }));
+    }
+
+    #[test]
+    fn parse_reads_missing_password_from_callback() {
+        let command = Command::parse(&args("set-password --email a@test.com"), || Ok("Pass12345".to_string()));
+
+        assert_eq!(command, Ok(Command::SetPassword { email_address: "a@test.com".to_string(), password: "Pass12345".to_string(), require_password_change: false }));
+    }
+
+    #[test]
+    fn parse_rejects_unknown_and_missing_options() {
+        assert!(Command::parse(&args("disable-user --email a@test.com --force yes"), || unreachable!()).is_err());
+        assert!(Command::parse(&args("assign-role --email a@test.com"), || unreachable!()).is_err());
+        assert!(Command::parse(&args("drop-everything"), || unreachable!()).is_err());
+    }
+
+    #[test]
+    fn parse_reads_and_bounds_the_false_positive_rate() {
+        let command = Command::parse(&args("build-breach-filter --input breached.txt --output breached.bin"), || unreachable!());
+
+        assert_eq!(command, Ok(Command::BuildBreachFilter {
+            input: "breached.txt".to_string(),
+            output: "breached.bin".to_string(),
+            false_positive_rate: password_policy::DEFAULT_FALSE_POSITIVE_RATE,
+        }));
+        assert!(Command::parse(&args("build-breach-filter --input a --output b --false-positive-rate 2"), || unreachable!()).is_err());
+    }
+
+    #[test]
+    fn csv_field_quotes_special_characters() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("Smith, \"Jr\""), "\"Smith, \"\"Jr\"\"\"");
+    }
+}
\ No newline at end of file
@@ -1,44 +1,107 @@
-use std::future::{ready, Ready};
+use std::{future::{ready, Future}, marker::PhantomData, pin::Pin};
 
 use actix_web::{HttpRequest, error::{ErrorUnauthorized, ErrorInternalServerError, ErrorBadRequest}, http, web, dev::Payload, Error as ActixWebError, FromRequest};
-use crate::{jwt, AppState};
+use crate::{entity::permission::Permission, error::{AppError, AppErrorType}, i18n::Message, jwt, model::claims::Claims, AppState};
 use log::error;
 
 pub struct JwtAuthenticationGuard {
-    pub id: i32
+    pub id: i32,
+    pub claims: Claims,
+}
+
+impl JwtAuthenticationGuard {
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.claims.permissions.iter().any(|name| name == permission)
+    }
+
+    // names of the given permissions the token does not carry
+    pub fn missing_permissions(&self, permissions: &[Permission]) -> Vec<String> {
+        permissions.iter()
+            .filter(|permission| !self.has_permission(&permission.name))
+            .map(|permission| permission.name.clone())
+            .collect()
+    }
+}
+
+pub trait RequiredPermission {
+    const NAME: &'static str;
+}
+
+pub struct PermissionGuard<P: RequiredPermission> {
+    pub auth: JwtAuthenticationGuard,
+    _permission: PhantomData<P>,
 }
 
 impl FromRequest for JwtAuthenticationGuard {
     type Error = ActixWebError;
-    type Future = Ready<Result<Self, Self::Error>>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
 
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
         let auth_header = req.headers().get(http::header::AUTHORIZATION);
         let app_data = match req.app_data::<web::Data<AppState>>() {
-            Some(data) => data,
-            None => return ready(Err(ErrorInternalServerError("Failed to retrieve app state").into()))
+            Some(data) => data.clone(),
+            None => return Box::pin(ready(Err(ErrorInternalServerError("Failed to retrieve app state"))))
         };
 
-        match auth_header {
+        let jwt = match auth_header {
             Some(token) => {
                 if let Ok(token_str) = token.to_str() {
                     if token_str.is_empty() || token_str.len() < 8{
-                        return ready(Err(ErrorUnauthorized("Authorization is required!").into()));
+                        return Box::pin(ready(Err(ErrorUnauthorized("Authorization is required!"))));
                     }
-                    let jwt = &token_str[7..];
-                    let result = jwt::validate_token(jwt, &app_data.jwt_config)
-                        .map(|claims| JwtAuthenticationGuard { id: claims.user.user_id })
-                        .map_err(|error| {
-                            error!("{}", error);
-                            ErrorUnauthorized("Authorization is required!").into()
-                        });
-
-                    ready(result)
+                    token_str[7..].to_string()
                 } else {
-                    ready(Err(ErrorBadRequest("Invalid token format").into()))
+                    return Box::pin(ready(Err(ErrorBadRequest("Invalid token format"))));
                 }
             },
-            None => ready(Err(ErrorUnauthorized("Authorization is required!").into()))
-        }
+            None => return Box::pin(ready(Err(ErrorUnauthorized("Authorization is required!"))))
+        };
+
+        Box::pin(async move {
+            let claims = jwt::validate_token(&jwt, &app_data.jwt_config)
+                .map_err(|error| {
+                    error!("{}", error);
+                    ErrorUnauthorized("Authorization is required!")
+                })?;
+
+            let is_revoked = app_data.revocation_store.is_revoked(&app_data.context, &claims, app_data.account_config.revoke_tokens_on_status_change).await
+                .map_err(|error| {
+                    error!("Error occured: {:?}", error);
+                    ErrorInternalServerError("Service unavailable try again later!")
+                })?;
+
+            if is_revoked {
+                error!("Token {} has been revoked!", claims.jti);
+                return Err(ErrorUnauthorized("Authorization is required!"));
+            }
+
+            match claims.user_id() {
+                Some(id) => Ok(JwtAuthenticationGuard { id, claims }),
+                None => {
+                    error!("Invalid subject {}", claims.sub);
+                    Err(ErrorUnauthorized("Authorization is required!"))
+                }
+            }
+        })
+    }
+}
+
+impl<P: RequiredPermission> FromRequest for PermissionGuard<P> {
+    type Error = ActixWebError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let auth = JwtAuthenticationGuard::from_request(req, payload);
+
+        Box::pin(async move {
+            let auth = auth.await?;
+
+            if auth.has_permission(P::NAME) {
+                Ok(PermissionGuard { auth, _permission: PhantomData })
+            } else {
+                error!("User with id {} is missing permission {}", auth.id, P::NAME);
+                Err(AppError::new(Some(Message::new("error.forbidden")), None, AppErrorType::ForbiddenError).into())
+            }
+        })
     }
 }
\ No newline at end of file
@@ -0,0 +1,54 @@
+use std::{env, io::{self, BufRead}, process::ExitCode};
+
+use argon2::Config;
+use bulk_sms_api::{admin::{self, Command}, config::DatabaseConfig, password_policy::PasswordPolicy};
+use dotenvy::dotenv;
+
+// logs are not initialised so that command output, like the user export, stays clean on stdout
+#[actix_web::main]
+async fn main() -> ExitCode {
+    dotenv().ok();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.is_empty() || args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", admin::USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let command = match Command::parse(&args, read_password) {
+        Ok(command) => command,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, admin::USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let password_policy = match PasswordPolicy::from_env() {
+        Ok(password_policy) => password_policy,
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let db = DatabaseConfig::from_env().connect().await;
+    let config = Config::default();
+
+    match admin::execute(&db, &config, &password_policy, command, &mut io::stdout()).await {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn read_password() -> Result<String, String> {
+    eprintln!("Password:");
+
+    let mut password = String::new();
+    io::stdin().lock().read_line(&mut password).map_err(|error| error.to_string())?;
+
+    Ok(password.trim_end_matches(['\r', '\n']).to_string())
+}
\ No newline at end of file
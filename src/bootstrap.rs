@@ -0,0 +1,131 @@
+use std::env;
+
+use argon2::Config;
+use log::{info, warn};
+
+use crate::{config::DEFAULT_SIGN_UP_ROLE, dao::Database, i18n::Locale, model::user::CreateUser, password_policy::PasswordPolicy, permissions, util};
+
+pub const SUPER_ADMIN_ROLE: &str = "SUPER_ADMIN";
+
+// any constant works as long as nothing else takes the same advisory lock
+const BOOTSTRAP_LOCK_ID: i64 = 0x534d_535f_4254;
+
+pub struct BootstrapAdmin {
+    pub first_name: String,
+    pub surname: String,
+    pub email_address: String,
+    pub password: String,
+}
+
+impl BootstrapAdmin {
+
+    // no admin is bootstrapped unless BOOTSTRAP_ADMIN_EMAIL is set
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let email_address = match env::var("BOOTSTRAP_ADMIN_EMAIL") {
+            Ok(email_address) if !email_address.trim().is_empty() => email_address.trim().to_string(),
+            _ => return Ok(None),
+        };
+
+        let password = env::var("BOOTSTRAP_ADMIN_PASSWORD")
+            .ok()
+            .filter(|password| !password.is_empty())
+            .ok_or_else(|| "BOOTSTRAP_ADMIN_PASSWORD is required when BOOTSTRAP_ADMIN_EMAIL is set!".to_string())?;
+
+        Ok(Some(BootstrapAdmin {
+            first_name: env::var("BOOTSTRAP_ADMIN_FIRST_NAME").unwrap_or_else(|_| "System".to_string()),
+            surname: env::var("BOOTSTRAP_ADMIN_SURNAME").unwrap_or_else(|_| "Administrator".to_string()),
+            email_address,
+            password,
+        }))
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct BootstrapReport {
+    pub permissions_seeded: u64,
+    pub role_permissions_granted: u64,
+    pub admin_id: Option<i32>,
+}
+
+// seeds the canonical permissions and roles and creates the first super-admin; safe to run on every start
+pub async fn run(db: &Database<'_>, argon_config: &Config<'_>, password_policy: &PasswordPolicy, admin: Option<&BootstrapAdmin>) -> Result<BootstrapReport, String> {
+    let mut transaction = db.begin().await
+        .map_err(|error| format!("Unable to start the bootstrap transaction: {}", error))?;
+
+    // instances starting together would otherwise race to create the same admin
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(BOOTSTRAP_LOCK_ID)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| format!("Unable to take the bootstrap lock: {}", error))?;
+
+    let permissions_seeded = db.permissions.seed_system_with(&mut transaction, permissions::ALL).await
+        .map_err(|error| format!("Unable to seed permissions: {}", error))?;
+
+    let super_admin = db.roles.seed_with(&mut transaction, SUPER_ADMIN_ROLE, true).await
+        .map_err(|error| format!("Unable to seed role {}: {}", SUPER_ADMIN_ROLE, error))?;
+
+    db.roles.seed_with(&mut transaction, DEFAULT_SIGN_UP_ROLE, false).await
+        .map_err(|error| format!("Unable to seed role {}: {}", DEFAULT_SIGN_UP_ROLE, error))?;
+
+    let role_permissions_granted = db.role_permissions.grant_system_permissions_with(&mut transaction, &super_admin.role_id).await
+        .map_err(|error| format!("Unable to grant permissions to {}: {}", SUPER_ADMIN_ROLE, error))?;
+
+    let admin_id = match admin {
+        Some(admin) => create_admin(db, &mut transaction, argon_config, password_policy, admin, &super_admin.role_id).await?,
+        None => None,
+    };
+
+    transaction.commit().await
+        .map_err(|error| format!("Unable to commit the bootstrap transaction: {}", error))?;
+
+    Ok(BootstrapReport { permissions_seeded, role_permissions_granted, admin_id })
+}
+
+async fn create_admin(db: &Database<'_>, conn: &mut sqlx::PgConnection, argon_config: &Config<'_>, password_policy: &PasswordPolicy, admin: &BootstrapAdmin, role_id: &i16) -> Result<Option<i32>, String> {
+    let has_admin = db.users.exists_by_role_with(conn, role_id).await
+        .map_err(|error| format!("Unable to look up existing administrators: {}", error))?;
+
+    if has_admin {
+        info!("A {} user already exists, skipping the administrator bootstrap", SUPER_ADMIN_ROLE);
+        return Ok(None);
+    }
+
+    let email_taken = db.users.exists_by_email_address_with(conn, &admin.email_address).await
+        .map_err(|error| format!("Unable to look up {}: {}", admin.email_address, error))?;
+
+    if email_taken {
+        warn!("{} belongs to a user without the {} role, skipping the administrator bootstrap", admin.email_address, SUPER_ADMIN_ROLE);
+        return Ok(None);
+    }
+
+    password_policy.check(&admin.password, &[&admin.first_name, &admin.surname, &admin.email_address])
+        .map_err(|error| format!("The password of {} is rejected: {}", admin.email_address, error.localised_message(Locale::default())))?;
+
+    let create = CreateUser {
+        first_name: admin.first_name.clone(),
+        middle_name: None,
+        surname: admin.surname.clone(),
+        email_address: admin.email_address.clone(),
+        mobile_number: None,
+        role_id: *role_id,
+        locale: None,
+    };
+
+    let user = db.users.create_with(conn, &create).await
+        .map_err(|error| format!("Unable to create {}: {}", admin.email_address, error))?;
+
+    db.users.activate_with(conn, &user.user_id).await
+        .map_err(|error| format!("Unable to activate {}: {}", admin.email_address, error))?;
+
+    let password = util::hash_password(&admin.password, argon_config).await
+        .map_err(|error| format!("Unable to hash the administrator password: {}", error))?;
+
+    db.user_credentials.create_for_email_address_with(conn, &user.user_id, &admin.email_address, &password).await
+        .map_err(|error| format!("Unable to create the credential of {}: {}", admin.email_address, error))?;
+
+    db.user_credentials.require_password_change_with(conn, &user.user_id).await
+        .map_err(|error| format!("Unable to flag the credential of {}: {}", admin.email_address, error))?;
+
+    Ok(Some(user.user_id))
+}
\ No newline at end of file
@@ -0,0 +1,172 @@
+use std::{env, time::Duration};
+
+use log::warn;
+
+use crate::dao::Database;
+
+const DEFAULT_MAX_CONNECTIONS: u32 = 5;
+const DEFAULT_PASSWORD_RESET_EXPIRES_IN: i64 = 30;
+const DEFAULT_PASSWORD_RESET_URL: &str = "http://localhost:8080/password/reset";
+const DEFAULT_PASSWORD_RESET_COOLDOWN: i32 = 60;
+const DEFAULT_EMAIL_FROM: &str = "no-reply@bulksms.local";
+const DEFAULT_CONFIRMATION_CODE_EXPIRES_IN: i64 = 60;
+const DEFAULT_CONFIRMATION_CODE_MAX_ATTEMPTS: i32 = 5;
+const DEFAULT_CONFIRMATION_CODE_RESEND_COOLDOWN: i32 = 60;
+const DEFAULT_CONFIRMATION_CODE_MAX_RESENDS: i32 = 5;
+pub const DEFAULT_SIGN_UP_ROLE: &str = "USER";
+const DEFAULT_LOCKOUT_THRESHOLD: i32 = 5;
+const DEFAULT_IP_LOCKOUT_THRESHOLD: i32 = 20;
+const DEFAULT_LOCKOUT_WINDOW: i32 = 15;
+const DEFAULT_LOCKOUT_DURATION: i32 = 15;
+const DEFAULT_SIGN_IN_DELAY_BASE: u64 = 250;
+const DEFAULT_SIGN_IN_DELAY_MAX: u64 = 4000;
+const DEFAULT_ACCOUNT_UNLOCK_URL: &str = "http://localhost:8080/account/unlock";
+// how long, in seconds, a server trusts its cached view of the revocations of a user
+const DEFAULT_REVOCATION_CACHE_TTL: u64 = 10;
+
+// shared by the server and the admin cli so both connect the same way
+pub struct DatabaseConfig {
+    pub url: String,
+    pub max_connections: u32,
+}
+
+impl DatabaseConfig {
+
+    pub fn from_env() -> Self {
+        let url = env::var("DATABASE_URL").expect("DATABASE_URL has not been set!");
+
+        let max_connections = env::var("MAX_CONNECTIONS")
+            .unwrap_or_else(|_| {
+                warn!("MAX_CONNECTIONS env variable was not provided. Will default to {}", DEFAULT_MAX_CONNECTIONS);
+                DEFAULT_MAX_CONNECTIONS.to_string()
+            })
+            .parse()
+            .unwrap_or_else(|_| {
+                warn!("MAX_CONNECTIONS was not of type u32. Will default to {}", DEFAULT_MAX_CONNECTIONS);
+                DEFAULT_MAX_CONNECTIONS
+            });
+
+        DatabaseConfig { url, max_connections }
+    }
+
+    pub async fn connect<'a>(&self) -> Database<'a> {
+        Database::new(&self.url, self.max_connections).await
+    }
+}
+
+pub struct AccountConfig {
+    pub password_reset_expires_in: i64,
+    pub password_reset_url: String,
+    pub password_reset_cooldown: i32,
+    pub email_from: String,
+    pub confirmation_code_expires_in: i64,
+    pub confirmation_code_max_attempts: i32,
+    pub confirmation_code_resend_cooldown: i32,
+    pub confirmation_code_max_resends: i32,
+    pub sign_up_enabled: bool,
+    pub sign_up_role: String,
+    pub sign_up_role_id: Option<i16>,
+    pub sign_up_allowed_domains: Vec<String>,
+    pub lockout_threshold: i32,
+    pub ip_lockout_threshold: i32,
+    pub lockout_window: i32,
+    pub lockout_duration: i32,
+    pub sign_in_delay_base: u64,
+    pub sign_in_delay_max: u64,
+    pub account_unlock_url: String,
+    pub revoke_tokens_on_status_change: bool,
+    pub revocation_cache_ttl: u64,
+}
+
+impl AccountConfig {
+
+    pub fn from_env() -> Self {
+        let password_reset_expires_in = env::var("PASSWORD_RESET_EXPIRES_IN")
+            .map(|expires_in| expires_in.parse::<i64>().expect("PASSWORD_RESET_EXPIRES_IN should be an i64."))
+            .unwrap_or(DEFAULT_PASSWORD_RESET_EXPIRES_IN);
+
+        let confirmation_code_expires_in = env::var("CONFIRMATION_CODE_EXPIRES_IN")
+            .map(|expires_in| expires_in.parse::<i64>().expect("CONFIRMATION_CODE_EXPIRES_IN should be an i64."))
+            .unwrap_or(DEFAULT_CONFIRMATION_CODE_EXPIRES_IN);
+
+        let confirmation_code_max_attempts = env::var("CONFIRMATION_CODE_MAX_ATTEMPTS")
+            .map(|attempts| attempts.parse::<i32>().expect("CONFIRMATION_CODE_MAX_ATTEMPTS should be an i32."))
+            .unwrap_or(DEFAULT_CONFIRMATION_CODE_MAX_ATTEMPTS);
+
+        let sign_up_enabled = env::var("SIGN_UP_ENABLED")
+            .map(|enabled| enabled.parse::<bool>().expect("SIGN_UP_ENABLED should be a bool."))
+            .unwrap_or(true);
+
+        let revoke_tokens_on_status_change = env::var("REVOKE_TOKENS_ON_STATUS_CHANGE")
+            .map(|enabled| enabled.parse::<bool>().expect("REVOKE_TOKENS_ON_STATUS_CHANGE should be a bool."))
+            .unwrap_or(true);
+
+        // an empty list lets any domain sign up
+        let sign_up_allowed_domains = env::var("SIGN_UP_ALLOWED_DOMAINS")
+            .map(|domains| domains.split(',')
+                .map(|domain| domain.trim().to_lowercase())
+                .filter(|domain| !domain.is_empty())
+                .collect())
+            .unwrap_or_default();
+
+        let parse_i32 = |name: &str, default: i32| env::var(name)
+            .map(|value| value.parse::<i32>().unwrap_or_else(|_| panic!("{} should be an i32.", name)))
+            .unwrap_or(default);
+
+        let parse_u64 = |name: &str, default: u64| env::var(name)
+            .map(|value| value.parse::<u64>().unwrap_or_else(|_| panic!("{} should be a u64.", name)))
+            .unwrap_or(default);
+
+        AccountConfig {
+            password_reset_expires_in,
+            password_reset_url: env::var("PASSWORD_RESET_URL").unwrap_or_else(|_| DEFAULT_PASSWORD_RESET_URL.to_string()),
+            password_reset_cooldown: parse_i32("PASSWORD_RESET_COOLDOWN", DEFAULT_PASSWORD_RESET_COOLDOWN),
+            email_from: env::var("EMAIL_FROM").unwrap_or_else(|_| DEFAULT_EMAIL_FROM.to_string()),
+            confirmation_code_expires_in,
+            confirmation_code_max_attempts,
+            confirmation_code_resend_cooldown: parse_i32("CONFIRMATION_CODE_RESEND_COOLDOWN", DEFAULT_CONFIRMATION_CODE_RESEND_COOLDOWN),
+            confirmation_code_max_resends: parse_i32("CONFIRMATION_CODE_MAX_RESENDS", DEFAULT_CONFIRMATION_CODE_MAX_RESENDS),
+            sign_up_enabled,
+            sign_up_role: env::var("SIGN_UP_ROLE").unwrap_or_else(|_| DEFAULT_SIGN_UP_ROLE.to_string()),
+            sign_up_role_id: None,
+            sign_up_allowed_domains,
+            lockout_threshold: parse_i32("SIGN_IN_LOCKOUT_THRESHOLD", DEFAULT_LOCKOUT_THRESHOLD),
+            ip_lockout_threshold: parse_i32("SIGN_IN_IP_LOCKOUT_THRESHOLD", DEFAULT_IP_LOCKOUT_THRESHOLD),
+            lockout_window: parse_i32("SIGN_IN_LOCKOUT_WINDOW", DEFAULT_LOCKOUT_WINDOW),
+            lockout_duration: parse_i32("SIGN_IN_LOCKOUT_DURATION", DEFAULT_LOCKOUT_DURATION),
+            sign_in_delay_base: parse_u64("SIGN_IN_DELAY_BASE", DEFAULT_SIGN_IN_DELAY_BASE),
+            sign_in_delay_max: parse_u64("SIGN_IN_DELAY_MAX", DEFAULT_SIGN_IN_DELAY_MAX),
+            account_unlock_url: env::var("ACCOUNT_UNLOCK_URL").unwrap_or_else(|_| DEFAULT_ACCOUNT_UNLOCK_URL.to_string()),
+            revoke_tokens_on_status_change,
+            revocation_cache_ttl: parse_u64("REVOCATION_CACHE_TTL", DEFAULT_REVOCATION_CACHE_TTL),
+        }
+    }
+
+    // looks the sign-up role up once so a missing or privileged role fails at startup rather than on sign-up
+    pub async fn resolve_sign_up_role(&mut self, db: &Database<'_>) -> Result<(), String> {
+        if !self.sign_up_enabled {
+            return Ok(());
+        }
+
+        let role = db.roles.find_by_name(&self.sign_up_role).await
+            .map_err(|error| format!("Sign-up role {} could not be found: {}", self.sign_up_role, error))?;
+
+        if role.is_system {
+            return Err(format!("Sign-up role {} is a system role and cannot be granted on sign-up!", self.sign_up_role));
+        }
+
+        self.sign_up_role_id = Some(role.role_id);
+        Ok(())
+    }
+
+    pub fn is_sign_up_domain_allowed(&self, email_address: &str) -> bool {
+        let domain = email_address.rsplit('@').next().unwrap_or_default().to_lowercase();
+        self.sign_up_allowed_domains.is_empty() || self.sign_up_allowed_domains.contains(&domain)
+    }
+
+    // doubles with every failed attempt, capped so a slow response never ties up a worker for long
+    pub fn sign_in_delay(&self, failed_attempts: i32) -> Duration {
+        let exponent = failed_attempts.saturating_sub(1).clamp(0, 16) as u32;
+        Duration::from_millis(self.sign_in_delay_base.saturating_mul(1 << exponent).min(self.sign_in_delay_max))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+
+use crate::entity::account_unlock_token::AccountUnlockToken;
+
+use super::Table;
+
+impl<'c> Table<'c, AccountUnlockToken> {
+
+    pub async fn create(&self, user_id: &i32, token_hash: &String, expires_at: &DateTime<Utc>) -> Result<AccountUnlockToken, sqlx::Error> {
+        sqlx::query_as!(AccountUnlockToken, 
+            r#"INSERT INTO "SMS_GATEWAY_USER"."ACCOUNT_UNLOCK_TOKEN" (token_hash, user_id, expires_at) VALUES ($1, $2, $3) RETURNING * "#, 
+            token_hash, user_id, expires_at)
+            .fetch_one(&*self.pool) 
+            .await
+    }
+
+    pub async fn find_by_token_hash(&self, token_hash: &String) -> Result<AccountUnlockToken, sqlx::Error> {
+        sqlx::query_as!(AccountUnlockToken, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."ACCOUNT_UNLOCK_TOKEN" WHERE token_hash = $1 "#, token_hash)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn mark_used(&self, account_unlock_token_id: &i32) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE "SMS_GATEWAY_USER"."ACCOUNT_UNLOCK_TOKEN" SET used_at = CURRENT_TIMESTAMP WHERE account_unlock_token_id = $1 AND used_at IS NULL "#, account_unlock_token_id)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+
+    pub async fn invalidate_by_user_id(&self, user_id: &i32) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE "SMS_GATEWAY_USER"."ACCOUNT_UNLOCK_TOKEN" SET used_at = CURRENT_TIMESTAMP WHERE user_id = $1 AND used_at IS NULL "#, user_id)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+}
\ No newline at end of file
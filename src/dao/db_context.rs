@@ -1,18 +1,27 @@
 use std::marker::PhantomData;
 use std::sync::Arc;
 
-use sqlx::{ FromRow, PgPool, Pool };
+use sqlx::{ FromRow, PgPool, Pool, Postgres, Transaction };
 use sqlx::postgres::{ PgRow, PgPoolOptions };
 
+use crate::entity::account_unlock_token::AccountUnlockToken;
+use crate::entity::email_outbox::EmailOutbox;
+use crate::entity::lockout::{EmailLockout, IpLockout};
+use crate::entity::password_reset_token::PasswordResetToken;
 use crate::entity::permission::Permission;
+use crate::entity::refresh_token::RefreshToken;
+use crate::entity::revoked_token::{RevokedToken, UserTokenRevocation};
 use crate::entity::role::Role;
+use crate::entity::signing_key_schedule::SigningKeySchedule;
 use crate::entity::user::User;
 use crate::entity::user_code::UserCode;
 use crate::entity::user_credential::UserCredential;
 
+type FromRowFn<'c, T> = fn(&'c PgRow) -> Result<T, sqlx::Error>;
+
 pub struct Table<'c, T> where T: FromRow<'c, PgRow> {
     pub pool: Arc<PgPool>,
-    _from_row: fn(&'c PgRow) -> Result<T, sqlx::Error>,
+    _from_row: FromRowFn<'c, T>,
     _marker: PhantomData<&'c T>,
 }
 
@@ -32,10 +41,7 @@ where
     T2: FromRow<'c, PgRow>,
 {
     pub pool: Arc<PgPool>,
-    _from_row: (
-        fn(&'c PgRow) -> Result<T1, sqlx::Error>,
-        fn(&'c PgRow) -> Result<T2, sqlx::Error>,
-    ),
+    _from_row: (FromRowFn<'c, T1>, FromRowFn<'c, T2>),
     _marker_t1: PhantomData<&'c T1>,
     _marker_t2: PhantomData<&'c T2>,
 }
@@ -63,14 +69,23 @@ pub struct Database<'c> {
     pub user_credentials: Arc<Table<'c, UserCredential>>,  
     pub user_role: Arc<JoinTable<'c, User, Role>>,
     pub user_code: Arc<Table<'c, UserCode>>,  
+    pub refresh_tokens: Arc<Table<'c, RefreshToken>>,
+    pub revoked_tokens: Arc<Table<'c, RevokedToken>>,
+    pub user_token_revocations: Arc<Table<'c, UserTokenRevocation>>,
+    pub password_reset_tokens: Arc<Table<'c, PasswordResetToken>>,
+    pub email_outbox: Arc<Table<'c, EmailOutbox>>,
+    pub email_lockouts: Arc<Table<'c, EmailLockout>>,
+    pub ip_lockouts: Arc<Table<'c, IpLockout>>,
+    pub account_unlock_tokens: Arc<Table<'c, AccountUnlockToken>>,
+    pub signing_key_schedules: Arc<Table<'c, SigningKeySchedule>>,
 }
 
 impl<'a> Database<'a> {
 
-    pub async fn new(database_url: &String, max_connections: u32) -> Database<'a> {
+    pub async fn new(database_url: &str, max_connections: u32) -> Database<'a> {
         let connection = PgPoolOptions::new()
             .max_connections(max_connections)
-            .connect(&database_url).await
+            .connect(database_url).await
             .expect("Unable to connect to the database!");
 
         let pool: Arc<sqlx::Pool<sqlx::Postgres>> = Arc::new(connection);
@@ -83,6 +98,15 @@ impl<'a> Database<'a> {
             user_credentials: Arc::from(Table::new(pool.clone())),
             user_role: Arc::from(JoinTable::new(pool.clone())),
             user_code: Arc::from(Table::new(pool.clone())),
+            refresh_tokens: Arc::from(Table::new(pool.clone())),
+            revoked_tokens: Arc::from(Table::new(pool.clone())),
+            user_token_revocations: Arc::from(Table::new(pool.clone())),
+            password_reset_tokens: Arc::from(Table::new(pool.clone())),
+            email_outbox: Arc::from(Table::new(pool.clone())),
+            email_lockouts: Arc::from(Table::new(pool.clone())),
+            ip_lockouts: Arc::from(Table::new(pool.clone())),
+            account_unlock_tokens: Arc::from(Table::new(pool.clone())),
+            signing_key_schedules: Arc::from(Table::new(pool.clone())),
         }
     }
 
@@ -95,6 +119,19 @@ impl<'a> Database<'a> {
             user_credentials: Arc::from(Table::new(Arc::new(pool.clone()))),
             user_role: Arc::from(JoinTable::new(Arc::new(pool.clone()))),
             user_code: Arc::from(Table::new(Arc::new(pool.clone()))),
+            refresh_tokens: Arc::from(Table::new(Arc::new(pool.clone()))),
+            revoked_tokens: Arc::from(Table::new(Arc::new(pool.clone()))),
+            user_token_revocations: Arc::from(Table::new(Arc::new(pool.clone()))),
+            password_reset_tokens: Arc::from(Table::new(Arc::new(pool.clone()))),
+            email_outbox: Arc::from(Table::new(Arc::new(pool.clone()))),
+            email_lockouts: Arc::from(Table::new(Arc::new(pool.clone()))),
+            ip_lockouts: Arc::from(Table::new(Arc::new(pool.clone()))),
+            account_unlock_tokens: Arc::from(Table::new(Arc::new(pool.clone()))),
+            signing_key_schedules: Arc::from(Table::new(Arc::new(pool.clone()))),
         }
     }
-}
+
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        self.users.pool.begin().await
+    }
+}
\ No newline at end of file
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+
+use crate::{email::{EmailContent, EmailDetails}, entity::email_outbox::EmailOutbox};
+
+use super::Table;
+
+impl<'c> Table<'c, EmailOutbox> {
+
+    pub async fn create(&self, details: &EmailDetails<'_>, content: &EmailContent) -> Result<EmailOutbox, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        self.create_with(&mut conn, details, content).await
+    }
+
+    pub async fn create_with(&self, conn: &mut PgConnection, details: &EmailDetails<'_>, content: &EmailContent) -> Result<EmailOutbox, sqlx::Error> {
+        sqlx::query_as!(EmailOutbox, 
+            r#"INSERT INTO "SMS_GATEWAY_USER"."EMAIL_OUTBOX" (subject, recipient, sender, html, plain_text) VALUES ($1, $2, $3, $4, $5) RETURNING * "#, 
+            details.subject, details.to, details.from, content.html, content.text)
+            .fetch_one(&mut *conn) 
+            .await
+    }
+
+    pub async fn find_by_id(&self, email_outbox_id: &i32) -> Result<EmailOutbox, sqlx::Error> {
+        sqlx::query_as!(EmailOutbox, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."EMAIL_OUTBOX" WHERE email_outbox_id = $1 "#, email_outbox_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn find_by_status(&self, status: &String) -> Result<Vec<EmailOutbox>, sqlx::Error> {
+        sqlx::query_as!(EmailOutbox, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."EMAIL_OUTBOX" WHERE status = $1 ORDER BY email_outbox_id DESC "#, status)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    // leases due messages by pushing next_attempt_at forward, so a crashed worker's batch is picked up again once the lease lapses
+    pub async fn claim_due(&self, limit: &i64, lease_until: &DateTime<Utc>) -> Result<Vec<EmailOutbox>, sqlx::Error> {
+        sqlx::query_as!(EmailOutbox, 
+            r#"UPDATE "SMS_GATEWAY_USER"."EMAIL_OUTBOX" SET next_attempt_at = $2 WHERE email_outbox_id IN (SELECT email_outbox_id FROM "SMS_GATEWAY_USER"."EMAIL_OUTBOX" WHERE status = 'PENDING' AND next_attempt_at <= CURRENT_TIMESTAMP ORDER BY next_attempt_at LIMIT $1 FOR UPDATE SKIP LOCKED) RETURNING * "#, 
+            limit, lease_until)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    // the bodies carry confirmation codes and links, so they are not kept once delivered
+    pub async fn mark_sent(&self, email_outbox_id: &i32) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE "SMS_GATEWAY_USER"."EMAIL_OUTBOX" SET status = 'SENT', attempts = attempts + 1, last_error = NULL, sent_at = CURRENT_TIMESTAMP, html = '', plain_text = '' WHERE email_outbox_id = $1 "#, email_outbox_id)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+
+    pub async fn schedule_retry(&self, email_outbox_id: &i32, last_error: &String, next_attempt_at: &DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE "SMS_GATEWAY_USER"."EMAIL_OUTBOX" SET attempts = attempts + 1, last_error = $2, next_attempt_at = $3 WHERE email_outbox_id = $1 "#, 
+            email_outbox_id, last_error, next_attempt_at)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+
+    pub async fn mark_dead(&self, email_outbox_id: &i32, last_error: &String) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE "SMS_GATEWAY_USER"."EMAIL_OUTBOX" SET status = 'DEAD', attempts = attempts + 1, last_error = $2 WHERE email_outbox_id = $1 "#, 
+            email_outbox_id, last_error)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+
+    pub async fn delete_dead_before(&self, created_before: &DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."EMAIL_OUTBOX" WHERE status = 'DEAD' AND created_at < $1 "#, created_before)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+
+    pub async fn retry(&self, email_outbox_id: &i32) -> Result<EmailOutbox, sqlx::Error> {
+        sqlx::query_as!(EmailOutbox, 
+            r#"UPDATE "SMS_GATEWAY_USER"."EMAIL_OUTBOX" SET status = 'PENDING', attempts = 0, next_attempt_at = CURRENT_TIMESTAMP WHERE email_outbox_id = $1 AND status = 'DEAD' RETURNING * "#, email_outbox_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+}
\ No newline at end of file
@@ -0,0 +1,67 @@
+use crate::entity::lockout::{EmailLockout, IpLockout};
+
+use super::Table;
+
+// failures older than the window start a new count; reaching the threshold locks for duration minutes
+// addresses are compared in lower case, whether or not a user has registered them
+impl<'c> Table<'c, EmailLockout> {
+
+    pub async fn find_by_email_address(&self, email_address: &str) -> Result<Option<EmailLockout>, sqlx::Error> {
+        sqlx::query_as!(EmailLockout, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."EMAIL_LOCKOUT" WHERE email_address = lower($1) "#, email_address)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn record_failure(&self, email_address: &str, window: &i32, threshold: &i32, duration: &i32) -> Result<EmailLockout, sqlx::Error> {
+        sqlx::query_as!(EmailLockout, 
+            r#"INSERT INTO "SMS_GATEWAY_USER"."EMAIL_LOCKOUT" AS l (email_address, failed_attempts, last_failed_at, locked_until)
+            VALUES (lower($1), 1, CURRENT_TIMESTAMP, CASE WHEN 1 >= $3 THEN CURRENT_TIMESTAMP + make_interval(mins => $4) END)
+            ON CONFLICT (email_address) DO UPDATE SET
+                failed_attempts = CASE WHEN l.last_failed_at < CURRENT_TIMESTAMP - make_interval(mins => $2) THEN 1 ELSE l.failed_attempts + 1 END,
+                last_failed_at = CURRENT_TIMESTAMP,
+                locked_until = CASE WHEN (CASE WHEN l.last_failed_at < CURRENT_TIMESTAMP - make_interval(mins => $2) THEN 1 ELSE l.failed_attempts + 1 END) >= $3 THEN CURRENT_TIMESTAMP + make_interval(mins => $4) ELSE l.locked_until END
+            RETURNING * "#,
+            email_address, window, threshold, duration)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn clear(&self, email_address: &str) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."EMAIL_LOCKOUT" WHERE email_address = lower($1) "#, email_address)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+}
+
+impl<'c> Table<'c, IpLockout> {
+
+    pub async fn find_by_ip_address(&self, ip_address: &String) -> Result<Option<IpLockout>, sqlx::Error> {
+        sqlx::query_as!(IpLockout, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."IP_LOCKOUT" WHERE ip_address = $1 "#, ip_address)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn record_failure(&self, ip_address: &String, window: &i32, threshold: &i32, duration: &i32) -> Result<IpLockout, sqlx::Error> {
+        sqlx::query_as!(IpLockout, 
+            r#"INSERT INTO "SMS_GATEWAY_USER"."IP_LOCKOUT" AS l (ip_address, failed_attempts, last_failed_at, locked_until)
+            VALUES ($1, 1, CURRENT_TIMESTAMP, CASE WHEN 1 >= $3 THEN CURRENT_TIMESTAMP + make_interval(mins => $4) END)
+            ON CONFLICT (ip_address) DO UPDATE SET
+                failed_attempts = CASE WHEN l.last_failed_at < CURRENT_TIMESTAMP - make_interval(mins => $2) THEN 1 ELSE l.failed_attempts + 1 END,
+                last_failed_at = CURRENT_TIMESTAMP,
+                locked_until = CASE WHEN (CASE WHEN l.last_failed_at < CURRENT_TIMESTAMP - make_interval(mins => $2) THEN 1 ELSE l.failed_attempts + 1 END) >= $3 THEN CURRENT_TIMESTAMP + make_interval(mins => $4) ELSE l.locked_until END
+            RETURNING * "#,
+            ip_address, window, threshold, duration)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn clear(&self, ip_address: &String) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."IP_LOCKOUT" WHERE ip_address = $1 "#, ip_address)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+}
\ No newline at end of file
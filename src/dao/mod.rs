@@ -5,6 +5,13 @@ pub mod role_permission_dao;
 pub mod user_dao;
 pub mod user_credential_dao;
 pub mod user_code_dao;
+pub mod refresh_token_dao;
+pub mod revoked_token_dao;
+pub mod password_reset_token_dao;
+pub mod email_outbox_dao;
+pub mod lockout_dao;
+pub mod account_unlock_token_dao;
+pub mod signing_key_schedule_dao;
 
 pub type Database<'c> = db_context::Database<'c>;
 pub type Table<'c, T> = db_context::Table<'c, T>;
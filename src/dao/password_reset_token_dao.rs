@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+
+use crate::entity::password_reset_token::PasswordResetToken;
+
+use super::Table;
+
+impl<'c> Table<'c, PasswordResetToken> {
+
+    // returns RowNotFound while the address is within the cooldown of its last request
+    pub async fn create(&self, user_id: Option<&i32>, email_address: &String, token_hash: &String, expires_at: &DateTime<Utc>, cooldown: &i32) -> Result<PasswordResetToken, sqlx::Error> {
+        sqlx::query_as!(PasswordResetToken, 
+            r#"INSERT INTO "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" (token_hash, user_id, email_address, expires_at) 
+            SELECT $1, $2, $3::varchar, $4 
+            WHERE NOT EXISTS (SELECT 1 FROM "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" WHERE email_address = $3::varchar AND created_at > CURRENT_TIMESTAMP - make_interval(secs => $5)) 
+            RETURNING * "#, 
+            token_hash, user_id, email_address, expires_at, *cooldown as f64)
+            .fetch_one(&*self.pool) 
+            .await
+    }
+
+    pub async fn find_latest_by_email_address(&self, email_address: &String) -> Result<PasswordResetToken, sqlx::Error> {
+        sqlx::query_as!(PasswordResetToken, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" WHERE email_address = $1 ORDER BY created_at DESC LIMIT 1 "#, email_address)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn find_by_token_hash(&self, token_hash: &String) -> Result<PasswordResetToken, sqlx::Error> {
+        sqlx::query_as!(PasswordResetToken, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" WHERE token_hash = $1 "#, token_hash)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn mark_used(&self, password_reset_token_id: &i32) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" SET used_at = CURRENT_TIMESTAMP WHERE password_reset_token_id = $1 AND used_at IS NULL "#, password_reset_token_id)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+
+    // only the most recently issued link should work, whichever address the earlier ones went to
+    pub async fn invalidate_others(&self, token: &PasswordResetToken) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" SET used_at = CURRENT_TIMESTAMP 
+            WHERE (user_id = $1 OR email_address = $2) AND password_reset_token_id <> $3 AND used_at IS NULL "#, 
+            token.user_id, token.email_address, token.password_reset_token_id)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+}
\ No newline at end of file
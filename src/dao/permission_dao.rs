@@ -1,6 +1,6 @@
-use sqlx::postgres::PgQueryResult;
+use sqlx::PgConnection;
 
-use crate::{entity::permission::{Permission, CreatePermission}, model::pagination::PaginatedResult};
+use crate::{entity::permission::{Permission, CreatePermission, UpdatePermission, PatchPermission}, model::{deletion::{DeletePermissionQuery, Deletion, PermissionDependants}, pagination::PaginatedResult}};
 
 use super::{Table, CountResult};
 
@@ -13,6 +13,20 @@ impl<'c> Table<'c, Permission> {
             .await
     }
 
+    pub async fn find_by_name(&self, name: &str) -> Result<Permission, sqlx::Error> {
+        sqlx::query_as!(Permission, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."PERMISSION" WHERE name = $1 "#, name)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn find_by_ids(&self, permission_ids: &[i16]) -> Result<Vec<Permission>, sqlx::Error> {
+        sqlx::query_as!(Permission, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."PERMISSION" WHERE permission_id = ANY($1) ORDER BY permission_id "#, permission_ids)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
     pub async fn find_all(&self) -> Result<Vec<Permission>, sqlx::Error> {
         sqlx::query_as!(Permission, r#"SELECT * FROM "SMS_GATEWAY_USER"."PERMISSION" "#)
             .fetch_all(&*self.pool)
@@ -54,10 +68,76 @@ impl<'c> Table<'c, Permission> {
             .await
     }
 
-    pub async fn delete(&self, permission_id: &i16) -> Result<PgQueryResult, sqlx::Error> {
-        sqlx::query_as!(PgQueryResult, 
-            r#"DELETE FROM "SMS_GATEWAY_USER"."PERMISSION" WHERE permission_id = $1 "#, permission_id)
-            .execute(&*self.pool)
+    // inserts missing names as system permissions and marks existing ones as system, returning how many rows changed
+    pub async fn seed_system_with(&self, conn: &mut PgConnection, names: &[&str]) -> Result<u64, sqlx::Error> {
+        let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+
+        sqlx::query!(
+            r#"INSERT INTO "SMS_GATEWAY_USER"."PERMISSION" (name, is_system) SELECT n, true FROM UNNEST($1::varchar[]) n ON CONFLICT (name) DO UPDATE SET is_system = true WHERE NOT "PERMISSION".is_system "#, 
+            names.as_slice())
+            .execute(&mut *conn)
+            .await.map(|x|x.rows_affected())
+    }
+
+    // system entries keep their name, only the description can change
+    pub async fn update(&self, permission_id: &i16, request: &UpdatePermission) -> Result<Permission, sqlx::Error> {
+        sqlx::query_as!(Permission, 
+            r#"UPDATE "SMS_GATEWAY_USER"."PERMISSION" SET name = $1, description = $2 WHERE permission_id = $3 AND (NOT is_system OR name = $1) RETURNING * "#, 
+            request.name, request.description, permission_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    // only the fields present in the request are changed
+    pub async fn patch(&self, permission_id: &i16, request: &PatchPermission) -> Result<Permission, sqlx::Error> {
+        sqlx::query_as!(Permission, 
+            r#"UPDATE "SMS_GATEWAY_USER"."PERMISSION" SET name = COALESCE($1, name), description = COALESCE($2, description) WHERE permission_id = $3 AND (NOT is_system OR COALESCE($1, name) = name) RETURNING * "#, 
+            request.name, request.description, permission_id)
+            .fetch_one(&*self.pool)
             .await
     }
+
+    pub async fn delete(&self, permission_id: &i16, options: &DeletePermissionQuery) -> Result<Deletion<PermissionDependants>, sqlx::Error> {
+        let mut transaction = self.pool.begin().await?;
+        let result = self.delete_with(&mut transaction, permission_id, options).await?;
+        transaction.commit().await?;
+
+        Ok(result)
+    }
+
+    pub async fn delete_with(&self, conn: &mut PgConnection, permission_id: &i16, options: &DeletePermissionQuery) -> Result<Deletion<PermissionDependants>, sqlx::Error> {
+        let permission = sqlx::query_as!(Permission, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."PERMISSION" WHERE permission_id = $1 FOR UPDATE "#, permission_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+        match permission {
+            None => return Ok(Deletion::NotFound),
+            Some(permission) if permission.is_system => return Ok(Deletion::Protected),
+            Some(_) => {}
+        }
+
+        if !options.force {
+            let roles = sqlx::query_scalar!(
+                r#"SELECT role_id FROM "SMS_GATEWAY_USER"."ROLE_PERMISSION" WHERE permission_id = $1 ORDER BY role_id "#, permission_id)
+                .fetch_all(&mut *conn)
+                .await?;
+
+            if !roles.is_empty() {
+                return Ok(Deletion::InUse(PermissionDependants { roles }));
+            }
+        }
+
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."ROLE_PERMISSION" WHERE permission_id = $1 "#, permission_id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."PERMISSION" WHERE permission_id = $1 "#, permission_id)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(Deletion::Deleted)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+
+use crate::entity::refresh_token::RefreshToken;
+
+use super::Table;
+
+impl<'c> Table<'c, RefreshToken> {
+
+    pub async fn create(&self, user_id: &i32, token_hash: &String, family_id: &String, audience: &String, expires_at: &DateTime<Utc>) -> Result<RefreshToken, sqlx::Error> {
+        sqlx::query_as!(RefreshToken, 
+            r#"INSERT INTO "SMS_GATEWAY_USER"."REFRESH_TOKEN" (token_hash, family_id, user_id, expires_at, audience) VALUES ($1, $2, $3, $4, $5) RETURNING * "#, 
+            token_hash, family_id, user_id, expires_at, audience)
+            .fetch_one(&*self.pool) 
+            .await
+    }
+
+    pub async fn find_by_token_hash(&self, token_hash: &String) -> Result<RefreshToken, sqlx::Error> {
+        sqlx::query_as!(RefreshToken, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."REFRESH_TOKEN" WHERE token_hash = $1 "#, token_hash)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn revoke(&self, refresh_token_id: &i32) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE "SMS_GATEWAY_USER"."REFRESH_TOKEN" SET revoked = TRUE WHERE refresh_token_id = $1 AND revoked = FALSE "#, refresh_token_id)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+
+    pub async fn revoke_family(&self, family_id: &String) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE "SMS_GATEWAY_USER"."REFRESH_TOKEN" SET revoked = TRUE WHERE family_id = $1 AND revoked = FALSE "#, family_id)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+
+    pub async fn revoke_by_user_id(&self, user_id: &i32) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE "SMS_GATEWAY_USER"."REFRESH_TOKEN" SET revoked = TRUE WHERE user_id = $1 AND revoked = FALSE "#, user_id)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+
+use crate::entity::revoked_token::{RevokedToken, UserRevocations, UserTokenRevocation};
+
+use super::Table;
+
+impl<'c> Table<'c, RevokedToken> {
+
+    pub async fn create(&self, jti: &String, user_id: &i32, expires_at: &DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO "SMS_GATEWAY_USER"."REVOKED_TOKEN" (jti, user_id, expires_at) VALUES ($1, $2, $3) ON CONFLICT (jti) DO NOTHING "#, 
+            jti, user_id, expires_at)
+            .execute(&*self.pool) 
+            .await.map(|x| x.rows_affected())
+    }
+
+    pub async fn delete_expired(&self) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."REVOKED_TOKEN" WHERE expires_at < CURRENT_TIMESTAMP "#)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+}
+
+impl<'c> Table<'c, UserTokenRevocation> {
+
+    pub async fn upsert(&self, user_id: &i32) -> Result<UserTokenRevocation, sqlx::Error> {
+        sqlx::query_as!(UserTokenRevocation, 
+            r#"INSERT INTO "SMS_GATEWAY_USER"."USER_TOKEN_REVOCATION" (user_id) VALUES ($1) ON CONFLICT (user_id) DO UPDATE SET revoked_at = CURRENT_TIMESTAMP RETURNING * "#, 
+            user_id)
+            .fetch_one(&*self.pool) 
+            .await
+    }
+
+    // always returns a row, users without any revocation get empty values
+    pub async fn find_by_user_id(&self, user_id: &i32) -> Result<UserRevocations, sqlx::Error> {
+        sqlx::query_as!(UserRevocations, 
+            r#"SELECT 
+                (SELECT revoked_at FROM "SMS_GATEWAY_USER"."USER_TOKEN_REVOCATION" WHERE user_id = $1) AS revoked_at, 
+                (SELECT deactivated_at FROM "SMS_GATEWAY_USER"."USER_DEACTIVATION" WHERE user_id = $1) AS deactivated_at, 
+                ARRAY(SELECT jti FROM "SMS_GATEWAY_USER"."REVOKED_TOKEN" WHERE user_id = $1 AND expires_at > CURRENT_TIMESTAMP) AS "revoked_jtis!" "#, 
+            user_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+}
\ No newline at end of file
@@ -1,6 +1,6 @@
-use sqlx::postgres::PgQueryResult;
+use sqlx::PgConnection;
 
-use crate::{entity::role::{Role, CreateRole}, model::pagination::PaginatedResult};
+use crate::{entity::role::{Role, CreateRole, UpdateRole, PatchRole}, model::{deletion::{DeleteRoleQuery, Deletion, RoleDependants}, pagination::PaginatedResult}};
 
 use super::{Table, CountResult};
 
@@ -13,6 +13,13 @@ impl<'c> Table<'c, Role> {
             .await
     }
 
+    pub async fn find_by_name(&self, name: &str) -> Result<Role, sqlx::Error> {
+        sqlx::query_as!(Role, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."ROLE" WHERE name = $1 "#, name)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
     pub async fn find_all(&self) -> Result<Vec<Role>, sqlx::Error> {
         sqlx::query_as!(Role, r#"SELECT * FROM "SMS_GATEWAY_USER"."ROLE" "#)
             .fetch_all(&*self.pool)
@@ -54,10 +61,106 @@ impl<'c> Table<'c, Role> {
             .await
     }
 
-    pub async fn delete(&self, role_id: &i16) -> Result<PgQueryResult, sqlx::Error> {
-        sqlx::query_as!(PgQueryResult, 
-            r#"DELETE FROM "SMS_GATEWAY_USER"."ROLE" WHERE role_id = $1 "#, role_id)
-            .execute(&*self.pool)
+    // creates the role when missing; an existing role keeps its flag unless it is being promoted to a system role
+    pub async fn seed_with(&self, conn: &mut PgConnection, name: &str, is_system: bool) -> Result<Role, sqlx::Error> {
+        sqlx::query_as!(Role, 
+            r#"INSERT INTO "SMS_GATEWAY_USER"."ROLE" (name, is_system) VALUES ($1, $2) ON CONFLICT (name) DO UPDATE SET is_system = "ROLE".is_system OR EXCLUDED.is_system RETURNING * "#, 
+            name, is_system)
+            .fetch_one(&mut *conn)
+            .await
+    }
+
+    // system entries keep their name, only the description can change
+    pub async fn update(&self, role_id: &i16, request: &UpdateRole) -> Result<Role, sqlx::Error> {
+        sqlx::query_as!(Role, 
+            r#"UPDATE "SMS_GATEWAY_USER"."ROLE" SET name = $1, description = $2 WHERE role_id = $3 AND (NOT is_system OR name = $1) RETURNING * "#, 
+            request.name, request.description, role_id)
+            .fetch_one(&*self.pool)
             .await
     }
+
+    // only the fields present in the request are changed
+    pub async fn patch(&self, role_id: &i16, request: &PatchRole) -> Result<Role, sqlx::Error> {
+        sqlx::query_as!(Role, 
+            r#"UPDATE "SMS_GATEWAY_USER"."ROLE" SET name = COALESCE($1, name), description = COALESCE($2, description) WHERE role_id = $3 AND (NOT is_system OR COALESCE($1, name) = name) RETURNING * "#, 
+            request.name, request.description, role_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn delete(&self, role_id: &i16, options: &DeleteRoleQuery) -> Result<Deletion<RoleDependants>, sqlx::Error> {
+        let mut transaction = self.pool.begin().await?;
+        let result = self.delete_with(&mut transaction, role_id, options).await?;
+
+        // a refused deletion also undoes any reassignment made on the way
+        match result {
+            Deletion::Deleted => transaction.commit().await?,
+            _ => transaction.rollback().await?,
+        }
+
+        Ok(result)
+    }
+
+    pub async fn delete_with(&self, conn: &mut PgConnection, role_id: &i16, options: &DeleteRoleQuery) -> Result<Deletion<RoleDependants>, sqlx::Error> {
+        // the row lock blocks users and permissions from being linked to the role until we are done
+        let role = sqlx::query_as!(Role, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."ROLE" WHERE role_id = $1 FOR UPDATE "#, role_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+        match role {
+            None => return Ok(Deletion::NotFound),
+            Some(role) if role.is_system => return Ok(Deletion::Protected),
+            Some(_) => {}
+        }
+
+        if let Some(reassign_to) = options.reassign_to {
+            let reassigned = sqlx::query_scalar!(
+                r#"UPDATE "SMS_GATEWAY_USER"."USER" SET role_id = $1 WHERE role_id = $2 RETURNING user_id "#, reassign_to, role_id)
+                .fetch_all(&mut *conn)
+                .await?;
+
+            // tokens carry the permissions of the previous role, so they are revoked along with the move
+            sqlx::query!(
+                r#"INSERT INTO "SMS_GATEWAY_USER"."USER_TOKEN_REVOCATION" (user_id) SELECT u FROM UNNEST($1::int4[]) u ON CONFLICT (user_id) DO UPDATE SET revoked_at = CURRENT_TIMESTAMP "#, 
+                reassigned.as_slice())
+                .execute(&mut *conn)
+                .await?;
+
+            sqlx::query!(
+                r#"UPDATE "SMS_GATEWAY_USER"."REFRESH_TOKEN" SET revoked = TRUE WHERE user_id = ANY($1) AND revoked = FALSE "#, 
+                reassigned.as_slice())
+                .execute(&mut *conn)
+                .await?;
+        }
+
+        let users = sqlx::query_scalar!(
+            r#"SELECT user_id FROM "SMS_GATEWAY_USER"."USER" WHERE role_id = $1 ORDER BY user_id "#, role_id)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        let permissions = match options.force {
+            true => vec![],
+            false => sqlx::query_scalar!(
+                r#"SELECT permission_id FROM "SMS_GATEWAY_USER"."ROLE_PERMISSION" WHERE role_id = $1 ORDER BY permission_id "#, role_id)
+                .fetch_all(&mut *conn)
+                .await?,
+        };
+
+        if !users.is_empty() || !permissions.is_empty() {
+            return Ok(Deletion::InUse(RoleDependants { users, permissions }));
+        }
+
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."ROLE_PERMISSION" WHERE role_id = $1 "#, role_id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."ROLE" WHERE role_id = $1 "#, role_id)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(Deletion::Deleted)
+    }
 }
\ No newline at end of file
@@ -1,10 +1,10 @@
-use sqlx::Execute;
+use sqlx::{Execute, PgConnection};
 
-use crate::entity::{role::Role, permission::Permission};
+use crate::{entity::{role::Role, permission::Permission}, model::role_permission::RolePermissionChanges};
 
 use super::JoinTable;
 
-static DEFAULT_INSERT: &'static str = r#"INSERT INTO "SMS_GATEWAY_USER"."ROLE_PERMISSION" (role_id, permission_id) VALUES ($1, $2)"#;
+static DEFAULT_INSERT: &str = r#"INSERT INTO "SMS_GATEWAY_USER"."ROLE_PERMISSION" (role_id, permission_id) VALUES ($1, $2)"#;
 
 fn build_insert_statement(rows: usize) -> String {
     let mut insert = String::from(DEFAULT_INSERT);
@@ -19,7 +19,7 @@ fn build_insert_statement(rows: usize) -> String {
                 insert.push_str(&params.to_string());
                 insert.push_str(", $");
                 insert.push_str(&(params + 1).to_string());
-                insert.push_str(")");
+                insert.push(')');
                 i += 1;
                 params += 2;
             }
@@ -31,8 +31,13 @@ fn build_insert_statement(rows: usize) -> String {
 impl<'c> JoinTable<'c, Role, Permission> {
 
     pub async fn create_role_permissions(&self, role_id: &i16, permissions: &Vec<Permission>) -> Result<u64, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        self.create_role_permissions_with(&mut conn, role_id, permissions).await
+    }
+
+    pub async fn create_role_permissions_with(&self, conn: &mut PgConnection, role_id: &i16, permissions: &Vec<Permission>) -> Result<u64, sqlx::Error> {
 
-        if 0 == permissions.len() {
+        if permissions.is_empty() {
             Ok(0)
         } else {
             let insert_statement = build_insert_statement(permissions.len());
@@ -46,7 +51,7 @@ impl<'c> JoinTable<'c, Role, Permission> {
 
             dbg!("{:?}", &query.sql());
 
-            query.execute(&*self.pool).await
+            query.execute(&mut *conn).await
                 .map(|x| x.rows_affected())
         }
     }
@@ -64,20 +69,81 @@ impl<'c> JoinTable<'c, Role, Permission> {
             .await
     }
 
-    pub async fn update_role_permissions(&self, role_id: &i16, permissions: &Vec<Permission>) -> Result<u64, sqlx::Error> {
-        if permissions.len() == 0 {
-            self.delete_role_permissions(role_id).await
-        } else {
-            let deleted = self.delete_role_permissions(role_id).await?;
-            let added = self.create_role_permissions(role_id, permissions).await?;
-            Ok(added + deleted)
-        }
+    // system permissions of a system role, which cannot be removed from it
+    pub async fn find_core_permissions(&self, role_id: &i16) -> Result<Vec<i16>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT rp.permission_id FROM "SMS_GATEWAY_USER"."ROLE_PERMISSION" rp JOIN "SMS_GATEWAY_USER"."ROLE" r ON r.role_id = rp.role_id JOIN "SMS_GATEWAY_USER"."PERMISSION" p ON p.permission_id = rp.permission_id WHERE rp.role_id = $1 AND r.is_system AND p.is_system ORDER BY rp.permission_id "#, 
+            role_id)
+            .fetch_all(&*self.pool)
+            .await
     }
 
-    pub async fn delete_role_permissions(&self, role_id: &i16) -> Result<u64, sqlx::Error> {
-        sqlx::query_as!(PgQueryResult, 
-            r#"DELETE FROM "SMS_GATEWAY_USER"."ROLE_PERMISSION" WHERE role_id = $1 "#, role_id)
+    pub async fn update_role_permissions(&self, role_id: &i16, permission_ids: &Vec<i16>) -> Result<RolePermissionChanges, sqlx::Error> {
+        let mut transaction = self.pool.begin().await?;
+        let result = self.update_role_permissions_with(&mut transaction, role_id, permission_ids).await?;
+        transaction.commit().await?;
+
+        Ok(result)
+    }
+
+    // applies only the difference so the role is never left without its permissions mid-update,
+    // and never strips a system role of its system permissions
+    pub async fn update_role_permissions_with(&self, conn: &mut PgConnection, role_id: &i16, permission_ids: &Vec<i16>) -> Result<RolePermissionChanges, sqlx::Error> {
+        // locking the role serialises concurrent updates of the same role
+        sqlx::query_as!(Role, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."ROLE" WHERE role_id = $1 FOR UPDATE "#, role_id)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        let mut removed = sqlx::query_scalar!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."ROLE_PERMISSION" rp WHERE rp.role_id = $1 AND rp.permission_id <> ALL($2) AND NOT EXISTS (SELECT 1 FROM "SMS_GATEWAY_USER"."ROLE" r JOIN "SMS_GATEWAY_USER"."PERMISSION" p ON p.permission_id = rp.permission_id WHERE r.role_id = rp.role_id AND r.is_system AND p.is_system) RETURNING rp.permission_id "#, 
+            role_id, permission_ids.as_slice())
+            .fetch_all(&mut *conn)
+            .await?;
+
+        let mut added = sqlx::query_scalar!(
+            r#"INSERT INTO "SMS_GATEWAY_USER"."ROLE_PERMISSION" (role_id, permission_id) SELECT DISTINCT $1::int2, p FROM UNNEST($2::int2[]) p ON CONFLICT DO NOTHING RETURNING permission_id "#, 
+            role_id, permission_ids.as_slice())
+            .fetch_all(&mut *conn)
+            .await?;
+
+        added.sort();
+        removed.sort();
+
+        Ok(RolePermissionChanges { added, removed })
+    }
+
+    // links every system permission to the role, leaving existing links alone
+    pub async fn grant_system_permissions_with(&self, conn: &mut PgConnection, role_id: &i16) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO "SMS_GATEWAY_USER"."ROLE_PERMISSION" (role_id, permission_id) SELECT $1, p.permission_id FROM "SMS_GATEWAY_USER"."PERMISSION" p WHERE p.is_system ON CONFLICT DO NOTHING "#, role_id)
+            .execute(&mut *conn)
+            .await.map(|x|x.rows_affected())
+    }
+
+    pub async fn add_role_permission(&self, role_id: &i16, permission_id: &i16) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO "SMS_GATEWAY_USER"."ROLE_PERMISSION" (role_id, permission_id) VALUES ($1, $2) ON CONFLICT DO NOTHING "#, role_id, permission_id)
+            .execute(&*self.pool)
+            .await.map(|x|x.rows_affected())
+    }
+
+    pub async fn remove_role_permission(&self, role_id: &i16, permission_id: &i16) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."ROLE_PERMISSION" rp WHERE rp.role_id = $1 AND rp.permission_id = $2 AND NOT EXISTS (SELECT 1 FROM "SMS_GATEWAY_USER"."ROLE" r JOIN "SMS_GATEWAY_USER"."PERMISSION" p ON p.permission_id = rp.permission_id WHERE r.role_id = rp.role_id AND r.is_system AND p.is_system) "#, role_id, permission_id)
             .execute(&*self.pool)
             .await.map(|x|x.rows_affected())
     }
+
+    pub async fn delete_role_permissions(&self, role_id: &i16) -> Result<u64, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        self.delete_role_permissions_with(&mut conn, role_id).await
+    }
+
+    pub async fn delete_role_permissions_with(&self, conn: &mut PgConnection, role_id: &i16) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."ROLE_PERMISSION" rp WHERE rp.role_id = $1 AND NOT EXISTS (SELECT 1 FROM "SMS_GATEWAY_USER"."ROLE" r JOIN "SMS_GATEWAY_USER"."PERMISSION" p ON p.permission_id = rp.permission_id WHERE r.role_id = rp.role_id AND r.is_system AND p.is_system) "#, role_id)
+            .execute(&mut *conn)
+            .await.map(|x|x.rows_affected())
+    }
 }
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+
+use crate::entity::signing_key_schedule::SigningKeySchedule;
+
+use super::Table;
+
+impl<'c> Table<'c, SigningKeySchedule> {
+
+    pub async fn find_all(&self) -> Result<Vec<SigningKeySchedule>, sqlx::Error> {
+        sqlx::query_as!(SigningKeySchedule, 
+            r#"SELECT * FROM "SMS_GATEWAY_USER"."SIGNING_KEY_SCHEDULE" ORDER BY kid "#)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    // the previous key keeps verifying until retire_previous_at, so both changes are written together
+    pub async fn promote(&self, kid: &str, activate_at: &DateTime<Utc>, previous_kid: &str, retire_previous_at: &DateTime<Utc>) -> Result<(), sqlx::Error> {
+        let mut transaction = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"INSERT INTO "SMS_GATEWAY_USER"."SIGNING_KEY_SCHEDULE" (kid, activate_at) VALUES ($1, $2) 
+            ON CONFLICT (kid) DO UPDATE SET activate_at = EXCLUDED.activate_at, retire_at = NULL, updated_at = CURRENT_TIMESTAMP "#, 
+            kid, activate_at)
+            .execute(&mut *transaction)
+            .await?;
+
+        if previous_kid != kid {
+            sqlx::query!(
+                r#"INSERT INTO "SMS_GATEWAY_USER"."SIGNING_KEY_SCHEDULE" (kid, retire_at) VALUES ($1, $2) 
+                ON CONFLICT (kid) DO UPDATE SET retire_at = EXCLUDED.retire_at, updated_at = CURRENT_TIMESTAMP "#, 
+                previous_kid, retire_previous_at)
+                .execute(&mut *transaction)
+                .await?;
+        }
+
+        transaction.commit().await
+    }
+
+    pub async fn retire(&self, kid: &str, retire_at: &DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO "SMS_GATEWAY_USER"."SIGNING_KEY_SCHEDULE" (kid, retire_at) VALUES ($1, $2) 
+            ON CONFLICT (kid) DO UPDATE SET retire_at = EXCLUDED.retire_at, updated_at = CURRENT_TIMESTAMP "#, 
+            kid, retire_at)
+            .execute(&*self.pool)
+            .await.map(|x| x.rows_affected())
+    }
+}
@@ -1,4 +1,4 @@
-use sqlx::postgres::PgQueryResult;
+use sqlx::{postgres::PgQueryResult, PgConnection};
 
 use crate::entity::{user::User, user_code::UserCode};
 
@@ -7,24 +7,31 @@ use super::Table;
 impl<'c> Table<'c, UserCode> {
 
     pub async fn create(&self, user_id: &i32, code: &i32) -> Result<UserCode, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        self.create_with(&mut conn, user_id, code).await
+    }
+
+    pub async fn create_with(&self, conn: &mut PgConnection, user_id: &i32, code: &i32) -> Result<UserCode, sqlx::Error> {
 
         sqlx::query_as!(User, 
             r#"SELECT * FROM "SMS_GATEWAY_USER"."USER" WHERE user_id = $1 "#, user_id)
-            .fetch_one(&*self.pool)
+            .fetch_one(&mut *conn)
             .await?;
 
         sqlx::query_as!(UserCode, 
             r#"INSERT INTO "SMS_GATEWAY_USER"."USER_CODE" (code, user_id) VALUES ($1, $2) RETURNING * "#, 
             code, user_id)
-            .fetch_one(&*self.pool) 
+            .fetch_one(&mut *conn) 
             .await
     }
 
-    pub async fn update(&self, user_id: &i32, code: &i32) -> Result<UserCode, sqlx::Error> {
-
+    // a fresh code gets a fresh round of attempts, the cooldown and max_resends bound the guesses overall;
+    // returns RowNotFound when the cooldown has not passed or max_resends is used up
+    pub async fn resend(&self, user_id: &i32, code: &i32, cooldown: &i32, max_resends: &i32) -> Result<UserCode, sqlx::Error> {
         sqlx::query_as!(UserCode, 
-            r#"UPDATE "SMS_GATEWAY_USER"."USER_CODE" SET code = $1 WHERE  user_id = $2 RETURNING * "#, 
-            code, user_id)
+            r#"UPDATE "SMS_GATEWAY_USER"."USER_CODE" SET code = $1, attempts = 0, created_at = CURRENT_TIMESTAMP, last_sent_at = CURRENT_TIMESTAMP, resend_count = resend_count + 1 
+            WHERE user_id = $2 AND last_sent_at <= CURRENT_TIMESTAMP - make_interval(secs => $3) AND resend_count < $4 RETURNING * "#, 
+            code, user_id, *cooldown as f64, max_resends)
             .fetch_one(&*self.pool) 
             .await
     }
@@ -36,6 +43,13 @@ impl<'c> Table<'c, UserCode> {
             .await
     }
 
+    pub async fn record_attempt(&self, user_id: &i32) -> Result<UserCode, sqlx::Error> {
+        sqlx::query_as!(UserCode, 
+            r#"UPDATE "SMS_GATEWAY_USER"."USER_CODE" SET attempts = attempts + 1 WHERE user_id = $1 RETURNING * "#, user_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
     pub async fn find_by_user_id_and_code(&self, user_id: &i32, code: &i32) -> Result<UserCode, sqlx::Error> {
         sqlx::query_as!(UserCode, 
             r#"SELECT * FROM "SMS_GATEWAY_USER"."USER_CODE" WHERE user_id = $1 and code = $2"#, user_id, code)
@@ -44,7 +58,7 @@ impl<'c> Table<'c, UserCode> {
     }
 
     pub async fn delete(&self, user_id: &i32, code: &i32) -> Result<PgQueryResult, sqlx::Error> {
-        sqlx::query_as!(PgQueryResult, 
+        sqlx::query!(
             r#"DELETE FROM "SMS_GATEWAY_USER"."USER_CODE" WHERE user_id = $1 and code = $2"#, user_id, code)
             .execute(&*self.pool)
             .await
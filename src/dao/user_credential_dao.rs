@@ -1,29 +1,104 @@
+use sqlx::PgConnection;
+
 use crate::{entity::user_credential::UserCredential, model::user_credentials::{CreateUserCredential, UpdateUserCredential}};
 
 use super::Table;
 
+const MAX_USERNAME_ROUNDS: usize = 3;
+
 impl<'c> Table<'c, UserCredential> {
 
     pub async fn create(&self, user_id: &i32, request: &CreateUserCredential) -> Result<UserCredential, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        self.create_with(&mut conn, user_id, request).await
+    }
+
+    pub async fn create_with(&self, conn: &mut PgConnection, user_id: &i32, request: &CreateUserCredential) -> Result<UserCredential, sqlx::Error> {
         let CreateUserCredential { username, password } = request;
 
         sqlx::query_as!(UserCredential, 
             r#"INSERT INTO "SMS_GATEWAY_USER"."USER_CREDENTIAL" (username, password, user_id) VALUES ($1, $2, $3) RETURNING * "#, 
             username, password, user_id)
-            .fetch_one(&*self.pool) 
+            .fetch_one(&mut *conn) 
             .await
     }
 
+    pub async fn create_for_email_address(&self, user_id: &i32, email_address: &str, password: &String) -> Result<UserCredential, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        self.create_for_email_address_with(&mut conn, user_id, email_address, password).await
+    }
+
+    // the username is the local part of the address, followed by the first free number when another user has it already
+    pub async fn create_for_email_address_with(&self, conn: &mut PgConnection, user_id: &i32, email_address: &str, password: &String) -> Result<UserCredential, sqlx::Error> {
+        let local_part = email_address.split('@').next().unwrap_or_default().to_string();
+
+        // a name taken by a concurrent sign-up between the lookup and the insert is skipped on the next round
+        for _ in 0..MAX_USERNAME_ROUNDS {
+            let taken = sqlx::query_scalar!(
+                r#"SELECT username FROM "SMS_GATEWAY_USER"."USER_CREDENTIAL" 
+                WHERE username = $1 OR (starts_with(username, $1) AND substr(username, length($1) + 1) ~ '^[0-9]+$') "#, 
+                local_part)
+                .fetch_all(&mut *conn)
+                .await?;
+
+            let username = std::iter::once(local_part.clone())
+                .chain((2..).map(|suffix| format!("{}{}", local_part, suffix)))
+                .find(|candidate| !taken.contains(candidate))
+                .unwrap_or_default();
+
+            let created = sqlx::query_as!(UserCredential, 
+                r#"INSERT INTO "SMS_GATEWAY_USER"."USER_CREDENTIAL" (username, password, user_id) VALUES ($1, $2, $3) ON CONFLICT (username) DO NOTHING RETURNING * "#, 
+                username, password, user_id)
+                .fetch_optional(&mut *conn) 
+                .await?;
+
+            if let Some(created) = created {
+                return Ok(created);
+            }
+        }
+
+        Err(sqlx::Error::RowNotFound)
+    }
+
     pub async fn update(&self, user_id: &i32, user_credential_id: &i32, request: &UpdateUserCredential) -> Result<UserCredential, sqlx::Error> {
         let UpdateUserCredential { previous_password: _, password } = request;
 
         sqlx::query_as!(UserCredential, 
-            r#"UPDATE "SMS_GATEWAY_USER"."USER_CREDENTIAL" SET password = $1 WHERE user_credential_id = $2 AND user_id = $3 RETURNING * "#, 
+            r#"UPDATE "SMS_GATEWAY_USER"."USER_CREDENTIAL" SET password = $1, password_change_required = false WHERE user_credential_id = $2 AND user_id = $3 RETURNING * "#, 
             password, user_credential_id, user_id)
             .fetch_one(&*self.pool) 
             .await
     }
 
+    pub async fn update_password(&self, user_id: &i32, password: &String) -> Result<UserCredential, sqlx::Error> {
+        sqlx::query_as!(UserCredential, 
+            r#"UPDATE "SMS_GATEWAY_USER"."USER_CREDENTIAL" SET password = $1, password_change_required = false WHERE user_id = $2 RETURNING * "#, 
+            password, user_id)
+            .fetch_one(&*self.pool) 
+            .await
+    }
+
+    pub async fn require_password_change(&self, user_id: &i32) -> Result<UserCredential, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        self.require_password_change_with(&mut conn, user_id).await
+    }
+
+    // the holder gets a token without permissions until they choose a new password
+    pub async fn require_password_change_with(&self, conn: &mut PgConnection, user_id: &i32) -> Result<UserCredential, sqlx::Error> {
+        sqlx::query_as!(UserCredential, 
+            r#"UPDATE "SMS_GATEWAY_USER"."USER_CREDENTIAL" SET password_change_required = true WHERE user_id = $1 RETURNING * "#, 
+            user_id)
+            .fetch_one(&mut *conn) 
+            .await
+    }
+
+    pub async fn is_password_change_required(&self, user_id: &i32) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM "SMS_GATEWAY_USER"."USER_CREDENTIAL" WHERE user_id = $1 AND password_change_required) AS "exists!" "#, user_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
     pub async fn find_by_user_id(&self, user_id: &i32) -> Result<UserCredential, sqlx::Error> {
         sqlx::query_as!(UserCredential, 
             r#"SELECT * FROM "SMS_GATEWAY_USER"."USER_CREDENTIAL" WHERE user_id = $1 "#, user_id)
@@ -1,6 +1,6 @@
-use sqlx::postgres::PgQueryResult;
+use sqlx::{postgres::PgQueryResult, PgConnection};
 
-use crate::{entity::{role::Role, user::User}, model::{pagination::PaginatedResult, user::{CreateUser, UpdateUser}}};
+use crate::{entity::{role::Role, user::User}, i18n::Locale, model::{pagination::PaginatedResult, user::{CreateUser, UpdateUser}}};
 
 use super::{Table, CountResult};
 
@@ -55,24 +55,53 @@ impl<'c> Table<'c, User> {
     }
 
     pub async fn create(&self, request: &CreateUser) -> Result<User, sqlx::Error> {
-        let CreateUser { first_name, middle_name, surname, email_address, mobile_number, role_id } = request;
+        let mut conn = self.pool.acquire().await?;
+        self.create_with(&mut conn, request).await
+    }
+
+    pub async fn create_with(&self, conn: &mut PgConnection, request: &CreateUser) -> Result<User, sqlx::Error> {
+        let CreateUser { first_name, middle_name, surname, email_address, mobile_number, role_id, locale } = request;
+        let locale = locale.as_deref().and_then(Locale::from_tag).unwrap_or_default();
 
         sqlx::query_as!(Role, 
             r#"SELECT * FROM "SMS_GATEWAY_USER"."ROLE" WHERE role_id = $1 "#, &role_id)
-            .fetch_one(&*self.pool)
+            .fetch_one(&mut *conn)
             .await?;
 
         sqlx::query_as!(User, 
-            r#"INSERT INTO "SMS_GATEWAY_USER"."USER" (first_name, middle_name, surname, email_address, mobile_number, enabled, email_confirmed, role_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING * "#, 
-            first_name, *middle_name, surname, email_address, *mobile_number, false, false, role_id)
-            .fetch_one(&*self.pool) 
+            r#"INSERT INTO "SMS_GATEWAY_USER"."USER" (first_name, middle_name, surname, email_address, mobile_number, enabled, email_confirmed, role_id, locale) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING * "#, 
+            first_name, *middle_name, surname, email_address, *mobile_number, false, false, role_id, locale.as_str())
+            .fetch_one(&mut *conn) 
+            .await
+    }
+
+    pub async fn exists_by_role_with(&self, conn: &mut PgConnection, role_id: &i16) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM "SMS_GATEWAY_USER"."USER" WHERE role_id = $1) AS "exists!" "#, role_id)
+            .fetch_one(&mut *conn)
+            .await
+    }
+
+    pub async fn exists_by_email_address_with(&self, conn: &mut PgConnection, email_address: &String) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM "SMS_GATEWAY_USER"."USER" WHERE email_address = $1) AS "exists!" "#, email_address)
+            .fetch_one(&mut *conn)
+            .await
+    }
+
+    // enables the account and marks its email address as confirmed, for users created by an operator
+    pub async fn activate_with(&self, conn: &mut PgConnection, user_id: &i32) -> Result<User, sqlx::Error> {
+        sqlx::query_as!(User, 
+            r#"UPDATE "SMS_GATEWAY_USER"."USER" SET enabled = true, email_confirmed = true WHERE user_id = $1 RETURNING * "#, user_id)
+            .fetch_one(&mut *conn)
             .await
     }
 
     pub async fn update(&self, user_id: &i32, request: &UpdateUser) -> Result<User, sqlx::Error> {
         self.find_by_id(user_id).await?;
 
-        let UpdateUser { first_name, middle_name, surname, mobile_number , enabled, email_confirmed, role_id} = request;
+        let UpdateUser { first_name, middle_name, surname, mobile_number , enabled, email_confirmed, role_id, locale } = request;
+        let locale = locale.as_deref().and_then(Locale::from_tag).map(|locale| locale.as_str());
 
         let role = sqlx::query_as!(Role, 
             r#"SELECT * FROM "SMS_GATEWAY_USER"."ROLE" WHERE role_id = $1 "#, role_id)
@@ -80,31 +109,64 @@ impl<'c> Table<'c, User> {
             .await?;
         
         sqlx::query_as!(User, 
-            r#"UPDATE "SMS_GATEWAY_USER"."USER" SET first_name = $1, middle_name = $2, surname = $3, mobile_number = $4, enabled = $5, email_confirmed = $6, role_id = $7 WHERE user_id = $8 RETURNING * "#, 
-            first_name, *middle_name, surname, *mobile_number, enabled, email_confirmed, role.role_id, user_id)
+            r#"UPDATE "SMS_GATEWAY_USER"."USER" SET first_name = $1, middle_name = $2, surname = $3, mobile_number = $4, enabled = $5, email_confirmed = $6, role_id = $7, locale = COALESCE($8, locale) WHERE user_id = $9 RETURNING * "#, 
+            first_name, *middle_name, surname, *mobile_number, enabled, email_confirmed, role.role_id, locale, user_id)
             .fetch_one(&*self.pool) 
             .await
     }
 
     pub async fn update_user(&self, user: &User) -> Result<User, sqlx::Error> {
-        let User { user_id, first_name, middle_name, surname, email_address: _email_address, mobile_number , enabled, email_confirmed, role_id, created_at: _created_at} = user;
+        let User { user_id, first_name, middle_name, surname, email_address: _email_address, mobile_number , enabled, email_confirmed, role_id, created_at: _created_at, locale} = user;
 
         sqlx::query_as!(User, 
-            r#"UPDATE "SMS_GATEWAY_USER"."USER" SET first_name = $1, middle_name = $2, surname = $3, mobile_number = $4, enabled = $5, email_confirmed = $6, role_id = $7 WHERE user_id = $8 RETURNING * "#, 
-            first_name, *middle_name, surname, *mobile_number, enabled, email_confirmed, role_id, user_id)
+            r#"UPDATE "SMS_GATEWAY_USER"."USER" SET first_name = $1, middle_name = $2, surname = $3, mobile_number = $4, enabled = $5, email_confirmed = $6, role_id = $7, locale = $8 WHERE user_id = $9 RETURNING * "#, 
+            first_name, *middle_name, surname, *mobile_number, enabled, email_confirmed, role_id, locale, user_id)
             .fetch_one(&*self.pool) 
             .await
     }
 
     pub async fn delete(&self, user_id: &i32) -> Result<PgQueryResult, sqlx::Error> {
-        sqlx::query_as!(PgQueryResult, 
+        let mut transaction = self.pool.begin().await?;
+        let result = self.delete_with(&mut transaction, user_id).await?;
+        transaction.commit().await?;
+
+        Ok(result)
+    }
+
+    pub async fn delete_with(&self, conn: &mut PgConnection, user_id: &i32) -> Result<PgQueryResult, sqlx::Error> {
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."REFRESH_TOKEN" WHERE user_id = $1 "#, user_id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."PASSWORD_RESET_TOKEN" WHERE user_id = $1 "#, user_id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."ACCOUNT_UNLOCK_TOKEN" WHERE user_id = $1 "#, user_id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."EMAIL_LOCKOUT" WHERE email_address = (SELECT lower(email_address) FROM "SMS_GATEWAY_USER"."USER" WHERE user_id = $1) "#, user_id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM "SMS_GATEWAY_USER"."USER_CODE" WHERE user_id = $1 "#, user_id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query!(
             r#"DELETE FROM "SMS_GATEWAY_USER"."USER_CREDENTIAL" WHERE user_id = $1 "#, user_id)
-            .execute(&*self.pool)
+            .execute(&mut *conn)
             .await?;
 
-        sqlx::query_as!(PgQueryResult, 
+        sqlx::query!(
             r#"DELETE FROM "SMS_GATEWAY_USER"."USER" WHERE user_id = $1 "#, user_id)
-            .execute(&*self.pool)
+            .execute(&mut *conn)
             .await
     }
-}
+}
\ No newline at end of file
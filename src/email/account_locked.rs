@@ -0,0 +1,10 @@
+pub struct AccountLockedTemplate {
+    pub recipient: String,
+    pub unlock_link: String,
+    pub locked_until: String,
+}
+
+email_template!(AccountLockedTemplate, "email.account_locked.subject",
+    En: (AccountLockedHtml, "email/en/account_locked.html", AccountLockedText, "email/en/account_locked.txt"),
+    Fr: (AccountLockedHtmlFr, "email/fr/account_locked.html", AccountLockedTextFr, "email/fr/account_locked.txt"),
+);
\ No newline at end of file
@@ -0,0 +1,84 @@
+use super::{account_locked::AccountLockedTemplate, email_confirmation::EmailConfirmationTemplate, invitation::InvitationTemplate, new_sign_in::NewSignInTemplate, password_changed::PasswordChangedTemplate, password_reset::PasswordResetTemplate, password_reset_unknown::PasswordResetUnknownTemplate, registration_attempt::RegistrationAttemptTemplate, welcome::WelcomeTemplate, EmailTemplate};
+
+pub const TEMPLATES: &[&str] = &[
+    "confirmation",
+    "password_reset",
+    "welcome",
+    "password_changed",
+    "account_locked",
+    "new_sign_in",
+    "invitation",
+    "registration_attempt",
+    "password_reset_unknown",
+];
+
+// sample data used to preview each template without touching real accounts
+pub fn sample(name: &str) -> Option<Box<dyn EmailTemplate>> {
+    let recipient = "John".to_string();
+
+    let template: Box<dyn EmailTemplate> = match name {
+        "confirmation" => Box::new(EmailConfirmationTemplate { code: 1234, user_id: 1, recipient }),
+        "password_reset" => Box::new(PasswordResetTemplate {
+            link: "http://localhost:8080/password/reset?token=sample".to_string(),
+            recipient,
+            expires_in: 30,
+        }),
+        "welcome" => Box::new(WelcomeTemplate { recipient }),
+        "password_changed" => Box::new(PasswordChangedTemplate { recipient, changed_at: "2024-01-01 09:00 UTC".to_string() }),
+        "account_locked" => Box::new(AccountLockedTemplate {
+            recipient,
+            unlock_link: "http://localhost:8080/account/unlock?token=sample".to_string(),
+            locked_until: "2024-01-01 09:15 UTC".to_string(),
+        }),
+        "new_sign_in" => Box::new(NewSignInTemplate {
+            recipient,
+            ip_address: "192.0.2.1".to_string(),
+            user_agent: "Mozilla/5.0".to_string(),
+            signed_in_at: "2024-01-01 09:00 UTC".to_string(),
+        }),
+        "invitation" => Box::new(InvitationTemplate {
+            recipient,
+            inviter: "Jane Pope".to_string(),
+            link: "http://localhost:8080/sign-up?invitation=sample".to_string(),
+        }),
+        "registration_attempt" => Box::new(RegistrationAttemptTemplate { recipient, attempted_at: "2024-01-01 09:00 UTC".to_string() }),
+        "password_reset_unknown" => Box::new(PasswordResetUnknownTemplate { recipient }),
+        _ => return None,
+    };
+
+    Some(template)
+}
+
+#[cfg(test)]
+mod catalogue_tests {
+    use crate::i18n::Locale;
+
+    use super::*;
+
+    #[test]
+    fn every_template_renders_html_and_text_in_every_locale() {
+        for locale in Locale::ALL {
+            for name in TEMPLATES {
+                let content = sample(name).unwrap().render(*locale).unwrap();
+
+                assert!(content.html.contains("John"), "{} {} html is missing the recipient", locale.as_str(), name);
+                assert!(content.html.contains(&format!("lang=\"{}\"", locale.as_str())), "{} {} html has the wrong lang", locale.as_str(), name);
+                assert!(content.text.contains("John"), "{} {} text is missing the recipient", locale.as_str(), name);
+                assert!(!content.text.contains('<'), "{} {} text contains markup", locale.as_str(), name);
+            }
+        }
+    }
+
+    #[test]
+    fn subject_is_translated() {
+        let template = sample("welcome").unwrap();
+
+        assert_eq!(template.subject(Locale::En), "Welcome to Bulk SMS");
+        assert_eq!(template.subject(Locale::Fr), "Bienvenue sur Bulk SMS");
+    }
+
+    #[test]
+    fn sample_returns_none_for_unknown_template() {
+        assert!(sample("unknown").is_none());
+    }
+}
\ No newline at end of file
@@ -1,8 +1,10 @@
-use askama::Template;
-
-#[derive(Template)]
-#[template(path = "email_confirmation_template.html")]
 pub struct EmailConfirmationTemplate {
     pub code: i32,
+    pub user_id: i32,
     pub recipient: String,
-}
\ No newline at end of file
+}
+
+email_template!(EmailConfirmationTemplate, "email.confirmation.subject",
+    En: (EmailConfirmationHtml, "email/en/confirmation.html", EmailConfirmationText, "email/en/confirmation.txt"),
+    Fr: (EmailConfirmationHtmlFr, "email/fr/confirmation.html", EmailConfirmationTextFr, "email/fr/confirmation.txt"),
+);
\ No newline at end of file
@@ -0,0 +1,38 @@
+use std::{fs, path::PathBuf};
+
+use async_trait::async_trait;
+use lettre::{AsyncFileTransport, AsyncTransport, Tokio1Executor};
+use log::{error, info};
+
+use crate::error::{AppError, AppErrorType};
+
+use super::{build_message, EmailContent, EmailDetails, EmailSender};
+
+// writes every message as an .eml file, handy for local development
+pub struct FileEmailSender {
+    transport: AsyncFileTransport<Tokio1Executor>,
+}
+
+impl FileEmailSender {
+
+    pub fn new(dir: PathBuf) -> Result<Self, AppError> {
+        fs::create_dir_all(&dir)
+            .map_err(|error| AppError::new(None, Some(format!("Unable to create {:?}: {}", dir, error)), AppErrorType::InternalServerError))?;
+
+        Ok(FileEmailSender { transport: AsyncFileTransport::new(dir) })
+    }
+}
+
+#[async_trait]
+impl EmailSender for FileEmailSender {
+    async fn send(&self, details: &EmailDetails<'_>, content: &EmailContent) -> Result<(), AppError> {
+        let message = build_message(details, content)?;
+
+        self.transport.send(message).await
+            .map(|id| info!("Wrote email '{}' to {} as {}", details.subject, details.to, id))
+            .map_err(|error| {
+                error!("Error occured: {:?}", error); 
+                AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,10 @@
+pub struct InvitationTemplate {
+    pub recipient: String,
+    pub inviter: String,
+    pub link: String,
+}
+
+email_template!(InvitationTemplate, "email.invitation.subject",
+    En: (InvitationHtml, "email/en/invitation.html", InvitationText, "email/en/invitation.txt"),
+    Fr: (InvitationHtmlFr, "email/fr/invitation.html", InvitationTextFr, "email/fr/invitation.txt"),
+);
\ No newline at end of file
@@ -0,0 +1,43 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::error::AppError;
+
+use super::{EmailContent, EmailDetails, EmailSender};
+
+#[derive(Debug, Clone)]
+pub struct SentEmail {
+    pub subject: String,
+    pub to: String,
+    pub from: String,
+    pub html: String,
+    pub text: String,
+}
+
+// keeps messages in memory so tests can assert on what would have been sent
+#[derive(Default)]
+pub struct InMemoryEmailSender {
+    sent: Mutex<Vec<SentEmail>>,
+}
+
+impl InMemoryEmailSender {
+
+    pub fn sent(&self) -> Vec<SentEmail> {
+        self.sent.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[async_trait]
+impl EmailSender for InMemoryEmailSender {
+    async fn send(&self, details: &EmailDetails<'_>, content: &EmailContent) -> Result<(), AppError> {
+        self.sent.lock().unwrap_or_else(|e| e.into_inner()).push(SentEmail {
+            subject: details.subject.to_string(),
+            to: details.to.to_string(),
+            from: details.from.to_string(),
+            html: content.html.clone(),
+            text: content.text.clone(),
+        });
+        Ok(())
+    }
+}
\ No newline at end of file
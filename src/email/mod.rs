@@ -1,7 +1,153 @@
+// generates an askama wrapper per locale and format around the template's fields, falling back to english
+macro_rules! email_template {
+    ($template:ident, $subject:literal, En: ($html:ident, $html_path:literal, $text:ident, $text_path:literal) $(, $locale:ident: ($locale_html:ident, $locale_html_path:literal, $locale_text:ident, $locale_text_path:literal))* $(,)?) => {
+        email_template!(@wrapper $template, $html, $html_path);
+        email_template!(@wrapper $template, $text, $text_path);
+        $(
+            email_template!(@wrapper $template, $locale_html, $locale_html_path);
+            email_template!(@wrapper $template, $locale_text, $locale_text_path);
+        )*
+
+        impl crate::email::EmailTemplate for $template {
+            fn subject(&self, locale: crate::i18n::Locale) -> &'static str {
+                locale.message($subject)
+            }
+
+            fn render_html(&self, locale: crate::i18n::Locale) -> askama::Result<String> {
+                match locale {
+                    $(crate::i18n::Locale::$locale => askama::Template::render(&$locale_html { inner: self }),)*
+                    _ => askama::Template::render(&$html { inner: self }),
+                }
+            }
+
+            fn render_text(&self, locale: crate::i18n::Locale) -> askama::Result<String> {
+                match locale {
+                    $(crate::i18n::Locale::$locale => askama::Template::render(&$locale_text { inner: self }),)*
+                    _ => askama::Template::render(&$text { inner: self }),
+                }
+            }
+        }
+    };
+    (@wrapper $template:ident, $name:ident, $path:literal) => {
+        #[derive(askama::Template)]
+        #[template(path = $path)]
+        pub struct $name<'a> {
+            inner: &'a $template,
+        }
+
+        impl<'a> std::ops::Deref for $name<'a> {
+            type Target = $template;
+
+            fn deref(&self) -> &Self::Target {
+                self.inner
+            }
+        }
+    };
+}
+
 pub mod email_confirmation;
+pub mod password_reset;
+pub mod password_reset_unknown;
+pub mod welcome;
+pub mod password_changed;
+pub mod account_locked;
+pub mod new_sign_in;
+pub mod invitation;
+pub mod registration_attempt;
+pub mod catalogue;
+pub mod smtp;
+pub mod file;
+pub mod memory;
+pub mod outbox;
+
+use std::{env, path::PathBuf, sync::Arc};
+
+use async_trait::async_trait;
+use lettre::{message::{Mailbox, MultiPart}, Message};
+use log::{error, info};
+
+use crate::{error::{AppError, AppErrorType}, i18n::Locale};
+
+use self::{file::FileEmailSender, memory::InMemoryEmailSender, smtp::SmtpEmailSender};
 
 pub struct EmailDetails<'a> {
     pub subject: &'a str,
     pub to: &'a str,
     pub from: &'a str,
+}
+
+pub struct EmailContent {
+    pub html: String,
+    pub text: String,
+}
+
+pub trait EmailTemplate {
+    fn subject(&self, locale: Locale) -> &'static str;
+    fn render_html(&self, locale: Locale) -> askama::Result<String>;
+    fn render_text(&self, locale: Locale) -> askama::Result<String>;
+
+    fn render(&self, locale: Locale) -> Result<EmailContent, AppError> {
+        self.render_html(locale)
+            .and_then(|html| self.render_text(locale).map(|text| EmailContent { html, text }))
+            .map_err(|error| {
+                error!("Error occured: {:?}", error); 
+                AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            })
+    }
+}
+
+#[async_trait]
+pub trait EmailSender: Send + Sync {
+    async fn send(&self, details: &EmailDetails<'_>, content: &EmailContent) -> Result<(), AppError>;
+}
+
+pub fn compose<'a>(template: &dyn EmailTemplate, locale: Locale, to: &'a str, from: &'a str) -> Result<(EmailDetails<'a>, EmailContent), AppError> {
+    let content = template.render(locale)?;
+    let details = EmailDetails {
+        subject: template.subject(locale),
+        to,
+        from,
+    };
+
+    Ok((details, content))
+}
+
+pub fn sender_from_env() -> Arc<dyn EmailSender> {
+    let transport = env::var("EMAIL_TRANSPORT").unwrap_or_else(|_| "file".to_string());
+
+    info!("Using {} email transport", transport);
+
+    match transport.as_str() {
+        "smtp" => {
+            let host = env::var("SMTP_HOST").expect("SMTP_HOST was not provided.");
+            let port = env::var("SMTP_PORT").ok().map(|port| port.parse::<u16>().expect("SMTP_PORT should be a u16."));
+            let tls = env::var("SMTP_TLS").unwrap_or_else(|_| "starttls".to_string());
+            let username = env::var("SMTP_USERNAME").ok().filter(|username| !username.is_empty());
+            let password = env::var("SMTP_PASSWORD").ok().filter(|password| !password.is_empty());
+
+            let sender = SmtpEmailSender::new(&host, port, &tls, username.zip(password))
+                .unwrap_or_else(|error| panic!("Unable to configure SMTP transport: {:?}", error.cause));
+            Arc::new(sender)
+        },
+        "file" => {
+            let dir = PathBuf::from(env::var("EMAIL_FILE_DIR").unwrap_or_else(|_| "mail".to_string()));
+            Arc::new(FileEmailSender::new(dir).unwrap_or_else(|error| panic!("Unable to configure file transport: {:?}", error.cause)))
+        },
+        "memory" => Arc::new(InMemoryEmailSender::default()),
+        _ => panic!("EMAIL_TRANSPORT should be one of smtp, file or memory."),
+    }
+}
+
+fn build_message(details: &EmailDetails<'_>, content: &EmailContent) -> Result<Message, AppError> {
+    let from = details.from.parse::<Mailbox>()
+        .map_err(|error| AppError::new(None, Some(format!("Invalid sender {}: {}", details.from, error)), AppErrorType::InternalServerError))?;
+    let to = details.to.parse::<Mailbox>()
+        .map_err(|error| AppError::new(None, Some(format!("Invalid recipient {}: {}", details.to, error)), AppErrorType::InternalServerError))?;
+
+    Message::builder()
+        .from(from)
+        .to(to)
+        .subject(details.subject)
+        .multipart(MultiPart::alternative_plain_html(content.text.clone(), content.html.clone()))
+        .map_err(|error| AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError))
 }
\ No newline at end of file
@@ -0,0 +1,11 @@
+pub struct NewSignInTemplate {
+    pub recipient: String,
+    pub ip_address: String,
+    pub user_agent: String,
+    pub signed_in_at: String,
+}
+
+email_template!(NewSignInTemplate, "email.new_sign_in.subject",
+    En: (NewSignInHtml, "email/en/new_sign_in.html", NewSignInText, "email/en/new_sign_in.txt"),
+    Fr: (NewSignInHtmlFr, "email/fr/new_sign_in.html", NewSignInTextFr, "email/fr/new_sign_in.txt"),
+);
\ No newline at end of file
@@ -0,0 +1,149 @@
+use std::{env, sync::Arc, time::{Duration as StdDuration, Instant}};
+
+use actix_web::rt;
+use chrono::{Duration, Utc};
+use log::{error, info, warn};
+
+use crate::{dao::Database, i18n::Locale};
+
+use super::{EmailContent, EmailDetails, EmailSender};
+
+const DEFAULT_POLL_INTERVAL: u64 = 5;
+const DEFAULT_BATCH_SIZE: i64 = 10;
+const DEFAULT_MAX_ATTEMPTS: i32 = 8;
+const DEFAULT_BACKOFF_BASE: i64 = 30;
+const DEFAULT_BACKOFF_MAX: i64 = 3600;
+const DEFAULT_LEASE: i64 = 300;
+const DEFAULT_DEAD_RETENTION: i64 = 30;
+const PURGE_INTERVAL: u64 = 3600;
+
+pub struct OutboxConfig {
+    pub poll_interval: u64,
+    pub batch_size: i64,
+    pub max_attempts: i32,
+    pub backoff_base: i64,
+    pub backoff_max: i64,
+    pub lease: i64,
+    pub dead_retention: i64,
+}
+
+impl OutboxConfig {
+
+    pub fn from_env() -> Self {
+        OutboxConfig {
+            poll_interval: env::var("OUTBOX_POLL_INTERVAL").map(|value| value.parse::<u64>().expect("OUTBOX_POLL_INTERVAL should be a u64.")).unwrap_or(DEFAULT_POLL_INTERVAL),
+            batch_size: env::var("OUTBOX_BATCH_SIZE").map(|value| value.parse::<i64>().expect("OUTBOX_BATCH_SIZE should be an i64.")).unwrap_or(DEFAULT_BATCH_SIZE),
+            max_attempts: env::var("OUTBOX_MAX_ATTEMPTS").map(|value| value.parse::<i32>().expect("OUTBOX_MAX_ATTEMPTS should be an i32.")).unwrap_or(DEFAULT_MAX_ATTEMPTS),
+            backoff_base: env::var("OUTBOX_BACKOFF_BASE").map(|value| value.parse::<i64>().expect("OUTBOX_BACKOFF_BASE should be an i64.")).unwrap_or(DEFAULT_BACKOFF_BASE),
+            backoff_max: env::var("OUTBOX_BACKOFF_MAX").map(|value| value.parse::<i64>().expect("OUTBOX_BACKOFF_MAX should be an i64.")).unwrap_or(DEFAULT_BACKOFF_MAX),
+            lease: DEFAULT_LEASE,
+            dead_retention: env::var("OUTBOX_DEAD_RETENTION").map(|value| value.parse::<i64>().expect("OUTBOX_DEAD_RETENTION should be an i64.")).unwrap_or(DEFAULT_DEAD_RETENTION),
+        }
+    }
+
+    // base, 2 * base, 4 * base ... capped at backoff_max, all in seconds
+    fn backoff(&self, attempts: i32) -> Duration {
+        let exponent = (attempts - 1).clamp(0, 20) as u32;
+        Duration::seconds(self.backoff_base.saturating_mul(2_i64.pow(exponent)).min(self.backoff_max))
+    }
+}
+
+pub fn start(context: Arc<Database<'static>>, sender: Arc<dyn EmailSender>, config: OutboxConfig) {
+    info!("Starting email outbox worker polling every {}s", config.poll_interval);
+
+    rt::spawn(async move {
+        let mut interval = rt::time::interval(StdDuration::from_secs(config.poll_interval));
+        let mut last_purge: Option<Instant> = None;
+        loop {
+            interval.tick().await;
+            if let Err(error) = drain(&context, sender.as_ref(), &config).await {
+                error!("Error occured: {:?}", error);
+            }
+
+            if last_purge.is_none_or(|purged_at| purged_at.elapsed() >= StdDuration::from_secs(PURGE_INTERVAL)) {
+                last_purge = Some(Instant::now());
+                if let Err(error) = purge(&context, &config).await {
+                    error!("Error occured: {:?}", error);
+                }
+            }
+        }
+    });
+}
+
+// dead messages are kept dead_retention days for inspection and manual retry, then deleted with their bodies
+pub async fn purge(context: &Database<'_>, config: &OutboxConfig) -> Result<u64, sqlx::Error> {
+    let count = context.email_outbox.delete_dead_before(&(Utc::now() - Duration::days(config.dead_retention))).await?;
+
+    if count > 0 {
+        info!("Purged {} dead emails older than {} days", count, config.dead_retention);
+    }
+
+    Ok(count)
+}
+
+pub async fn drain(context: &Database<'_>, sender: &dyn EmailSender, config: &OutboxConfig) -> Result<usize, sqlx::Error> {
+    let lease_until = Utc::now() + Duration::seconds(config.lease);
+    let messages = context.email_outbox.claim_due(&config.batch_size, &lease_until).await?;
+    let count = messages.len();
+
+    for message in messages {
+        let details = EmailDetails {
+            subject: &message.subject,
+            to: &message.recipient,
+            from: &message.sender,
+        };
+
+        let content = EmailContent {
+            html: message.html.clone(),
+            text: message.plain_text.clone(),
+        };
+
+        match sender.send(&details, &content).await {
+            Ok(_) => {
+                context.email_outbox.mark_sent(&message.email_outbox_id).await?;
+            },
+            Err(error) => {
+                let last_error = error.cause.or_else(|| error.message.map(|message| message.render(Locale::default()))).unwrap_or_default();
+                let attempts = message.attempts + 1;
+
+                if attempts >= config.max_attempts {
+                    warn!("Giving up on email {} to {} after {} attempts: {}", message.email_outbox_id, message.recipient, attempts, last_error);
+                    context.email_outbox.mark_dead(&message.email_outbox_id, &last_error).await?;
+                } else {
+                    let next_attempt_at = Utc::now() + config.backoff(attempts);
+                    context.email_outbox.schedule_retry(&message.email_outbox_id, &last_error, &next_attempt_at).await?;
+                }
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod outbox_tests {
+    use super::*;
+
+    fn config() -> OutboxConfig {
+        OutboxConfig {
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            batch_size: DEFAULT_BATCH_SIZE,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            backoff_base: 30,
+            backoff_max: 300,
+            lease: DEFAULT_LEASE,
+            dead_retention: DEFAULT_DEAD_RETENTION,
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_until_capped() {
+        let config = config();
+
+        assert_eq!(config.backoff(1), Duration::seconds(30));
+        assert_eq!(config.backoff(2), Duration::seconds(60));
+        assert_eq!(config.backoff(3), Duration::seconds(120));
+        assert_eq!(config.backoff(5), Duration::seconds(300));
+        assert_eq!(config.backoff(100), Duration::seconds(300));
+    }
+}
\ No newline at end of file
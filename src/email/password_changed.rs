@@ -0,0 +1,9 @@
+pub struct PasswordChangedTemplate {
+    pub recipient: String,
+    pub changed_at: String,
+}
+
+email_template!(PasswordChangedTemplate, "email.password_changed.subject",
+    En: (PasswordChangedHtml, "email/en/password_changed.html", PasswordChangedText, "email/en/password_changed.txt"),
+    Fr: (PasswordChangedHtmlFr, "email/fr/password_changed.html", PasswordChangedTextFr, "email/fr/password_changed.txt"),
+);
\ No newline at end of file
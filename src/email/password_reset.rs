@@ -0,0 +1,10 @@
+pub struct PasswordResetTemplate {
+    pub link: String,
+    pub recipient: String,
+    pub expires_in: i64,
+}
+
+email_template!(PasswordResetTemplate, "email.password_reset.subject",
+    En: (PasswordResetHtml, "email/en/password_reset.html", PasswordResetText, "email/en/password_reset.txt"),
+    Fr: (PasswordResetHtmlFr, "email/fr/password_reset.html", PasswordResetTextFr, "email/fr/password_reset.txt"),
+);
\ No newline at end of file
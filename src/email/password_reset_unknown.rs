@@ -0,0 +1,8 @@
+pub struct PasswordResetUnknownTemplate {
+    pub recipient: String,
+}
+
+email_template!(PasswordResetUnknownTemplate, "email.password_reset_unknown.subject",
+    En: (PasswordResetUnknownHtml, "email/en/password_reset_unknown.html", PasswordResetUnknownText, "email/en/password_reset_unknown.txt"),
+    Fr: (PasswordResetUnknownHtmlFr, "email/fr/password_reset_unknown.html", PasswordResetUnknownTextFr, "email/fr/password_reset_unknown.txt"),
+);
\ No newline at end of file
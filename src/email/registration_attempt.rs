@@ -0,0 +1,9 @@
+pub struct RegistrationAttemptTemplate {
+    pub recipient: String,
+    pub attempted_at: String,
+}
+
+email_template!(RegistrationAttemptTemplate, "email.registration_attempt.subject",
+    En: (RegistrationAttemptHtml, "email/en/registration_attempt.html", RegistrationAttemptText, "email/en/registration_attempt.txt"),
+    Fr: (RegistrationAttemptHtmlFr, "email/fr/registration_attempt.html", RegistrationAttemptTextFr, "email/fr/registration_attempt.txt"),
+);
\ No newline at end of file
@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use lettre::{transport::smtp::authentication::Credentials, AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use log::error;
+
+use crate::error::{AppError, AppErrorType};
+
+use super::{build_message, EmailContent, EmailDetails, EmailSender};
+
+pub struct SmtpEmailSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpEmailSender {
+
+    pub fn new(host: &str, port: Option<u16>, tls: &str, credentials: Option<(String, String)>) -> Result<Self, AppError> {
+        let builder = match tls {
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+            // plain connections are only meant for local catch-all servers
+            "none" => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)),
+            _ => return Err(AppError::new(None, Some(format!("Unknown SMTP TLS mode {}", tls)), AppErrorType::InternalServerError)),
+        }
+        .map_err(|error| AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError))?;
+
+        let builder = match port {
+            Some(port) => builder.port(port),
+            None => builder,
+        };
+
+        let builder = match credentials {
+            Some((username, password)) => builder.credentials(Credentials::new(username, password)),
+            None => builder,
+        };
+
+        Ok(SmtpEmailSender { transport: builder.build() })
+    }
+}
+
+#[async_trait]
+impl EmailSender for SmtpEmailSender {
+    async fn send(&self, details: &EmailDetails<'_>, content: &EmailContent) -> Result<(), AppError> {
+        let message = build_message(details, content)?;
+
+        self.transport.send(message).await
+            .map(|_| ())
+            .map_err(|error| {
+                error!("Error occured: {:?}", error); 
+                AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,8 @@
+pub struct WelcomeTemplate {
+    pub recipient: String,
+}
+
+email_template!(WelcomeTemplate, "email.welcome.subject",
+    En: (WelcomeHtml, "email/en/welcome.html", WelcomeText, "email/en/welcome.txt"),
+    Fr: (WelcomeHtmlFr, "email/fr/welcome.html", WelcomeTextFr, "email/fr/welcome.txt"),
+);
\ No newline at end of file
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, postgres::PgRow, Row};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AccountUnlockToken {
+    pub account_unlock_token_id: i32,
+    pub token_hash: String,
+    pub user_id: i32,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl<'c> FromRow<'c, PgRow> for AccountUnlockToken {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(AccountUnlockToken {
+            account_unlock_token_id: row.get(0),
+            token_hash: row.get(1),
+            user_id: row.get(2),
+            expires_at: row.get(3),
+            used_at: row.get(4),
+            created_at: row.get(5),
+        })
+    }
+}
\ No newline at end of file
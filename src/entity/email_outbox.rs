@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, postgres::PgRow, Row};
+
+pub const STATUS_PENDING: &str = "PENDING";
+pub const STATUS_SENT: &str = "SENT";
+pub const STATUS_DEAD: &str = "DEAD";
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailOutbox {
+    pub email_outbox_id: i32,
+    pub subject: String,
+    pub recipient: String,
+    pub sender: String,
+    #[serde(skip_serializing, default)]
+    pub html: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing, default)]
+    pub plain_text: String,
+}
+
+impl<'c> FromRow<'c, PgRow> for EmailOutbox {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(EmailOutbox {
+            email_outbox_id: row.get(0),
+            subject: row.get(1),
+            recipient: row.get(2),
+            sender: row.get(3),
+            html: row.get(4),
+            status: row.get(5),
+            attempts: row.get(6),
+            last_error: row.get(7),
+            next_attempt_at: row.get(8),
+            sent_at: row.get(9),
+            created_at: row.get(10),
+            plain_text: row.get(11),
+        })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, postgres::PgRow, Row};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailLockout {
+    pub email_address: String,
+    pub failed_attempts: i32,
+    pub last_failed_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpLockout {
+    pub ip_address: String,
+    pub failed_attempts: i32,
+    pub last_failed_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+impl EmailLockout {
+    pub fn is_locked(&self, now: DateTime<Utc>) -> bool {
+        self.locked_until.is_some_and(|locked_until| locked_until > now)
+    }
+}
+
+impl IpLockout {
+    pub fn is_locked(&self, now: DateTime<Utc>) -> bool {
+        self.locked_until.is_some_and(|locked_until| locked_until > now)
+    }
+}
+
+impl<'c> FromRow<'c, PgRow> for EmailLockout {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(EmailLockout {
+            email_address: row.get(0),
+            failed_attempts: row.get(1),
+            last_failed_at: row.get(2),
+            locked_until: row.get(3),
+        })
+    }
+}
+
+impl<'c> FromRow<'c, PgRow> for IpLockout {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(IpLockout {
+            ip_address: row.get(0),
+            failed_attempts: row.get(1),
+            last_failed_at: row.get(2),
+            locked_until: row.get(3),
+        })
+    }
+}
\ No newline at end of file
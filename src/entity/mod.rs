@@ -5,3 +5,11 @@ pub mod user_credential;
 pub mod organisation;
 pub mod unit;
 pub mod user_code;
+pub mod refresh_token;
+
+pub mod revoked_token;
+pub mod password_reset_token;
+pub mod email_outbox;
+pub mod lockout;
+pub mod account_unlock_token;
+pub mod signing_key_schedule;
\ No newline at end of file
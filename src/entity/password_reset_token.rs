@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, postgres::PgRow, Row};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PasswordResetToken {
+    pub password_reset_token_id: i32,
+    pub token_hash: String,
+    // None for addresses without an account, whose token is never sent
+    pub user_id: Option<i32>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub email_address: String,
+}
+
+impl<'c> FromRow<'c, PgRow> for PasswordResetToken {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(PasswordResetToken {
+            password_reset_token_id: row.get(0),
+            token_hash: row.get(1),
+            user_id: row.get(2),
+            expires_at: row.get(3),
+            used_at: row.get(4),
+            created_at: row.get(5),
+            email_address: row.get(6),
+        })
+    }
+}
\ No newline at end of file
@@ -9,6 +9,8 @@ pub struct Permission {
     pub permission_id: i16,
     pub name: String,
     pub created_at: DateTime<Utc>,
+    pub description: Option<String>,
+    pub is_system: bool,
 }
 
 #[derive(Deserialize, Serialize, Validate)]
@@ -18,12 +20,32 @@ pub struct CreatePermission {
     pub name: String,
 }
 
+#[derive(Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePermission {
+    #[validate(length(min = 3, message = "Permission name is required!"))]
+    pub name: String,
+    #[validate(length(max = 255, message = "Description must not exceed 255 characters!"))]
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchPermission {
+    #[validate(length(min = 3, message = "Permission name is required!"))]
+    pub name: Option<String>,
+    #[validate(length(max = 255, message = "Description must not exceed 255 characters!"))]
+    pub description: Option<String>,
+}
+
 impl<'c> FromRow<'c, PgRow> for Permission {
     fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
         Ok(Permission {
             permission_id: row.get(0),
             name: row.get(1),
             created_at: row.get(2),
+            description: row.get(3),
+            is_system: row.get(4),
         })
     }
 }
\ No newline at end of file
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, postgres::PgRow, Row};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RefreshToken {
+    pub refresh_token_id: i32,
+    pub token_hash: String,
+    pub family_id: String,
+    pub user_id: i32,
+    pub expires_at: DateTime<Utc>,
+    pub revoked: bool,
+    pub created_at: DateTime<Utc>,
+    pub audience: Option<String>,
+}
+
+impl<'c> FromRow<'c, PgRow> for RefreshToken {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(RefreshToken {
+            refresh_token_id: row.get(0),
+            token_hash: row.get(1),
+            family_id: row.get(2),
+            user_id: row.get(3),
+            expires_at: row.get(4),
+            revoked: row.get(5),
+            created_at: row.get(6),
+            audience: row.get(7),
+        })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, postgres::PgRow, Row};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RevokedToken {
+    pub revoked_token_id: i32,
+    pub jti: String,
+    pub user_id: i32,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UserTokenRevocation {
+    pub user_id: i32,
+    pub revoked_at: DateTime<Utc>,
+}
+
+// everything that can invalidate the tokens of one user, as checked by the authentication guard
+#[derive(Debug, Default)]
+pub struct UserRevocations {
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub deactivated_at: Option<DateTime<Utc>>,
+    pub revoked_jtis: Vec<String>,
+}
+
+impl<'c> FromRow<'c, PgRow> for RevokedToken {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(RevokedToken {
+            revoked_token_id: row.get(0),
+            jti: row.get(1),
+            user_id: row.get(2),
+            expires_at: row.get(3),
+            created_at: row.get(4),
+        })
+    }
+}
+
+impl<'c> FromRow<'c, PgRow> for UserTokenRevocation {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(UserTokenRevocation {
+            user_id: row.get(0),
+            revoked_at: row.get(1),
+        })
+    }
+}
\ No newline at end of file
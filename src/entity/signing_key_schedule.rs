@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, postgres::PgRow, Row};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SigningKeySchedule {
+    pub kid: String,
+    pub activate_at: Option<DateTime<Utc>>,
+    pub retire_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'c> FromRow<'c, PgRow> for SigningKeySchedule {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(SigningKeySchedule {
+            kid: row.get(0),
+            activate_at: row.get(1),
+            retire_at: row.get(2),
+            updated_at: row.get(3),
+        })
+    }
+}
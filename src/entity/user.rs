@@ -19,6 +19,7 @@ pub struct User {
     pub email_confirmed: bool,
     pub role_id: i16,
     pub created_at: DateTime<Utc>,
+    pub locale: String,
 }
 
 impl<'c> FromRow<'c, PgRow> for User {
@@ -34,6 +35,7 @@ impl<'c> FromRow<'c, PgRow> for User {
             email_confirmed: row.get(7),
             role_id: row.get(8),
             created_at: row.get(9),
+            locale: row.get(10),
         })
     }
 }
\ No newline at end of file
@@ -9,6 +9,9 @@ pub struct UserCode {
     pub code: i32,
     pub created_at: DateTime<Utc>,
     pub user_id: i32,
+    pub attempts: i32,
+    pub resend_count: i32,
+    pub last_sent_at: DateTime<Utc>,
 }
 
 impl<'c> FromRow<'c, PgRow> for UserCode {
@@ -18,6 +21,9 @@ impl<'c> FromRow<'c, PgRow> for UserCode {
             code: row.get(1),
             created_at: row.get(2),
             user_id: row.get(3),
+            attempts: row.get(4),
+            resend_count: row.get(5),
+            last_sent_at: row.get(6),
         })
     }
 }
\ No newline at end of file
@@ -15,6 +15,7 @@ pub struct UserCredential {
     pub password: String,
     pub user_id: i32,
     pub created_at: DateTime<Utc>,
+    pub password_change_required: bool,
 }
 
 impl<'c> FromRow<'c, PgRow> for UserCredential {
@@ -25,6 +26,7 @@ impl<'c> FromRow<'c, PgRow> for UserCredential {
             password: row.get(2),
             user_id: row.get(3),
             created_at: row.get(4),
+            password_change_required: row.get(5),
         })
     }
 }
\ No newline at end of file
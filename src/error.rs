@@ -3,23 +3,32 @@ use std::{ fmt::Display, fmt::Formatter, fmt::Result };
 use serde::{Serialize, Deserialize};
 use actix_web::{ error::ResponseError, http::StatusCode, HttpResponse};
 
+use crate::i18n::{Locale, Message};
+
 #[derive(Debug)]
 pub struct AppError {
-    pub message: Option<String>,
+    pub message: Option<Message>,
     pub cause: Option<String>,
     pub error_type: AppErrorType,
 }
 
 impl AppError {
-    pub fn new(message: Option<String>, cause: Option<String>, error_type: AppErrorType) -> Self {
+    pub fn new(message: Option<Message>, cause: Option<String>, error_type: AppErrorType) -> Self {
         AppError { message, cause, error_type }
     }
 
-    fn message(&self) -> String {
-        match &*self {
-            AppError { message: Some(message), cause: _, error_type: _ } => message.clone(),
-            _ => String::from("An unexpected error occurred!"),
-            
+    pub fn localised_message(&self, locale: Locale) -> String {
+        match self {
+            AppError { message: Some(message), cause: _, error_type: _ } => message.render(locale),
+            _ => Message::new("error.unexpected").render(locale),
+        }
+    }
+
+    // the message key doubles as a stable code clients can branch on whatever the locale
+    pub fn response_error(&self, locale: Locale) -> AppResponseError {
+        AppResponseError {
+            error: self.localised_message(locale),
+            code: self.message.as_ref().map(|message| message.key.to_string()),
         }
     }
 }
@@ -29,17 +38,22 @@ pub enum AppErrorType {
     NotFoundError,
     BadRequestError,
     UnAuthorisedError,
+    ForbiddenError,
+    ConflictError,
+    TooManyRequestsError,
     InternalServerError,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AppResponseError {
     pub error: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
 }
 
 impl AppResponseError {
     pub fn new(message: String) -> Self {
-        AppResponseError { error: message }
+        AppResponseError { error: message, code: None }
     }
 }
 
@@ -49,12 +63,15 @@ impl ResponseError for AppError {
             AppErrorType::NotFoundError => StatusCode::NOT_FOUND,
             AppErrorType::BadRequestError => StatusCode::BAD_REQUEST,
             AppErrorType::UnAuthorisedError => StatusCode::UNAUTHORIZED,
+            AppErrorType::ForbiddenError => StatusCode::FORBIDDEN,
+            AppErrorType::ConflictError => StatusCode::CONFLICT,
+            AppErrorType::TooManyRequestsError => StatusCode::TOO_MANY_REQUESTS,
             AppErrorType::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
     fn error_response(&self) -> HttpResponse {
-        HttpResponse::build(self.status_code()).json(AppResponseError::new(self.message()))
+        HttpResponse::build(self.status_code()).json(self.response_error(Locale::default()))
     }
 }
 
@@ -1,64 +1,258 @@
-use actix_web::{post, web::{Path, Data, Json, ServiceConfig}, HttpResponse};
-use log::error;
+use actix_web::{post, rt::time::sleep, web::{Path, Data, ServiceConfig}, HttpRequest, HttpResponse};
+use actix_web_validator::Json;
+use chrono::{DateTime, Duration, Utc};
+use log::{error, warn};
 
-use crate::{error::{AppError, AppErrorType}, jwt, model::{sign_in::SignIn, sign_up::SignUp, token_response::TokenResponse, user::CreateUser, user_credentials::CreateUserCredential}, util, AppState};
+use crate::{auth::JwtAuthenticationGuard, email::{self, account_locked::AccountLockedTemplate, email_confirmation::EmailConfirmationTemplate, registration_attempt::RegistrationAttemptTemplate, welcome::WelcomeTemplate}, entity::user::User, error::{AppError, AppErrorType}, i18n::{Locale, Message}, jwt, model::{account_unlock::UnlockAccount, app_response::AppResponse, refresh_token::RefreshTokenRequest, sign_in::SignIn, sign_out::SignOut, sign_up::SignUp, token_response::TokenResponse, user::CreateUser}, util, AppState};
 
 pub fn init(cfg: &mut ServiceConfig) {
     cfg.service(sign_in);
     cfg.service(sign_up);
+    cfg.service(refresh);
+    cfg.service(sign_out);
+    // must be registered before confirm_email_address, which would otherwise match "resend" as the code
+    cfg.service(resend_confirmation_code);
+    cfg.service(confirm_email_address);
+    cfg.service(unlock_account);
 }
 
 #[post("sign-in")]
-pub async fn sign_in(state: Data<AppState<'_>>, body: Json<SignIn>) -> Result<HttpResponse, AppError> {
-    let SignIn { email_address, password } = body.into_inner();
+pub async fn sign_in(state: Data<AppState<'_>>, req: HttpRequest, body: Json<SignIn>) -> Result<HttpResponse, AppError> {
+    let SignIn { email_address, password, audience } = body.into_inner();
 
-    let user = state.context.users.find_by_email_address(&email_address).await
+    let audience = audience.unwrap_or_else(|| state.jwt_config.audience.clone());
+
+    if !state.jwt_config.is_allowed_audience(&audience) {
+        return Err(AppError::new(Some(Message::new("auth.audience_not_allowed").arg("audience", audience)), None, AppErrorType::BadRequestError));
+    }
+
+    // no stored password is longer than the policy allows, so an oversized one is refused before argon2 has to work through it
+    if password.chars().count() > state.password_policy.max_length {
+        return Err(AppError::new(Some(Message::new("password.too_long").arg("max", state.password_policy.max_length)), None, AppErrorType::BadRequestError));
+    }
+
+    let ip_address = req.connection_info().peer_addr().map(|address| address.to_string());
+
+    if let Some(ip_address) = &ip_address {
+        let ip_lockout = state.context.ip_lockouts.find_by_ip_address(ip_address).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+        })?;
+
+        if ip_lockout.is_some_and(|lockout| lockout.is_locked(Utc::now())) {
+            return Err(AppError::new(Some(Message::new("auth.too_many_attempts")), None, AppErrorType::TooManyRequestsError));
+        }
+    }
+
+    // the lockout follows the submitted address, so a locked address looks the same whether or not it is registered
+    let lockout = state.context.email_lockouts.find_by_email_address(&email_address).await
     .map_err(|error| {
         error!("Error occured: {:?}", error); 
-        match error {
-            sqlx::Error::RowNotFound => AppError::new(Some("Invalid email address/password!".to_string()), None, AppErrorType::UnAuthorisedError),
-            _  => AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+        AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    // a locked address is refused before the password is checked, so guessing cannot continue
+    if let Some(locked_until) = lockout.as_ref().filter(|lockout| lockout.is_locked(Utc::now())).and_then(|lockout| lockout.locked_until) {
+        return Err(account_locked(&locked_until));
+    }
+
+    let user = match state.context.users.find_by_email_address(&email_address).await {
+        Ok(user) => user,
+        Err(sqlx::Error::RowNotFound) => {
+            // an unknown address must cost as much as a wrong password, or timing gives registered addresses away
+            util::verify_dummy_password(&password, &state.argon_config).await?;
+            return Err(reject_sign_in(&state, &email_address, None, ip_address.as_ref()).await);
+        },
+        Err(error) => {
+            error!("Error occured: {:?}", error); 
+            return Err(AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError));
+        }
+    };
+
+    let user_credentials = match state.context.user_credentials.find_by_user_id(&user.user_id).await {
+        Ok(user_credentials) => user_credentials,
+        Err(sqlx::Error::RowNotFound) => {
+            util::verify_dummy_password(&password, &state.argon_config).await?;
+            return Err(reject_sign_in(&state, &email_address, Some(&user), ip_address.as_ref()).await);
+        },
+        Err(error) => {
+            error!("Error occured: {:?}", error); 
+            return Err(AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError));
+        }
+    };
+
+    let is_pass_correct = util::verify_password(&user_credentials.password, &password).await?;
+
+    if !is_pass_correct {
+        return Err(reject_sign_in(&state, &email_address, Some(&user), ip_address.as_ref()).await);
+    }
+
+    // checked only once the password matched, so the account state is not disclosed to anyone guessing
+    ensure_active(&user)?;
+
+    if lockout.is_some() {
+        state.context.email_lockouts.clear(&email_address).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+        })?;
+    }
+
+    issue_tokens(&state, user, None, audience).await
+    .map(|tokens| HttpResponse::Ok().json(tokens))
+}
+
+// counts the failure against the submitted and the client address, then slows the response down progressively;
+// only the owner of a registered address is told about the lockout, the caller gets the same answer either way
+async fn reject_sign_in(state: &AppState<'_>, email_address: &str, user: Option<&User>, ip_address: Option<&String>) -> AppError {
+    let config = &state.account_config;
+    let mut failed_attempts = 0;
+
+    if let Some(ip_address) = ip_address {
+        match state.context.ip_lockouts.record_failure(ip_address, &config.lockout_window, &config.ip_lockout_threshold, &config.lockout_duration).await {
+            Ok(lockout) => failed_attempts = lockout.failed_attempts,
+            Err(error) => {
+                error!("Error occured: {:?}", error); 
+                return AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError);
+            }
+        }
+    }
+
+    let locked_until = match state.context.email_lockouts.record_failure(email_address, &config.lockout_window, &config.lockout_threshold, &config.lockout_duration).await {
+        Ok(lockout) => {
+            failed_attempts = failed_attempts.max(lockout.failed_attempts);
+            lockout.locked_until.filter(|_| lockout.is_locked(Utc::now()))
+        },
+        Err(error) => {
+            error!("Error occured: {:?}", error); 
+            return AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError);
         }
+    };
+
+    sleep(config.sign_in_delay(failed_attempts)).await;
+
+    match (user, locked_until) {
+        (Some(user), Some(locked_until)) => match notify_account_locked(state, user, &locked_until).await {
+            Ok(_) => account_locked(&locked_until),
+            Err(error) => error,
+        },
+        (None, Some(locked_until)) => account_locked(&locked_until),
+        _ => AppError::new(Some(Message::new("auth.invalid_credentials")), None, AppErrorType::UnAuthorisedError),
+    }
+}
+
+fn ensure_active(user: &User) -> Result<(), AppError> {
+    if !user.enabled {
+        return Err(AppError::new(Some(Message::new("auth.account_disabled")), None, AppErrorType::ForbiddenError));
+    }
+
+    if !user.email_confirmed {
+        return Err(AppError::new(Some(Message::new("auth.email_not_confirmed")), None, AppErrorType::ForbiddenError));
+    }
+
+    Ok(())
+}
+
+fn account_locked(locked_until: &DateTime<Utc>) -> AppError {
+    AppError::new(Some(Message::new("auth.account_locked").arg("until", locked_until.format("%Y-%m-%d %H:%M UTC"))), None, AppErrorType::TooManyRequestsError)
+}
+
+async fn notify_account_locked(state: &AppState<'_>, user: &User, locked_until: &DateTime<Utc>) -> Result<(), AppError> {
+    // only the link from the latest lockout should work
+    state.context.account_unlock_tokens.invalidate_by_user_id(&user.user_id).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
     })?;
 
-    let user_credentials = state.context.user_credentials.find_by_user_id(&user.user_id).await
+    let token = util::generate_account_unlock_token().await;
+    let token_hash = util::hash_token(&token).await;
+
+    // the link is pointless once the lockout runs out by itself
+    state.context.account_unlock_tokens.create(&user.user_id, &token_hash, locked_until).await
     .map_err(|error| {
         error!("Error occured: {:?}", error); 
-        match &error {
-            sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23503")) => {
-                AppError::new(Some("Invalid email address/password!".to_string()), None, AppErrorType::UnAuthorisedError)
-            },
-            sqlx::Error::RowNotFound => AppError::new(Some("Invalid email address/password!".to_string()), None, AppErrorType::UnAuthorisedError),
-            _ => AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError),
-        }
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
     })?;
 
-    let user_role = state.context.roles.find_by_id(&user.role_id).await
+    let template = AccountLockedTemplate {
+        recipient: user.first_name.clone(),
+        unlock_link: format!("{}?token={}", state.account_config.account_unlock_url, token),
+        locked_until: locked_until.format("%Y-%m-%d %H:%M UTC").to_string(),
+    };
+    let (details, content) = email::compose(&template, Locale::from_tag(&user.locale).unwrap_or_default(), &user.email_address, &state.account_config.email_from)?;
+
+    state.context.email_outbox.create(&details, &content).await
     .map_err(|error| {
         error!("Error occured: {:?}", error); 
-        AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
     })?;
 
-    let permissions = state.context.role_permissions.find_role_permissions(&user.role_id).await
+    Ok(())
+}
+
+#[post("account/unlock")]
+pub async fn unlock_account(state: Data<AppState<'_>>, body: Json<UnlockAccount>, locale: Locale) -> Result<HttpResponse, AppError> {
+    let token_hash = util::hash_token(&body.into_inner().token).await;
+
+    let unlock_token = state.context.account_unlock_tokens.find_by_token_hash(&token_hash).await
     .map_err(|error| {
         error!("Error occured: {:?}", error); 
-        AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+        match &error {
+            sqlx::Error::RowNotFound => AppError::new(Some(Message::new("auth.unlock_token_invalid")), None, AppErrorType::BadRequestError),
+            _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
+        }
     })?;
 
-    let is_pass_correct = util::verify_password(&user_credentials.password, &password).await?;
+    if unlock_token.used_at.is_some() || unlock_token.expires_at < Utc::now() {
+        return Err(AppError::new(Some(Message::new("auth.unlock_token_invalid")), None, AppErrorType::BadRequestError));
+    }
 
-    if !is_pass_correct {
-        Err(AppError::new(None, Some("Invalid email address/password!".to_string()), AppErrorType::UnAuthorisedError))
-    } else {
-        jwt::generate_token(user, user_role, permissions, &state.jwt_config).await
-        .map(|token| HttpResponse::Ok().json(TokenResponse { token }))
+    let used = state.context.account_unlock_tokens.mark_used(&unlock_token.account_unlock_token_id).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    if used == 0 {
+        return Err(AppError::new(Some(Message::new("auth.unlock_token_invalid")), None, AppErrorType::BadRequestError));
     }
 
+    let user = state.context.users.find_by_id(&unlock_token.user_id).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    state.context.email_lockouts.clear(&user.email_address).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    Ok(HttpResponse::Ok().json(AppResponse::new(locale.message("auth.account_unlocked"))))
 }
 
 #[post("sign-up")]
-pub async fn sign_up(state: Data<AppState<'_>>, body: Json<SignUp>) -> Result<HttpResponse, AppError> {
-    let SignUp { first_name, surname, email_address, password} = body.into_inner();
+pub async fn sign_up(state: Data<AppState<'_>>, body: Json<SignUp>, locale: Locale) -> Result<HttpResponse, AppError> {
+    if !state.account_config.sign_up_enabled {
+        return Err(AppError::new(Some(Message::new("auth.sign_up_disabled")), None, AppErrorType::ForbiddenError));
+    }
+
+    let SignUp { first_name, surname, email_address, password, locale: preferred_locale } = body.into_inner();
+
+    if !state.account_config.is_sign_up_domain_allowed(&email_address) {
+        return Err(AppError::new(Some(Message::new("auth.sign_up_domain_not_allowed")), None, AppErrorType::ForbiddenError));
+    }
+
+    state.password_policy.check(&password, &[&first_name, &surname, &email_address])?;
+
+    let role_id = state.account_config.sign_up_role_id
+        .ok_or_else(|| AppError::new(None, Some(format!("Sign-up role {} has not been resolved!", state.account_config.sign_up_role)), AppErrorType::InternalServerError))?;
+
+    // an explicit preference wins over the negotiated Accept-Language
+    let locale = preferred_locale.as_deref().and_then(Locale::from_tag).unwrap_or(locale);
 
     let create = CreateUser {
         first_name,
@@ -66,34 +260,48 @@ pub async fn sign_up(state: Data<AppState<'_>>, body: Json<SignUp>) -> Result<Ht
         surname,
         email_address: email_address.clone(),
         mobile_number: None,
-        role_id: 1 // TODO - should be for basic user role
+        role_id,
+        locale: Some(locale.as_str().to_string()),
     };
 
-    let user = state.context.users.create(&create).await
-    .map(|user| user)
+    let hashed_password = util::hash_password(&password, &state.argon_config).await?;
+
+    // the user, credential, code and confirmation email are written together or not at all
+    let mut transaction = state.context.begin().await
     .map_err(|error| {
         error!("Error occured: {:?}", error); 
-        match &error {
-            sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23505")) => {
-                AppError::new(Some("Email address already exists!".to_string()), None, AppErrorType::BadRequestError)
-            }
-            _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
-        }
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
     })?;
-    let splits: Vec<&str> = user.email_address.split('@').collect();
-    let username = splits.get(0).ok_or_else(|| AppError::new(None, Some("Email address is invalid!".to_string()), AppErrorType::InternalServerError))?;
 
-    let hashed_password = util::hash_password(&password, &state.argon_config).await?;
+    let user = match state.context.users.create_with(&mut transaction, &create).await {
+        Ok(user) => user,
+        // the caller gets the same answer either way, only the owner of the address hears about the attempt
+        Err(sqlx::Error::Database(d)) if d.constraint() == Some("uq_user_email_address") => {
+            transaction.rollback().await
+            .map_err(|error| {
+                error!("Error occured: {:?}", error); 
+                AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            })?;
+
+            notify_registration_attempt(&state, &email_address).await?;
+
+            return Ok(HttpResponse::Accepted().json(AppResponse::new(locale.message("auth.sign_up_received"))));
+        },
+        Err(error) => {
+            error!("Error occured: {:?}", error); 
+            return Err(AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError));
+        }
+    };
     
-    state.context.user_credentials.create(&user.user_id, &CreateUserCredential{ username: username.to_string(), password: hashed_password }).await
+    state.context.user_credentials.create_for_email_address_with(&mut transaction, &user.user_id, &email_address, &hashed_password).await
     .map_err(|error| {
         error!("Error occured: {:?}", error); 
         match &error {
-            sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23503")) => {
-                AppError::new(Some(format!("User with id {} could not be found!", user.user_id)), None, AppErrorType::NotFoundError)
+            sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23503")) => {
+                AppError::new(Some(Message::new("user.not_found").arg("id", user.user_id)), None, AppErrorType::NotFoundError)
             },
-            sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23505")) => {
-                AppError::new(Some("Credential/username already exists!".to_string()), None, AppErrorType::BadRequestError)
+            sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23505")) => {
+                AppError::new(Some(Message::new("auth.credential_exists")), None, AppErrorType::BadRequestError)
             }
             _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
         }
@@ -101,72 +309,159 @@ pub async fn sign_up(state: Data<AppState<'_>>, body: Json<SignUp>) -> Result<Ht
 
     let code = util::generate_confirmation_code().await;
 
-    state.context.user_code.create(&user.user_id, &code).await
+    let user_code = state.context.user_code.create_with(&mut transaction, &user.user_id, &code).await
     .map_err(|error| {
         error!("Error occured: {:?}", error); 
         match &error {
-            sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23503")) => {
-                AppError::new(Some(format!("User with id {} could not be found!", user.user_id)), None, AppErrorType::NotFoundError)
+            sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23503")) => {
+                AppError::new(Some(Message::new("user.not_found").arg("id", user.user_id)), None, AppErrorType::NotFoundError)
             },
-            sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23505")) => {
-                AppError::new(Some("Credential/username already exists!".to_string()), None, AppErrorType::BadRequestError)
+            sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23505")) => {
+                AppError::new(Some(Message::new("auth.credential_exists")), None, AppErrorType::BadRequestError)
             }
             _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
         }
     })?;
 
-    // TODO - send email to email gateway
+    let template = EmailConfirmationTemplate { code: user_code.code, user_id: user.user_id, recipient: user.first_name.clone() };
+    let (details, content) = email::compose(&template, Locale::from_tag(&user.locale).unwrap_or_default(), &user.email_address, &state.account_config.email_from)?;
 
-    Ok(HttpResponse::Created().json(user))
-    
+    state.context.email_outbox.create_with(&mut transaction, &details, &content).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    transaction.commit().await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    // the account id travels in the confirmation email, returning it here would tell new addresses from registered ones
+    Ok(HttpResponse::Accepted().json(AppResponse::new(locale.message("auth.sign_up_received"))))
+}
+
+async fn notify_registration_attempt(state: &AppState<'_>, email_address: &String) -> Result<(), AppError> {
+    let user = state.context.users.find_by_email_address(email_address).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    let template = RegistrationAttemptTemplate {
+        recipient: user.first_name.clone(),
+        attempted_at: Utc::now().format("%Y-%m-%d %H:%M UTC").to_string(),
+    };
+    let (details, content) = email::compose(&template, Locale::from_tag(&user.locale).unwrap_or_default(), &user.email_address, &state.account_config.email_from)?;
+
+    state.context.email_outbox.create(&details, &content).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    Ok(())
+}
+
+#[post("sign-up/{user_id}/verify/resend")]
+pub async fn resend_confirmation_code(state: Data<AppState<'_>>, path: Path<i32>, locale: Locale) -> Result<HttpResponse, AppError> {
+    let user_id = path.into_inner();
+
+    let user = state.context.users.find_by_id(&user_id).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        match &error {
+            sqlx::Error::RowNotFound => AppError::new(Some(Message::new("user.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError),
+            _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
+        }
+    })?;
+
+    if user.email_confirmed {
+        return Err(AppError::new(Some(Message::new("auth.email_already_confirmed")), None, AppErrorType::BadRequestError));
+    }
+
+    let code = util::generate_confirmation_code().await;
+    let config = &state.account_config;
+
+    let user_code = match state.context.user_code.resend(&user_id, &code, &config.confirmation_code_resend_cooldown, &config.confirmation_code_max_resends).await {
+        Err(sqlx::Error::RowNotFound) => match state.context.user_code.find_by_user_id(&user_id).await {
+            Ok(pending) if pending.resend_count >= config.confirmation_code_max_resends => {
+                return Err(AppError::new(Some(Message::new("auth.confirmation_resend_limit_reached")), None, AppErrorType::TooManyRequestsError));
+            },
+            Ok(pending) => {
+                let wait = Duration::seconds(config.confirmation_code_resend_cooldown.into()) - (Utc::now() - pending.last_sent_at);
+                return Err(AppError::new(Some(Message::new("auth.confirmation_resend_too_soon").arg("seconds", wait.num_seconds().max(1))), None, AppErrorType::TooManyRequestsError));
+            },
+            // users created without signing up have no code yet
+            Err(sqlx::Error::RowNotFound) => state.context.user_code.create(&user_id, &code).await,
+            Err(error) => Err(error),
+        },
+        result => result,
+    }
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    let template = EmailConfirmationTemplate { code: user_code.code, user_id: user.user_id, recipient: user.first_name.clone() };
+    let (details, content) = email::compose(&template, Locale::from_tag(&user.locale).unwrap_or_default(), &user.email_address, &state.account_config.email_from)?;
+
+    state.context.email_outbox.create(&details, &content).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    Ok(HttpResponse::Ok().json(AppResponse::new(locale.message("auth.confirmation_code_sent"))))
 }
 
 #[post("sign-up/{user_id}/verify/{code}")]
 pub async fn confirm_email_address(state: Data<AppState<'_>>, path: Path<(i32, i32)>) -> Result<HttpResponse, AppError> {
-    // find email address and respective code
-    // check that the code has not expired
-    // if expired generate new code and resend
-    // else if code matches
-    // save user to user table and credentials to credentials table
-    // remove from temp_user table
     let (user_id, code) = path.into_inner();
 
-    state.context.user_code.find_by_user_id_and_code(&user_id, &code).await
+    // count the attempt before comparing so parallel guesses cannot slip past the limit
+    let user_code = state.context.user_code.record_attempt(&user_id).await
     .map_err(|error| {
         error!("Error occured: {:?}", error); 
         match &error {
-            sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23503")) => {
-                AppError::new(Some(format!("User with id {} and code {} could not be found!", &user_id, &code)), None, AppErrorType::NotFoundError)
-            },
+            sqlx::Error::RowNotFound => AppError::new(Some(Message::new("auth.no_pending_confirmation").arg("id", user_id)), None, AppErrorType::NotFoundError),
             _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
         }
     })?;
 
+    if user_code.attempts > state.account_config.confirmation_code_max_attempts {
+        return Err(AppError::new(Some(Message::new("auth.confirmation_attempts_exceeded")), None, AppErrorType::BadRequestError));
+    }
+
+    if user_code.created_at + Duration::minutes(state.account_config.confirmation_code_expires_in) < Utc::now() {
+        return Err(AppError::new(Some(Message::new("auth.confirmation_code_expired")), None, AppErrorType::BadRequestError));
+    }
+
+    if user_code.code != code {
+        return Err(AppError::new(Some(Message::new("auth.confirmation_code_invalid")), None, AppErrorType::BadRequestError));
+    }
+
     let mut user = state.context.users.find_by_id(&user_id).await.map_err(|error| {
         error!("Error occured: {:?}", error); 
         match &error {
-            sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23503")) => {
-                AppError::new(Some(format!("User with id {} could not be found!", &user_id)), None, AppErrorType::NotFoundError)
-            },
+            sqlx::Error::RowNotFound => AppError::new(Some(Message::new("user.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError),
             _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
         }
     })?;
 
     if user.email_confirmed {
-        return Err(AppError::new(None, Some(format!("User with id {} already confirmed email!", &user_id)), AppErrorType::BadRequestError));
+        return Err(AppError::new(Some(Message::new("auth.email_already_confirmed")), None, AppErrorType::BadRequestError));
     }
 
     user.email_confirmed = true;
     user.enabled = true;
 
     user = state.context.users.update_user(&user).await
-    .map(|user| user)
     .map_err(|error| {
         error!("Error occured: {:?}", error); 
         match &error {
-            sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23503")) => {
-                AppError::new(Some(format!("User with id {} could not be found!", &user_id)), None, AppErrorType::NotFoundError)
-            },
+            sqlx::Error::RowNotFound => AppError::new(Some(Message::new("user.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError),
             _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
         }
     })?;
@@ -177,20 +472,158 @@ pub async fn confirm_email_address(state: Data<AppState<'_>>, path: Path<(i32, i
         AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
     })?;
 
-    // generate json web token
+    let template = WelcomeTemplate { recipient: user.first_name.clone() };
+    let (details, content) = email::compose(&template, Locale::from_tag(&user.locale).unwrap_or_default(), &user.email_address, &state.account_config.email_from)?;
+
+    state.context.email_outbox.create(&details, &content).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    issue_tokens(&state, user, None, state.jwt_config.audience.clone()).await
+    .map(|tokens| HttpResponse::Ok().json(tokens))
+}
+
+#[post("refresh")]
+pub async fn refresh(state: Data<AppState<'_>>, body: Json<RefreshTokenRequest>) -> Result<HttpResponse, AppError> {
+    let RefreshTokenRequest { refresh_token } = body.into_inner();
+
+    let token_hash = util::hash_token(&refresh_token).await;
+
+    let refresh_token = state.context.refresh_tokens.find_by_token_hash(&token_hash).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        match error {
+            sqlx::Error::RowNotFound => AppError::new(Some(Message::new("auth.invalid_refresh_token")), None, AppErrorType::UnAuthorisedError),
+            _  => AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+        }
+    })?;
+
+    if refresh_token.revoked {
+        warn!("Refresh token reuse detected for user with id {}. Revoking token family.", refresh_token.user_id);
+        return Err(revoke_token_family(&state, &refresh_token.family_id).await);
+    }
+
+    if refresh_token.expires_at < Utc::now() {
+        return Err(AppError::new(Some(Message::new("auth.refresh_token_expired")), None, AppErrorType::UnAuthorisedError));
+    }
+
+    let rotated = state.context.refresh_tokens.revoke(&refresh_token.refresh_token_id).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    // another request rotated the same token first
+    if rotated == 0 {
+        warn!("Refresh token reuse detected for user with id {}. Revoking token family.", refresh_token.user_id);
+        return Err(revoke_token_family(&state, &refresh_token.family_id).await);
+    }
+
+    let user = state.context.users.find_by_id(&refresh_token.user_id).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        match error {
+            sqlx::Error::RowNotFound => AppError::new(Some(Message::new("auth.invalid_refresh_token")), None, AppErrorType::UnAuthorisedError),
+            _  => AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+        }
+    })?;
+
+    // refresh tokens outlive a status change when revoking on status change is turned off
+    ensure_active(&user)?;
+
+    let audience = refresh_token.audience.unwrap_or_else(|| state.jwt_config.audience.clone());
 
+    issue_tokens(&state, user, Some(refresh_token.family_id), audience).await
+    .map(|tokens| HttpResponse::Ok().json(tokens))
+}
+
+#[post("sign-out")]
+pub async fn sign_out(state: Data<AppState<'_>>, body: Option<Json<SignOut>>, auth: JwtAuthenticationGuard, locale: Locale) -> Result<HttpResponse, AppError> {
+    state.revocation_store.revoke_token(&state.context, &auth.id, &auth.claims.jti, &auth.claims.exp).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    if let Some(SignOut { refresh_token: Some(refresh_token) }) = body.map(|body| body.into_inner()) {
+        let token_hash = util::hash_token(&refresh_token).await;
+
+        match state.context.refresh_tokens.find_by_token_hash(&token_hash).await {
+            Ok(refresh_token) if refresh_token.user_id == auth.id => {
+                state.context.refresh_tokens.revoke_family(&refresh_token.family_id).await
+                .map_err(|error| {
+                    error!("Error occured: {:?}", error); 
+                    AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+                })?;
+            },
+            Ok(_) | Err(sqlx::Error::RowNotFound) => {},
+            Err(error) => {
+                error!("Error occured: {:?}", error); 
+                return Err(AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError));
+            }
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(AppResponse::new(locale.message("auth.signed_out"))))
+}
+
+async fn revoke_token_family(state: &AppState<'_>, family_id: &String) -> AppError {
+    match state.context.refresh_tokens.revoke_family(family_id).await {
+        Ok(_) => AppError::new(Some(Message::new("auth.invalid_refresh_token")), None, AppErrorType::UnAuthorisedError),
+        Err(error) => {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+        }
+    }
+}
+
+async fn issue_tokens(state: &AppState<'_>, user: User, family_id: Option<String>, audience: String) -> Result<TokenResponse, AppError> {
     let user_role = state.context.roles.find_by_id(&user.role_id).await
     .map_err(|error| {
         error!("Error occured: {:?}", error); 
         AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
     })?;
 
-    let permissions = state.context.role_permissions.find_role_permissions(&user.role_id).await
+    let password_change_required = state.context.user_credentials.is_password_change_required(&user.user_id).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    // until the password is changed the token only lets the user update their own credential
+    let permissions = match password_change_required {
+        true => vec![],
+        false => state.context.role_permissions.find_role_permissions(&user.role_id).await
+            .map_err(|error| {
+                error!("Error occured: {:?}", error); 
+                AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+            })?,
+    };
+
+    let user_id = user.user_id;
+    let token = jwt::generate_token(user, user_role, permissions, &audience, &state.jwt_config).await?;
+
+    let family_id = match family_id {
+        Some(family_id) => family_id,
+        None => util::generate_token_family().await,
+    };
+    let refresh_token = util::generate_refresh_token().await;
+    let token_hash = util::hash_token(&refresh_token).await;
+    let expires_at = Utc::now() + Duration::minutes(state.jwt_config.refresh_expires_in);
+
+    state.context.refresh_tokens.create(&user_id, &token_hash, &family_id, &audience, &expires_at).await
     .map_err(|error| {
         error!("Error occured: {:?}", error); 
         AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
     })?;
 
-    jwt::generate_token(user, user_role, permissions, &state.jwt_config).await
-    .map(|token| HttpResponse::Ok().json(TokenResponse { token }))
+    Ok(TokenResponse {
+        token,
+        refresh_token,
+        expires_in: state.jwt_config.expires_in * 60,
+        token_type: "Bearer".to_string(),
+        password_change_required,
+    })
 }
\ No newline at end of file
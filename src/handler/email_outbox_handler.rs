@@ -0,0 +1,39 @@
+use actix_web::{ get, post, web::{ Data, Path, Query, ServiceConfig }, HttpResponse };
+use log::{error, info};
+
+use crate::{ auth::PermissionGuard, entity::email_outbox::STATUS_DEAD, error::{AppError, AppErrorType}, i18n::Message, model::email_outbox::EmailOutboxQuery, permissions::{EmailRead, EmailUpdate}, AppState };
+
+pub fn init(cfg: &mut ServiceConfig) {
+    cfg.service(get_email_outbox);
+    cfg.service(retry_email);
+}
+
+#[get("email-outbox")]
+pub async fn get_email_outbox(state: Data<AppState<'_>>, query: Query<EmailOutboxQuery>, _: PermissionGuard<EmailRead>) -> Result<HttpResponse, AppError> {
+    let status = query.into_inner().status.unwrap_or_else(|| STATUS_DEAD.to_string()).to_uppercase();
+
+    state.context.email_outbox.find_by_status(&status).await
+        .map(|emails| HttpResponse::Ok().json(emails))
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+        })
+}
+
+#[post("email-outbox/{email_outbox_id}/retry")]
+pub async fn retry_email(state: Data<AppState<'_>>, path: Path<i32>, guard: PermissionGuard<EmailUpdate>) -> Result<HttpResponse, AppError> {
+    let email_outbox_id = path.into_inner();
+
+    state.context.email_outbox.retry(&email_outbox_id).await
+        .map(|email| {
+            info!("User with id {} requeued email {}", guard.auth.id, email_outbox_id);
+            HttpResponse::Ok().json(email)
+        })
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            match error {
+                sqlx::Error::RowNotFound => AppError::new(Some(Message::new("email.outbox_not_found").arg("id", email_outbox_id)), None, AppErrorType::NotFoundError),
+                _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
+            }
+        })
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+use actix_web::{ get, web::{ Path, Query, ServiceConfig }, HttpResponse };
+
+use crate::{ auth::PermissionGuard, email::catalogue, error::{AppError, AppErrorType}, i18n::{Locale, Message}, model::email_template::{EmailTemplatePreviewQuery, EmailTemplateSummary}, permissions::EmailRead };
+
+pub fn init(cfg: &mut ServiceConfig) {
+    cfg.service(get_email_templates);
+    cfg.service(preview_email_template);
+}
+
+#[get("email-templates")]
+pub async fn get_email_templates(_: PermissionGuard<EmailRead>, locale: Locale) -> Result<HttpResponse, AppError> {
+    let templates = catalogue::TEMPLATES.iter()
+        .filter_map(|name| catalogue::sample(name).map(|template| EmailTemplateSummary {
+            name: name.to_string(),
+            subject: template.subject(locale).to_string(),
+        }))
+        .collect::<Vec<EmailTemplateSummary>>();
+
+    Ok(HttpResponse::Ok().json(templates))
+}
+
+#[get("email-templates/{name}/preview")]
+pub async fn preview_email_template(path: Path<String>, query: Query<EmailTemplatePreviewQuery>, _: PermissionGuard<EmailRead>, locale: Locale) -> Result<HttpResponse, AppError> {
+    let name = path.into_inner();
+    let EmailTemplatePreviewQuery { format, locale: requested_locale } = query.into_inner();
+    let format = format.unwrap_or_else(|| "html".to_string()).to_lowercase();
+    let locale = requested_locale.as_deref().and_then(Locale::from_tag).unwrap_or(locale);
+
+    let template = catalogue::sample(&name)
+        .ok_or_else(|| AppError::new(Some(Message::new("email.template_not_found").arg("name", name)), None, AppErrorType::NotFoundError))?;
+
+    let content = template.render(locale)?;
+
+    match format.as_str() {
+        "html" => Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(content.html)),
+        "text" => Ok(HttpResponse::Ok().content_type("text/plain; charset=utf-8").body(content.text)),
+        _ => Err(AppError::new(Some(Message::new("email.preview_format_not_supported").arg("format", &format)), None, AppErrorType::BadRequestError)),
+    }
+}
\ No newline at end of file
@@ -0,0 +1,16 @@
+use actix_web::{get, web::{Data, ServiceConfig}, HttpResponse};
+
+use crate::{error::AppError, model::jwk::JwkSet, AppState};
+
+pub fn init(cfg: &mut ServiceConfig) {
+    cfg.service(get_jwks);
+}
+
+#[get(".well-known/jwks.json")]
+pub async fn get_jwks(state: Data<AppState<'_>>) -> Result<HttpResponse, AppError> {
+    let keys = state.jwt_config.keyring().keys()
+        .filter_map(|key| key.public_jwk.clone())
+        .collect();
+
+    Ok(HttpResponse::Ok().json(JwkSet { keys }))
+}
\ No newline at end of file
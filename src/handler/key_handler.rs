@@ -0,0 +1,69 @@
+use actix_web::{ delete, get, post, web::{ Data, Json, Path, Query, ServiceConfig }, HttpResponse };
+use chrono::{Duration, Utc};
+use log::info;
+
+use crate::{ auth::PermissionGuard, error::AppError, i18n::Locale, model::{app_response::AppResponse, signing_key::{PromoteSigningKey, RetireSigningKeyQuery, SigningKey}}, permissions::{KeyRead, KeyUpdate}, AppState };
+
+pub fn init(cfg: &mut ServiceConfig) {
+    cfg.service(get_keys);
+    cfg.service(reload_keys);
+    cfg.service(promote_key);
+    cfg.service(retire_key);
+}
+
+#[get("keys")]
+pub async fn get_keys(state: Data<AppState<'_>>, _: PermissionGuard<KeyRead>) -> Result<HttpResponse, AppError> {
+    let keyring = state.jwt_config.keyring();
+
+    let keys: Vec<SigningKey> = keyring.keys()
+        .map(|key| SigningKey {
+            kid: key.kid.clone(),
+            algorithm: format!("{:?}", key.algorithm),
+            active: keyring.is_active(&key.kid),
+            can_sign: key.encoding_key.is_some(),
+            activate_at: key.activate_at,
+            retire_at: key.retire_at,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(keys))
+}
+
+#[post("keys/reload")]
+pub async fn reload_keys(state: Data<AppState<'_>>, _: PermissionGuard<KeyUpdate>, locale: Locale) -> Result<HttpResponse, AppError> {
+    let added = state.jwt_config.reload_keys()?;
+    info!("Loaded {} new signing key(s)", added);
+
+    Ok(HttpResponse::Ok().json(AppResponse::new(locale.message("key.reloaded"))))
+}
+
+// rotations are stored in the database, every server applies them once they are due
+#[post("keys/{kid}/promote")]
+pub async fn promote_key(state: Data<AppState<'_>>, path: Path<String>, body: Option<Json<PromoteSigningKey>>, _: PermissionGuard<KeyUpdate>, locale: Locale) -> Result<HttpResponse, AppError> {
+    let kid = path.into_inner();
+    let body = body.map(|body| body.into_inner());
+
+    let activate_at = body.as_ref()
+        .and_then(|body| body.activate_at)
+        .unwrap_or_else(Utc::now);
+    // by default the previous key verifies tokens until the last one it signed has expired
+    let retire_previous_after = body
+        .and_then(|body| body.retire_previous_after)
+        .unwrap_or(state.jwt_config.expires_in);
+
+    state.jwt_config.promote_key(&state.context, &kid, activate_at, Duration::minutes(retire_previous_after)).await?;
+    info!("Promoted signing key {} from {}", kid, activate_at);
+
+    Ok(HttpResponse::Ok().json(AppResponse::new(locale.message("key.promoted"))))
+}
+
+#[delete("keys/{kid}")]
+pub async fn retire_key(state: Data<AppState<'_>>, path: Path<String>, query: Query<RetireSigningKeyQuery>, _: PermissionGuard<KeyUpdate>, locale: Locale) -> Result<HttpResponse, AppError> {
+    let kid = path.into_inner();
+    let retire_at = query.retire_at.unwrap_or_else(Utc::now);
+
+    state.jwt_config.retire_key(&state.context, &kid, retire_at).await?;
+    info!("Retired signing key {} from {}", kid, retire_at);
+
+    Ok(HttpResponse::Ok().json(AppResponse::new(locale.message("key.retired"))))
+}
\ No newline at end of file
@@ -0,0 +1,76 @@
+use actix_web::{ get, post, web::{ Data, Path, ServiceConfig }, HttpResponse };
+use log::error;
+use sqlx::Error::RowNotFound;
+use crate::{ auth::PermissionGuard, error::{AppError, AppErrorType}, i18n::{Locale, Message}, model::app_response::AppResponse, permissions::{UserRead, UserUpdate}, AppState };
+
+pub fn init(cfg: &mut ServiceConfig) {
+    cfg.service(get_user_lockout);
+    cfg.service(unlock_user);
+    cfg.service(unlock_ip_address);
+}
+
+#[get("users/{user_id}/lockout")]
+pub async fn get_user_lockout(state: Data<AppState<'_>>, path: Path<i32>, _: PermissionGuard<UserRead>) -> Result<HttpResponse , AppError> {
+    let user_id = path.into_inner();
+
+    let user = state.context.users.find_by_id(&user_id).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            match error {
+                RowNotFound => AppError::new(Some(Message::new("user.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError),
+                _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            }
+        })?;
+
+    state.context.email_lockouts.find_by_email_address(&user.email_address).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+        })?
+        .map(|lockout| HttpResponse::Ok().json(lockout))
+        .ok_or_else(|| AppError::new(Some(Message::new("lockout.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError))
+}
+
+#[post("users/{user_id}/unlock")]
+pub async fn unlock_user(state: Data<AppState<'_>>, path: Path<i32>, _: PermissionGuard<UserUpdate>, locale: Locale) -> Result<HttpResponse , AppError> {
+    let user_id = path.into_inner();
+
+    let user = state.context.users.find_by_id(&user_id).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            match error {
+                RowNotFound => AppError::new(Some(Message::new("user.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError),
+                _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            }
+        })?;
+
+    state.context.email_lockouts.clear(&user.email_address).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+        })?;
+
+    // links emailed for the lockout are no longer needed
+    state.context.account_unlock_tokens.invalidate_by_user_id(&user_id).await
+        .map(|_| HttpResponse::Ok().json(AppResponse::new(locale.message("lockout.user_unlocked"))))
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+        })
+}
+
+#[post("ip-lockouts/{ip_address}/unlock")]
+pub async fn unlock_ip_address(state: Data<AppState<'_>>, path: Path<String>, _: PermissionGuard<UserUpdate>, locale: Locale) -> Result<HttpResponse , AppError> {
+    let ip_address = path.into_inner();
+
+    let cleared = state.context.ip_lockouts.clear(&ip_address).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+        })?;
+
+    match cleared {
+        0 => Err(AppError::new(Some(Message::new("lockout.ip_not_found").arg("ip", ip_address)), None, AppErrorType::NotFoundError)),
+        _ => Ok(HttpResponse::Ok().json(AppResponse::new(locale.message("lockout.ip_unlocked")))),
+    }
+}
\ No newline at end of file
@@ -2,8 +2,20 @@ pub mod permission_handler;
 pub mod role_handler;
 pub mod user_handler;
 pub mod auth_handler;
+pub mod jwks_handler;
+pub mod key_handler;
+pub mod password_handler;
+pub mod email_outbox_handler;
+pub mod email_template_handler;
+pub mod lockout_handler;
 
 pub use permission_handler::init as init_permission_handler;
 pub use role_handler::init as init_role_handler;
 pub use user_handler::init as init_user_handler;
-pub use auth_handler::init as init_auth_handler;
\ No newline at end of file
+pub use auth_handler::init as init_auth_handler;
+pub use jwks_handler::init as init_jwks_handler;
+pub use key_handler::init as init_key_handler;
+pub use password_handler::init as init_password_handler;
+pub use email_outbox_handler::init as init_email_outbox_handler;
+pub use email_template_handler::init as init_email_template_handler;
+pub use lockout_handler::init as init_lockout_handler;
\ No newline at end of file
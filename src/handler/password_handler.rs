@@ -0,0 +1,154 @@
+use actix_web::{post, web::{Data, ServiceConfig}, HttpResponse};
+use actix_web_validator::Json;
+use chrono::{Duration, Utc};
+use log::{error, warn};
+
+use crate::{email::{self, password_changed::PasswordChangedTemplate, password_reset::PasswordResetTemplate, password_reset_unknown::PasswordResetUnknownTemplate}, error::{AppError, AppErrorType}, i18n::{Locale, Message}, model::{app_response::AppResponse, password_reset::{ForgotPassword, ResetPassword}}, util, AppState};
+
+pub fn init(cfg: &mut ServiceConfig) {
+    cfg.service(forgot_password);
+    cfg.service(reset_password);
+}
+
+#[post("password/forgot")]
+pub async fn forgot_password(state: Data<AppState<'_>>, body: Json<ForgotPassword>, locale: Locale) -> Result<HttpResponse, AppError> {
+    let ForgotPassword { email_address } = body.into_inner();
+
+    // respond the same way whether or not the email address is registered
+    let response = HttpResponse::Ok().json(AppResponse::new(locale.message("password.reset_requested")));
+
+    let user = match state.context.users.find_by_email_address(&email_address).await {
+        Ok(user) => Some(user),
+        Err(sqlx::Error::RowNotFound) => {
+            warn!("Password reset requested for an unknown email address");
+            None
+        },
+        Err(error) => {
+            error!("Error occured: {:?}", error); 
+            return Err(AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError));
+        }
+    };
+
+    // unknown addresses get a token nobody receives and an email of their own, so both paths cost the same
+    let token = util::generate_password_reset_token().await;
+    let token_hash = util::hash_token(&token).await;
+    let expires_at = Utc::now() + Duration::minutes(state.account_config.password_reset_expires_in);
+    let cooldown = state.account_config.password_reset_cooldown;
+
+    // the cooldown follows the submitted address, so it looks the same whether or not the address is registered
+    let reset_token = match state.context.password_reset_tokens.create(user.as_ref().map(|user| &user.user_id), &email_address, &token_hash, &expires_at, &cooldown).await {
+        Err(sqlx::Error::RowNotFound) => match state.context.password_reset_tokens.find_latest_by_email_address(&email_address).await {
+            Ok(latest) => {
+                let wait = Duration::seconds(cooldown.into()) - (Utc::now() - latest.created_at);
+                return Err(AppError::new(Some(Message::new("password.reset_too_soon").arg("seconds", wait.num_seconds().max(1))), None, AppErrorType::TooManyRequestsError));
+            },
+            Err(error) => Err(error),
+        },
+        result => result,
+    }
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    state.context.password_reset_tokens.invalidate_others(&reset_token).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    let (details, content) = match &user {
+        Some(user) => {
+            let template = PasswordResetTemplate {
+                link: format!("{}?token={}", state.account_config.password_reset_url, token),
+                recipient: user.first_name.clone(),
+                expires_in: state.account_config.password_reset_expires_in,
+            };
+            email::compose(&template, Locale::from_tag(&user.locale).unwrap_or_default(), &user.email_address, &state.account_config.email_from)?
+        },
+        None => {
+            let template = PasswordResetUnknownTemplate { recipient: email_address.clone() };
+            email::compose(&template, locale, &email_address, &state.account_config.email_from)?
+        },
+    };
+
+    state.context.email_outbox.create(&details, &content).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    Ok(response)
+}
+
+#[post("password/reset")]
+pub async fn reset_password(state: Data<AppState<'_>>, body: Json<ResetPassword>, locale: Locale) -> Result<HttpResponse, AppError> {
+    let ResetPassword { token, password } = body.into_inner();
+
+    let token_hash = util::hash_token(&token).await;
+
+    let reset_token = state.context.password_reset_tokens.find_by_token_hash(&token_hash).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        match &error {
+            sqlx::Error::RowNotFound => AppError::new(Some(Message::new("password.reset_token_invalid")), None, AppErrorType::BadRequestError),
+            _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
+        }
+    })?;
+
+    let user_id = match reset_token.user_id {
+        Some(user_id) if reset_token.used_at.is_none() && reset_token.expires_at >= Utc::now() => user_id,
+        _ => return Err(AppError::new(Some(Message::new("password.reset_token_invalid")), None, AppErrorType::BadRequestError)),
+    };
+
+    let user = state.context.users.find_by_id(&user_id).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    // checked before the token is used up so a rejected password can be retried with the same link
+    state.password_policy.check(&password, &[&user.first_name, &user.surname, &user.email_address])?;
+
+    // a concurrent request may have used the token between the lookup and here
+    let used = state.context.password_reset_tokens.mark_used(&reset_token.password_reset_token_id).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    if used == 0 {
+        return Err(AppError::new(Some(Message::new("password.reset_token_invalid")), None, AppErrorType::BadRequestError));
+    }
+
+    let hashed_password = util::hash_password(&password, &state.argon_config).await?;
+
+    state.context.user_credentials.update_password(&user_id, &hashed_password).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        match &error {
+            sqlx::Error::RowNotFound => AppError::new(Some(Message::new("credential.not_found")), None, AppErrorType::NotFoundError),
+            _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
+        }
+    })?;
+
+    state.revocation_store.revoke_user(&state.context, &user_id).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some("Service unavailable try again later!".to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    let template = PasswordChangedTemplate {
+        recipient: user.first_name.clone(),
+        changed_at: Utc::now().format("%Y-%m-%d %H:%M UTC").to_string(),
+    };
+    let (details, content) = email::compose(&template, Locale::from_tag(&user.locale).unwrap_or_default(), &user.email_address, &state.account_config.email_from)?;
+
+    state.context.email_outbox.create(&details, &content).await
+    .map_err(|error| {
+        error!("Error occured: {:?}", error); 
+        AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    })?;
+
+    Ok(HttpResponse::Ok().json(AppResponse::new(locale.message("password.reset"))))
+}
\ No newline at end of file
@@ -1,7 +1,7 @@
-use actix_web::{ delete, get, post, web::{ Data, Path, ServiceConfig, Query }, HttpResponse };
+use actix_web::{ delete, get, patch, post, put, web::{ Data, Path, ServiceConfig, Query }, HttpResponse };
 use log::error;
 use sqlx::Error::RowNotFound;
-use crate::{ auth::JwtAuthenticationGuard, entity::permission::CreatePermission, error::{AppError, AppErrorType, AppResponseError}, model::{app_response::AppResponse, pagination::PaginationRequest}, AppState };
+use crate::{ auth::PermissionGuard, entity::permission::{CreatePermission, PatchPermission, UpdatePermission}, error::{AppError, AppErrorType, AppResponseError}, i18n::{Locale, Message}, model::{app_response::AppResponse, deletion::{DeletePermissionQuery, Deletion, DeletionConflict}, pagination::PaginationRequest}, permissions::{PermissionDelete, PermissionRead, PermissionUpdate, PermissionWrite}, AppState };
 use actix_web_validator::Json;
 
 pub fn init(cfg: &mut ServiceConfig) {
@@ -9,11 +9,13 @@ pub fn init(cfg: &mut ServiceConfig) {
     cfg.service(get_permission_by_id);
     cfg.service(get_permissions_paginated);
     cfg.service(create_permission);
+    cfg.service(update_permission);
+    cfg.service(patch_permission);
     cfg.service(delete_permission_with_id);
 }
 
 #[get("permissions")]
-pub async fn get_permissions(state: Data<AppState<'_>>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+pub async fn get_permissions(state: Data<AppState<'_>>, _: PermissionGuard<PermissionRead>) -> Result<HttpResponse , AppError> {
     match state.context.permissions.find_all().await {
         Ok(permissions) => Ok(HttpResponse::Ok().json(permissions)),
         Err(error) => {
@@ -24,7 +26,7 @@ pub async fn get_permissions(state: Data<AppState<'_>>, _: JwtAuthenticationGuar
 }
 
 #[get("permissions-paginated")]
-pub async fn get_permissions_paginated(state: Data<AppState<'_>>, pagination: Query<PaginationRequest>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+pub async fn get_permissions_paginated(state: Data<AppState<'_>>, pagination: Query<PaginationRequest>, _: PermissionGuard<PermissionRead>) -> Result<HttpResponse , AppError> {
     state.context.permissions.find_paginated(pagination.page, pagination.page_size).await
         .map(|results| HttpResponse::Ok().json(results))
         .map_err(|e| {
@@ -34,45 +36,90 @@ pub async fn get_permissions_paginated(state: Data<AppState<'_>>, pagination: Qu
 }
 
 #[get("permissions/{permission_id}")]
-pub async fn get_permission_by_id(state: Data<AppState<'_>>, path: Path<i16>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+pub async fn get_permission_by_id(state: Data<AppState<'_>>, path: Path<i16>, _: PermissionGuard<PermissionRead>) -> Result<HttpResponse , AppError> {
     let permission_id = path.into_inner();
     state.context.permissions.find_by_id(&permission_id).await
         .map(|permission| HttpResponse::Ok().json(permission))
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
             match error {
-                RowNotFound => AppError::new(Some(format!("Permission with id {} could not be found!", permission_id)), None, AppErrorType::NotFoundError),
+                RowNotFound => AppError::new(Some(Message::new("permission.not_found").arg("id", permission_id)), None, AppErrorType::NotFoundError),
                 _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
             }
         })
 }
 
 #[post("permissions")]
-pub async fn create_permission(state: Data<AppState<'_>>, body: Json<CreatePermission>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError>  {
+pub async fn create_permission(state: Data<AppState<'_>>, body: Json<CreatePermission>, _: PermissionGuard<PermissionWrite>) -> Result<HttpResponse , AppError>  {
     state.context.permissions.create(&body.into_inner()).await
         .map(|permission| HttpResponse::Created().json(permission))
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
             match &error {
-                sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23505")) => {
-                    AppError::new(Some("Permission already exists!".to_string()), None, AppErrorType::BadRequestError)
+                sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23505")) => {
+                    AppError::new(Some(Message::new("permission.exists")), None, AppErrorType::BadRequestError)
                 }
                 _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
             }
         })
 }
 
+#[put("permissions/{permission_id}")]
+pub async fn update_permission(state: Data<AppState<'_>>, path: Path<i16>, body: Json<UpdatePermission>, _: PermissionGuard<PermissionUpdate>) -> Result<HttpResponse , AppError>  {
+    let permission_id = path.into_inner();
+    ensure_renamable(&state, permission_id, Some(&body.name)).await?;
+
+    state.context.permissions.update(&permission_id, &body.into_inner()).await
+        .map(|permission| HttpResponse::Ok().json(permission))
+        .map_err(|error| map_update_error(error, permission_id))
+}
+
+#[patch("permissions/{permission_id}")]
+pub async fn patch_permission(state: Data<AppState<'_>>, path: Path<i16>, body: Json<PatchPermission>, _: PermissionGuard<PermissionUpdate>) -> Result<HttpResponse , AppError>  {
+    let permission_id = path.into_inner();
+    ensure_renamable(&state, permission_id, body.name.as_deref()).await?;
+
+    state.context.permissions.patch(&permission_id, &body.into_inner()).await
+        .map(|permission| HttpResponse::Ok().json(permission))
+        .map_err(|error| map_update_error(error, permission_id))
+}
+
+// system permissions keep their name so the guards depending on it keep working
+async fn ensure_renamable(state: &AppState<'_>, permission_id: i16, name: Option<&str>) -> Result<(), AppError> {
+    let permission = state.context.permissions.find_by_id(&permission_id).await
+        .map_err(|error| map_update_error(error, permission_id))?;
+
+    if permission.is_system && name.is_some_and(|name| name != permission.name) {
+        return Err(AppError::new(Some(Message::new("permission.system").arg("id", permission_id)), None, AppErrorType::ForbiddenError));
+    }
+
+    Ok(())
+}
+
+fn map_update_error(error: sqlx::Error, permission_id: i16) -> AppError {
+    error!("Error occured: {:?}", error); 
+    match &error {
+        RowNotFound => AppError::new(Some(Message::new("permission.not_found").arg("id", permission_id)), None, AppErrorType::NotFoundError),
+        sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23505")) => {
+            AppError::new(Some(Message::new("permission.exists")), None, AppErrorType::ConflictError)
+        }
+        _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    }
+}
+
 #[delete("permissions/{permission_id}")]
-pub async fn delete_permission_with_id(state: Data<AppState<'_>>, path: Path<i16>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+pub async fn delete_permission_with_id(state: Data<AppState<'_>>, path: Path<i16>, query: Query<DeletePermissionQuery>, _: PermissionGuard<PermissionDelete>, locale: Locale) -> Result<HttpResponse , AppError> {
     let permission_id = path.into_inner();
     
-    state.context.permissions.delete(&permission_id).await
-        .map(|result| {
-            if result.rows_affected() == 0 {
-                HttpResponse::NotFound().json(AppResponseError::new(format!("Permission with id {} could not be found!", permission_id)))
-            } else {
-                HttpResponse::Ok().json(AppResponse::new("Permission deleted successfully."))
-            }
+    state.context.permissions.delete(&permission_id, &query).await
+        .map(|result| match result {
+            Deletion::Deleted => HttpResponse::Ok().json(AppResponse::new(locale.message("permission.deleted"))),
+            Deletion::NotFound => HttpResponse::NotFound().json(AppResponseError::new(Message::new("permission.not_found").arg("id", permission_id).render(locale))),
+            Deletion::Protected => HttpResponse::Forbidden().json(AppResponseError::new(Message::new("permission.system").arg("id", permission_id).render(locale))),
+            Deletion::InUse(dependants) => HttpResponse::Conflict().json(DeletionConflict {
+                error: Message::new("permission.in_use").arg("id", permission_id).render(locale),
+                dependants,
+            }),
         })
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
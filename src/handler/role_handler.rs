@@ -1,19 +1,25 @@
-use actix_web::{ delete, get, post, web::{ Data, Path, ServiceConfig, Query, Json }, HttpResponse };
+use actix_web::{ delete, get, patch, post, put, web::{ Data, Path, ServiceConfig, Query }, HttpResponse };
+use actix_web_validator::Json;
 use log::error;
 
-use crate::{auth::JwtAuthenticationGuard, entity::role::CreateRole, error::{AppError, AppErrorType, AppResponseError}, model::{app_response::AppResponse, pagination::PaginationRequest}, AppState};
+use crate::{auth::{JwtAuthenticationGuard, PermissionGuard, RequiredPermission}, entity::role::{CreateRole, PatchRole, UpdateRole}, error::{AppError, AppErrorType, AppResponseError}, i18n::{Locale, Message}, model::{app_response::AppResponse, deletion::{DeleteRoleQuery, Deletion, DeletionConflict}, pagination::PaginationRequest, role_permission::{RolePermissionChanges, UpdateRolePermissions}}, permissions::{RoleDelete, RoleRead, RoleUpdate, RoleWrite, UserUpdate}, AppState};
 
 pub fn init(cfg: &mut ServiceConfig) {
     cfg.service(get_roles);
     cfg.service(get_role_by_id);
     cfg.service(get_roles_paginated);
     cfg.service(create_role);
+    cfg.service(update_role);
+    cfg.service(patch_role);
     cfg.service(delete_role_with_id);
     cfg.service(get_role_permissions);
+    cfg.service(update_role_permissions);
+    cfg.service(add_role_permission);
+    cfg.service(remove_role_permission);
 }
 
 #[get("roles")]
-pub async fn get_roles(state: Data<AppState<'_>>, _: JwtAuthenticationGuard) -> Result<HttpResponse, AppError> {
+pub async fn get_roles(state: Data<AppState<'_>>, _: PermissionGuard<RoleRead>) -> Result<HttpResponse, AppError> {
     match state.context.roles.find_all().await {
         Ok(roles) => Ok(HttpResponse::Ok().json(roles)),
         Err(error) => {
@@ -24,7 +30,7 @@ pub async fn get_roles(state: Data<AppState<'_>>, _: JwtAuthenticationGuard) ->
 }
 
 #[get("roles-paginated")]
-pub async fn get_roles_paginated(state: Data<AppState<'_>>, pagination: Query<PaginationRequest>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+pub async fn get_roles_paginated(state: Data<AppState<'_>>, pagination: Query<PaginationRequest>, _: PermissionGuard<RoleRead>) -> Result<HttpResponse , AppError> {
     state.context.roles.find_paginated(pagination.page, pagination.page_size).await
         .map(|results| HttpResponse::Ok().json(results))
         .map_err(|e| {
@@ -34,62 +40,262 @@ pub async fn get_roles_paginated(state: Data<AppState<'_>>, pagination: Query<Pa
 }
 
 #[get("roles/{role_id}")]
-pub async fn get_role_by_id(state: Data<AppState<'_>>, path: Path<i16>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+pub async fn get_role_by_id(state: Data<AppState<'_>>, path: Path<i16>, _: PermissionGuard<RoleRead>) -> Result<HttpResponse , AppError> {
     let role_id = path.into_inner();
     state.context.roles.find_by_id(&role_id).await
         .map(|role| HttpResponse::Ok().json(role))
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
             match error {
-                sqlx::Error::RowNotFound => AppError::new(Some(format!("Role with id {} could not be found!", role_id)), None, AppErrorType::NotFoundError),
+                sqlx::Error::RowNotFound => AppError::new(Some(Message::new("role.not_found").arg("id", role_id)), None, AppErrorType::NotFoundError),
                 _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
             }
         })
 }
 
 #[post("roles")]
-pub async fn create_role(state: Data<AppState<'_>>, body: Json<CreateRole>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError>  {
+pub async fn create_role(state: Data<AppState<'_>>, body: Json<CreateRole>, _: PermissionGuard<RoleWrite>) -> Result<HttpResponse , AppError>  {
     state.context.roles.create(&body.into_inner()).await
         .map(|role| HttpResponse::Created().json(role))
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
             match &error {
-                sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23505")) => {
-                    AppError::new(Some("Role already exists!".to_string()), None, AppErrorType::BadRequestError)
+                sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23505")) => {
+                    AppError::new(Some(Message::new("role.exists")), None, AppErrorType::BadRequestError)
                 }
                 _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
             }
         })
 }
 
+#[put("roles/{role_id}")]
+pub async fn update_role(state: Data<AppState<'_>>, path: Path<i16>, body: Json<UpdateRole>, _: PermissionGuard<RoleUpdate>) -> Result<HttpResponse , AppError>  {
+    let role_id = path.into_inner();
+    ensure_renamable(&state, role_id, Some(&body.name)).await?;
+
+    state.context.roles.update(&role_id, &body.into_inner()).await
+        .map(|role| HttpResponse::Ok().json(role))
+        .map_err(|error| map_update_error(error, role_id))
+}
+
+#[patch("roles/{role_id}")]
+pub async fn patch_role(state: Data<AppState<'_>>, path: Path<i16>, body: Json<PatchRole>, _: PermissionGuard<RoleUpdate>) -> Result<HttpResponse , AppError>  {
+    let role_id = path.into_inner();
+    ensure_renamable(&state, role_id, body.name.as_deref()).await?;
+
+    state.context.roles.patch(&role_id, &body.into_inner()).await
+        .map(|role| HttpResponse::Ok().json(role))
+        .map_err(|error| map_update_error(error, role_id))
+}
+
+// system roles keep their name so the guards depending on it keep working
+async fn ensure_renamable(state: &AppState<'_>, role_id: i16, name: Option<&str>) -> Result<(), AppError> {
+    let role = state.context.roles.find_by_id(&role_id).await
+        .map_err(|error| map_update_error(error, role_id))?;
+
+    if role.is_system && name.is_some_and(|name| name != role.name) {
+        return Err(AppError::new(Some(Message::new("role.system").arg("id", role_id)), None, AppErrorType::ForbiddenError));
+    }
+
+    Ok(())
+}
+
+fn map_update_error(error: sqlx::Error, role_id: i16) -> AppError {
+    error!("Error occured: {:?}", error); 
+    match &error {
+        sqlx::Error::RowNotFound => AppError::new(Some(Message::new("role.not_found").arg("id", role_id)), None, AppErrorType::NotFoundError),
+        sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23505")) => {
+            AppError::new(Some(Message::new("role.exists")), None, AppErrorType::ConflictError)
+        }
+        _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+    }
+}
+
 #[delete("roles/{role_id}")]
-pub async fn delete_role_with_id(state: Data<AppState<'_>>, path: Path<i16>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+pub async fn delete_role_with_id(state: Data<AppState<'_>>, path: Path<i16>, query: Query<DeleteRoleQuery>, guard: PermissionGuard<RoleDelete>, locale: Locale) -> Result<HttpResponse , AppError> {
     let role_id = path.into_inner();
+
+    if let Some(reassign_to) = query.reassign_to {
+        // moving users to another role is a change to those users
+        if !guard.auth.has_permission(UserUpdate::NAME) {
+            return Err(AppError::new(Some(Message::new("error.forbidden")), None, AppErrorType::ForbiddenError));
+        }
+        if reassign_to == role_id {
+            return Err(AppError::new(Some(Message::new("role.reassign_invalid").arg("id", reassign_to)), None, AppErrorType::BadRequestError));
+        }
+        ensure_grantable(&state, &guard.auth, reassign_to).await?;
+    }
     
-    state.context.roles.delete(&role_id).await
-        .map(|result| {
-            if result.rows_affected() == 0 {
-                HttpResponse::NotFound().json(AppResponseError::new(format!("Role with id {} could not be found!", role_id)))
-            } else {
-                HttpResponse::Ok().json(AppResponse::new("Role deleted successfully."))
+    let result = state.context.roles.delete(&role_id, &query).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            match &error {
+                sqlx::Error::Database(d) if d.constraint() == Some("fk_user_role_id") => {
+                    AppError::new(Some(Message::new("role.reassign_invalid").arg("id", query.reassign_to.unwrap_or_default())), None, AppErrorType::BadRequestError)
+                }
+                _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
             }
-        })
+        })?;
+
+    // the reassigned users had their tokens revoked with the deletion, drop what the guard cached about them
+    if result == Deletion::Deleted && query.reassign_to.is_some() {
+        state.revocation_store.invalidate_all();
+    }
+
+    Ok(match result {
+        Deletion::Deleted => HttpResponse::Ok().json(AppResponse::new(locale.message("role.deleted"))),
+        Deletion::NotFound => HttpResponse::NotFound().json(AppResponseError::new(Message::new("role.not_found").arg("id", role_id).render(locale))),
+        Deletion::Protected => HttpResponse::Forbidden().json(AppResponseError::new(Message::new("role.system").arg("id", role_id).render(locale))),
+        Deletion::InUse(dependants) => HttpResponse::Conflict().json(DeletionConflict {
+            error: Message::new("role.in_use").arg("id", role_id).render(locale),
+            dependants,
+        }),
+    })
+}
+
+// reassigned users gain every permission of the target role, system roles included,
+// so the caller may only hand out permissions they already hold
+pub(crate) async fn ensure_grantable(state: &AppState<'_>, auth: &JwtAuthenticationGuard, role_id: i16) -> Result<(), AppError> {
+    let permissions = state.context.role_permissions.find_role_permissions(&role_id).await
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
-            AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
-        })
+            match error {
+                sqlx::Error::RowNotFound => AppError::new(Some(Message::new("role.reassign_invalid").arg("id", role_id)), None, AppErrorType::BadRequestError),
+                _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            }
+        })?;
+
+    let missing = auth.missing_permissions(&permissions);
+
+    if !missing.is_empty() {
+        return Err(AppError::new(Some(Message::new("role.reassign_privileged").arg("id", role_id).arg("permissions", missing.join(", "))), None, AppErrorType::ForbiddenError));
+    }
+
+    Ok(())
 }
 
 #[get("roles/{role_id}/permissions")]
-pub async fn get_role_permissions(state: Data<AppState<'_>>, path: Path<i16>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+pub async fn get_role_permissions(state: Data<AppState<'_>>, path: Path<i16>, _: PermissionGuard<RoleRead>) -> Result<HttpResponse , AppError> {
     let role_id = path.into_inner();
     state.context.role_permissions.find_role_permissions(&role_id).await
         .map(|roles| HttpResponse::Ok().json(roles))
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
             match error {
-                sqlx::Error::RowNotFound => AppError::new(Some(format!("Role with id {} could not be found!", role_id)), None, AppErrorType::NotFoundError),
+                sqlx::Error::RowNotFound => AppError::new(Some(Message::new("role.not_found").arg("id", role_id)), None, AppErrorType::NotFoundError),
                 _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
             }
         })
-}
\ No newline at end of file
+}
+
+#[put("roles/{role_id}/permissions")]
+pub async fn update_role_permissions(state: Data<AppState<'_>>, path: Path<i16>, body: Json<UpdateRolePermissions>, guard: PermissionGuard<RoleUpdate>) -> Result<HttpResponse , AppError> {
+    let role_id = path.into_inner();
+    ensure_core_permissions_kept(&state, role_id, |permission_id| !body.permission_ids.contains(permission_id)).await?;
+    ensure_held(&state, &guard.auth, role_id, &body.permission_ids).await?;
+
+    state.context.role_permissions.update_role_permissions(&role_id, &body.permission_ids).await
+        .map(|changes| HttpResponse::Ok().json(changes))
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            match &error {
+                sqlx::Error::RowNotFound => AppError::new(Some(Message::new("role.not_found").arg("id", role_id)), None, AppErrorType::NotFoundError),
+                sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23503")) => {
+                    AppError::new(Some(Message::new("role.unknown_permissions")), None, AppErrorType::BadRequestError)
+                }
+                _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            }
+        })
+}
+
+#[post("roles/{role_id}/permissions/{permission_id}")]
+pub async fn add_role_permission(state: Data<AppState<'_>>, path: Path<(i16, i16)>, guard: PermissionGuard<RoleUpdate>) -> Result<HttpResponse , AppError> {
+    let (role_id, permission_id) = path.into_inner();
+    ensure_held(&state, &guard.auth, role_id, &[permission_id]).await?;
+    state.context.role_permissions.add_role_permission(&role_id, &permission_id).await
+        .map(|result| {
+            if result == 0 {
+                HttpResponse::Ok().json(RolePermissionChanges::default())
+            } else {
+                HttpResponse::Created().json(RolePermissionChanges { added: vec![permission_id], removed: vec![] })
+            }
+        })
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            match &error {
+                sqlx::Error::Database(d) if d.constraint() == Some("fk_role_permission_role_id") => {
+                    AppError::new(Some(Message::new("role.not_found").arg("id", role_id)), None, AppErrorType::NotFoundError)
+                }
+                sqlx::Error::Database(d) if d.constraint() == Some("fk_role_permission_permission_id") => {
+                    AppError::new(Some(Message::new("permission.not_found").arg("id", permission_id)), None, AppErrorType::NotFoundError)
+                }
+                _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            }
+        })
+}
+
+#[delete("roles/{role_id}/permissions/{permission_id}")]
+pub async fn remove_role_permission(state: Data<AppState<'_>>, path: Path<(i16, i16)>, _: PermissionGuard<RoleUpdate>, locale: Locale) -> Result<HttpResponse , AppError> {
+    let (role_id, permission_id) = path.into_inner();
+    ensure_core_permissions_kept(&state, role_id, |core| *core == permission_id).await?;
+
+    state.context.role_permissions.remove_role_permission(&role_id, &permission_id).await
+        .map(|result| {
+            if result == 0 {
+                HttpResponse::NotFound().json(AppResponseError::new(Message::new("role.permission_not_assigned").arg("permission_id", permission_id).arg("role_id", role_id).render(locale)))
+            } else {
+                HttpResponse::Ok().json(AppResponse::new(locale.message("role.permission_removed")))
+            }
+        })
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+        })
+}
+
+async fn ensure_core_permissions_kept(state: &AppState<'_>, role_id: i16, removed: impl Fn(&i16) -> bool) -> Result<(), AppError> {
+    let core_permissions = state.context.role_permissions.find_core_permissions(&role_id).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+        })?;
+
+    let stripped = core_permissions.iter()
+        .filter(|permission_id| removed(permission_id))
+        .map(|permission_id| permission_id.to_string())
+        .collect::<Vec<String>>();
+
+    if !stripped.is_empty() {
+        return Err(AppError::new(Some(Message::new("role.core_permissions").arg("ids", stripped.join(", ")).arg("id", role_id)), None, AppErrorType::ForbiddenError));
+    }
+
+    Ok(())
+}
+
+// granting a permission hands it to every user of the role, the caller included,
+// so only permissions the caller already holds can be added
+async fn ensure_held(state: &AppState<'_>, auth: &JwtAuthenticationGuard, role_id: i16, permission_ids: &[i16]) -> Result<(), AppError> {
+    let to_error = |error: sqlx::Error| {
+        error!("Error occured: {:?}", error); 
+        match error {
+            sqlx::Error::RowNotFound => AppError::new(Some(Message::new("role.not_found").arg("id", role_id)), None, AppErrorType::NotFoundError),
+            _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+        }
+    };
+
+    let current = state.context.role_permissions.find_role_permissions(&role_id).await.map_err(to_error)?;
+    let added = permission_ids.iter()
+        .filter(|permission_id| !current.iter().any(|permission| permission.permission_id == **permission_id))
+        .copied()
+        .collect::<Vec<i16>>();
+
+    // unknown ids are left for the insert to report
+    let permissions = state.context.permissions.find_by_ids(&added).await.map_err(to_error)?;
+    let missing = auth.missing_permissions(&permissions);
+
+    if !missing.is_empty() {
+        return Err(AppError::new(Some(Message::new("role.permissions_not_held").arg("permissions", missing.join(", "))), None, AppErrorType::ForbiddenError));
+    }
+
+    Ok(())
+}
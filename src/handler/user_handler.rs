@@ -1,7 +1,7 @@
 use actix_web::{ delete, get, post, put, web::{ Data, Path, ServiceConfig, Query }, HttpResponse };
 use log::error;
 use sqlx::Error::RowNotFound;
-use crate::{ auth::JwtAuthenticationGuard, error::{AppError, AppErrorType, AppResponseError}, model::{app_response::AppResponse, pagination::PaginationRequest, user::{CreateUser, UpdateUser}, user_credentials::{CreateUserCredential, UpdateUserCredential}}, util, AppState };
+use crate::{ auth::{JwtAuthenticationGuard, PermissionGuard, RequiredPermission}, entity::permission::Permission, error::{AppError, AppErrorType, AppResponseError}, handler::role_handler, i18n::{Locale, Message}, model::{app_response::AppResponse, pagination::PaginationRequest, user::{CreateUser, UpdateUser}, user_credentials::{CreateUserCredential, UpdateUserCredential}}, permissions::{UserDelete, UserRead, UserUpdate, UserWrite}, util, AppState };
 use actix_web_validator::Json;
 
 pub fn init(cfg: &mut ServiceConfig) {
@@ -13,24 +13,26 @@ pub fn init(cfg: &mut ServiceConfig) {
     cfg.service(delete_user_with_id);
     cfg.service(create_user_credential);
     cfg.service(update_user_credential);
+    cfg.service(revoke_user_tokens);
+    cfg.service(get_my_permissions);
 }
 
 #[get("users/{user_id}")]
-pub async fn get_user_by_id(state: Data<AppState<'_>>, path: Path<i32>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+pub async fn get_user_by_id(state: Data<AppState<'_>>, path: Path<i32>, _: PermissionGuard<UserRead>) -> Result<HttpResponse , AppError> {
     let user_id = path.into_inner();
     state.context.users.find_by_id(&user_id).await
         .map(|user| HttpResponse::Ok().json(user))
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
             match error {
-                RowNotFound => AppError::new(Some(format!("User with id {} could not be found!", user_id)), None, AppErrorType::NotFoundError),
+                RowNotFound => AppError::new(Some(Message::new("user.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError),
                 _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
             }
         })
 }
 
 #[get("users")]
-pub async fn get_users(state: Data<AppState<'_>>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+pub async fn get_users(state: Data<AppState<'_>>, _: PermissionGuard<UserRead>) -> Result<HttpResponse , AppError> {
     state.context.users.find_all().await
         .map(|users| HttpResponse::Ok().json(users))
         .map_err(|error| {
@@ -40,7 +42,7 @@ pub async fn get_users(state: Data<AppState<'_>>, _: JwtAuthenticationGuard) ->
 }
 
 #[get("users-paginated")]
-pub async fn get_users_paginated(state: Data<AppState<'_>>, pagination: Query<PaginationRequest>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+pub async fn get_users_paginated(state: Data<AppState<'_>>, pagination: Query<PaginationRequest>, _: PermissionGuard<UserRead>) -> Result<HttpResponse , AppError> {
     state.context.users.find_paginated(pagination.page, pagination.page_size).await
         .map(|results| HttpResponse::Ok().json(results))
         .map_err(|e| {
@@ -50,14 +52,14 @@ pub async fn get_users_paginated(state: Data<AppState<'_>>, pagination: Query<Pa
 }
 
 #[post("users")]
-pub async fn create_user(state: Data<AppState<'_>>, body: Json<CreateUser>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError>  {
+pub async fn create_user(state: Data<AppState<'_>>, body: Json<CreateUser>, _: PermissionGuard<UserWrite>) -> Result<HttpResponse , AppError>  {
     state.context.users.create(&body.into_inner()).await
         .map(|user| HttpResponse::Created().json(user))
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
             match &error {
-                sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23505")) => {
-                    AppError::new(Some("User already exists!".to_string()), None, AppErrorType::BadRequestError)
+                sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23505")) => {
+                    AppError::new(Some(Message::new("user.exists")), None, AppErrorType::BadRequestError)
                 }
                 _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
             }
@@ -65,22 +67,33 @@ pub async fn create_user(state: Data<AppState<'_>>, body: Json<CreateUser>, _: J
 }
 
 #[post("users/{user_id}/credentials")]
-pub async fn create_user_credential(state: Data<AppState<'_>>, path: Path<i32>, body: Json<CreateUserCredential>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError>  {
+pub async fn create_user_credential(state: Data<AppState<'_>>, path: Path<i32>, body: Json<CreateUserCredential>, _: PermissionGuard<UserWrite>, locale: Locale) -> Result<HttpResponse , AppError>  {
     let user_id = path.into_inner();
     let CreateUserCredential { username, password }= body.into_inner();
 
+    let user = state.context.users.find_by_id(&user_id).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            match error {
+                RowNotFound => AppError::new(Some(Message::new("user.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError),
+                _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            }
+        })?;
+
+    state.password_policy.check(&password, &[&user.first_name, &user.surname, &user.email_address, &username])?;
+
     let hashed_password = util::hash_password(&password, &state.argon_config).await?;
 
     state.context.user_credentials.create(&user_id, &CreateUserCredential{ username, password: hashed_password }).await
-        .map(|_| HttpResponse::Created().json(AppResponse { message: "Successfully created!" }))
+        .map(|_| HttpResponse::Created().json(AppResponse::new(locale.message("user.created"))))
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
             match &error {
-                sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23503")) => {
-                    AppError::new(Some(format!("User with id {} could not be found!", user_id)), None, AppErrorType::NotFoundError)
+                sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23503")) => {
+                    AppError::new(Some(Message::new("user.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError)
                 },
-                sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23505")) => {
-                    AppError::new(Some("Credential/username already exists!".to_string()), None, AppErrorType::BadRequestError)
+                sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23505")) => {
+                    AppError::new(Some(Message::new("auth.credential_exists")), None, AppErrorType::BadRequestError)
                 }
                 _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
             }
@@ -88,18 +101,22 @@ pub async fn create_user_credential(state: Data<AppState<'_>>, path: Path<i32>,
 }
 
 #[put("users/{user_id}/credentials/{user_credential_id}")]
-pub async fn update_user_credential(state: Data<AppState<'_>>, path: Path<(i32, i32)>, body: Json<UpdateUserCredential>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError>  {
+pub async fn update_user_credential(state: Data<AppState<'_>>, path: Path<(i32, i32)>, body: Json<UpdateUserCredential>, auth: JwtAuthenticationGuard, locale: Locale) -> Result<HttpResponse , AppError>  {
     let (user_id, user_credential_id) = path.into_inner();
+
+    if auth.id != user_id && !auth.has_permission(UserUpdate::NAME) {
+        return Err(AppError::new(Some(Message::new("error.forbidden")), None, AppErrorType::ForbiddenError));
+    }
     let UpdateUserCredential { previous_password, password } = body.into_inner();
 
     let user_credential = state.context.user_credentials.find_by_user_id(&user_id).await
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
             match &error {
-                sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23503")) => {
-                    AppError::new(Some("Credential does not exist!".to_string()), None, AppErrorType::BadRequestError)
+                sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23503")) => {
+                    AppError::new(Some(Message::new("credential.not_found")), None, AppErrorType::BadRequestError)
                 },
-                sqlx::Error::RowNotFound => AppError::new(Some("Credential does not exist!".to_string()), None, AppErrorType::NotFoundError),
+                sqlx::Error::RowNotFound => AppError::new(Some(Message::new("credential.not_found")), None, AppErrorType::NotFoundError),
                 _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
             }
         })?;
@@ -107,48 +124,140 @@ pub async fn update_user_credential(state: Data<AppState<'_>>, path: Path<(i32,
     let is_correct = util::verify_password(&user_credential.password, &previous_password).await?;
 
     if !is_correct {
-        return Err(AppError::new(Some("Credential do match!".to_string()), None, AppErrorType::BadRequestError))
+        return Err(AppError::new(Some(Message::new("credential.mismatch")), None, AppErrorType::BadRequestError))
     }
 
-    state.context.user_credentials.update(&user_id, &user_credential_id, &UpdateUserCredential{previous_password, password}).await
-        .map(|_| HttpResponse::Ok().json(AppResponse { message: "Successfully updated!" }))
+    let user = state.context.users.find_by_id(&user_id).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            match error {
+                RowNotFound => AppError::new(Some(Message::new("user.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError),
+                _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            }
+        })?;
+
+    state.password_policy.check(&password, &[&user.first_name, &user.surname, &user.email_address, &user_credential.username])?;
+
+    let hashed_password = util::hash_password(&password, &state.argon_config).await?;
+
+    state.context.user_credentials.update(&user_id, &user_credential_id, &UpdateUserCredential{previous_password, password: hashed_password}).await
+        .map(|_| HttpResponse::Ok().json(AppResponse::new(locale.message("user.updated"))))
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
             match &error {
-                sqlx::Error::Database(d) if d.code().map_or(false, |code| code.eq("23503")) => {
-                    AppError::new(Some("Credential does not exist!".to_string()), None, AppErrorType::BadRequestError)
+                sqlx::Error::Database(d) if d.code().is_some_and(|code| code.eq("23503")) => {
+                    AppError::new(Some(Message::new("credential.not_found")), None, AppErrorType::BadRequestError)
                 },
-                sqlx::Error::RowNotFound => AppError::new(Some("Credential does not exist!".to_string()), None, AppErrorType::NotFoundError),
+                sqlx::Error::RowNotFound => AppError::new(Some(Message::new("credential.not_found")), None, AppErrorType::NotFoundError),
                 _ => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError),
             }
         })
 }
 
 #[put("users/{user_id}")]
-pub async fn update_user(state: Data<AppState<'_>>, path: Path<i32>, body: Json<UpdateUser>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError>  {
+pub async fn update_user(state: Data<AppState<'_>>, path: Path<i32>, body: Json<UpdateUser>, guard: PermissionGuard<UserUpdate>) -> Result<HttpResponse , AppError>  {
     let user_id = path.into_inner();
-    state.context.users.update(&user_id, &body.into_inner()).await
-        .map(|user| HttpResponse::Ok().json(user))
+
+    let previous = state.context.users.find_by_id(&user_id).await
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
-            AppError::new(Some(format!("User with id {} could not be found!", user_id)), None, AppErrorType::NotFoundError)
-        })
+            match error {
+                RowNotFound => AppError::new(Some(Message::new("user.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError),
+                _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            }
+        })?;
+
+    if body.role_id != previous.role_id {
+        role_handler::ensure_grantable(&state, &guard.auth, body.role_id).await?;
+    }
+
+    let user = state.context.users.update(&user_id, &body.into_inner()).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(Some(Message::new("user.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError)
+        })?;
+
+    // tokens issued before the user was disabled or unconfirmed would otherwise stay valid until they expire
+    let lost_access = (previous.enabled && !user.enabled) || (previous.email_confirmed && !user.email_confirmed);
+    // and tokens issued under another role would keep its permissions
+    let changed_role = previous.role_id != user.role_id;
+
+    if (lost_access && state.account_config.revoke_tokens_on_status_change) || changed_role {
+        state.revocation_store.revoke_user(&state.context, &user_id).await
+            .map_err(|error| {
+                error!("Error occured: {:?}", error); 
+                AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            })?;
+    }
+
+    Ok(HttpResponse::Ok().json(user))
 }
 
 #[delete("users/{user_id}")]
-pub async fn delete_user_with_id(state: Data<AppState<'_>>, path: Path<i32>, _: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+pub async fn delete_user_with_id(state: Data<AppState<'_>>, path: Path<i32>, _: PermissionGuard<UserDelete>, locale: Locale) -> Result<HttpResponse , AppError> {
     let user_id = path.into_inner();
     
     state.context.users.delete(&user_id).await
         .map(|result| {
             if result.rows_affected() == 0 {
-                HttpResponse::NotFound().json(AppResponseError::new(format!("User with id {} could not be found!", user_id)))
+                HttpResponse::NotFound().json(AppResponseError::new(Message::new("user.not_found").arg("id", user_id).render(locale)))
             } else {
-                HttpResponse::Ok().json(AppResponse::new("User deleted successfully."))
+                HttpResponse::Ok().json(AppResponse::new(locale.message("user.deleted")))
             }
         })
         .map_err(|error| {
             error!("Error occured: {:?}", error); 
             AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
         })
+}
+
+#[post("users/{user_id}/revoke-tokens")]
+pub async fn revoke_user_tokens(state: Data<AppState<'_>>, path: Path<i32>, _: PermissionGuard<UserUpdate>, locale: Locale) -> Result<HttpResponse , AppError> {
+    let user_id = path.into_inner();
+
+    state.context.users.find_by_id(&user_id).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            match error {
+                RowNotFound => AppError::new(Some(Message::new("user.not_found").arg("id", user_id)), None, AppErrorType::NotFoundError),
+                _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            }
+        })?;
+
+    state.revocation_store.revoke_user(&state.context, &user_id).await
+        .map(|_| HttpResponse::Ok().json(AppResponse::new(locale.message("user.tokens_revoked"))))
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+        })
+}
+
+#[get("me/permissions")]
+pub async fn get_my_permissions(state: Data<AppState<'_>>, auth: JwtAuthenticationGuard) -> Result<HttpResponse , AppError> {
+    let user = state.context.users.find_by_id(&auth.id).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            match error {
+                RowNotFound => AppError::new(Some(Message::new("user.not_found").arg("id", auth.id)), None, AppErrorType::NotFoundError),
+                _  => AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            }
+        })?;
+
+    let password_change_required = state.context.user_credentials.is_password_change_required(&auth.id).await
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+        })?;
+
+    // tokens issued while a password change is pending grant nothing, so neither does this list
+    if password_change_required {
+        return Ok(HttpResponse::Ok().json(Vec::<Permission>::new()));
+    }
+
+    state.context.role_permissions.find_role_permissions(&user.role_id).await
+        .map(|permissions| HttpResponse::Ok().json(permissions))
+        .map_err(|error| {
+            error!("Error occured: {:?}", error); 
+            AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+        })
 }
\ No newline at end of file
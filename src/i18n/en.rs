@@ -0,0 +1,88 @@
+pub const MESSAGES: &[(&str, &str)] = &[
+    ("error.unexpected", "An unexpected error occurred!"),
+    ("error.forbidden", "You are not allowed to perform this action!"),
+    ("auth.audience_not_allowed", "Audience {audience} is not allowed!"),
+    ("auth.invalid_credentials", "Invalid email address/password!"),
+    ("auth.account_disabled", "Account is disabled, contact an administrator!"),
+    ("auth.email_not_confirmed", "Email address has not been confirmed yet!"),
+    ("auth.credential_exists", "Credential/username already exists!"),
+    ("auth.sign_up_disabled", "Sign-up is disabled, contact an administrator for an account!"),
+    ("auth.sign_up_domain_not_allowed", "Sign-up is not allowed for this email domain!"),
+    ("auth.sign_up_received", "Check your inbox to complete the registration."),
+    ("auth.email_already_confirmed", "Email address is already confirmed!"),
+    ("auth.confirmation_code_sent", "A new confirmation code has been sent."),
+    ("auth.confirmation_resend_too_soon", "Wait {seconds} seconds before requesting another confirmation code!"),
+    ("auth.confirmation_resend_limit_reached", "Too many confirmation codes requested, contact an administrator!"),
+    ("auth.no_pending_confirmation", "No pending confirmation for user with id {id}!"),
+    ("auth.confirmation_attempts_exceeded", "Too many attempts, request a new confirmation code!"),
+    ("auth.confirmation_code_expired", "Confirmation code has expired, request a new confirmation code!"),
+    ("auth.confirmation_code_invalid", "Confirmation code is invalid!"),
+    ("auth.invalid_refresh_token", "Invalid refresh token!"),
+    ("auth.refresh_token_expired", "Refresh token has expired!"),
+    ("auth.signed_out", "Signed out successfully."),
+    ("auth.too_many_attempts", "Too many failed sign-in attempts, try again later!"),
+    ("auth.account_locked", "Account is locked until {until} after too many failed sign-in attempts!"),
+    ("auth.unlock_token_invalid", "Unlock link is invalid or has expired!"),
+    ("auth.account_unlocked", "Account unlocked successfully."),
+    ("user.not_found", "User with id {id} could not be found!"),
+    ("user.exists", "User already exists!"),
+    ("user.created", "Successfully created!"),
+    ("user.updated", "Successfully updated!"),
+    ("user.deleted", "User deleted successfully."),
+    ("user.tokens_revoked", "User tokens revoked successfully."),
+    ("lockout.not_found", "User with id {id} has no failed sign-in attempts!"),
+    ("lockout.user_unlocked", "User unlocked successfully."),
+    ("lockout.ip_not_found", "Address {ip} has no failed sign-in attempts!"),
+    ("lockout.ip_unlocked", "Address unlocked successfully."),
+    ("credential.not_found", "Credential does not exist!"),
+    ("credential.mismatch", "Credential do match!"),
+    ("password.too_short", "Password must be at least {min} characters long!"),
+    ("password.too_long", "Password must be at most {max} characters long!"),
+    ("password.missing_lowercase", "Password must contain a lowercase letter!"),
+    ("password.missing_uppercase", "Password must contain an uppercase letter!"),
+    ("password.missing_digit", "Password must contain a digit!"),
+    ("password.missing_symbol", "Password must contain a symbol!"),
+    ("password.contains_personal_information", "Password must not contain your name or email address!"),
+    ("password.breached", "Password has appeared in a data breach, choose another one!"),
+    ("role.not_found", "Role with id {id} could not be found!"),
+    ("role.exists", "Role already exists!"),
+    ("role.deleted", "Role deleted successfully."),
+    ("role.in_use", "Role with id {id} is still in use!"),
+    ("role.reassign_invalid", "Users cannot be reassigned to role {id}!"),
+    ("role.reassign_privileged", "Only roles whose permissions you hold can receive users, role {id} also grants {permissions}!"),
+    ("role.permissions_not_held", "Only permissions you hold can be granted, missing {permissions}!"),
+    ("role.system", "System role {id} cannot be deleted or renamed!"),
+    ("role.core_permissions", "Core permissions {ids} cannot be removed from system role {id}!"),
+    ("role.permission_not_assigned", "Permission with id {permission_id} is not assigned to role {role_id}!"),
+    ("role.permission_removed", "Permission removed from role successfully."),
+    ("role.unknown_permissions", "One or more permissions could not be found!"),
+    ("permission.not_found", "Permission with id {id} could not be found!"),
+    ("permission.exists", "Permission already exists!"),
+    ("permission.deleted", "Permission deleted successfully."),
+    ("permission.in_use", "Permission with id {id} is still assigned to roles!"),
+    ("permission.system", "System permission {id} cannot be deleted or renamed!"),
+    ("password.reset_requested", "If the email address is registered, a password reset link has been sent."),
+    ("password.reset_too_soon", "Wait {seconds} seconds before requesting another password reset!"),
+    ("password.reset_token_invalid", "Password reset token is invalid or has expired!"),
+    ("password.reset", "Password has been reset successfully."),
+    ("key.not_found", "Key {kid} could not be found!"),
+    ("key.no_private_key", "Key {kid} has no private key and cannot sign tokens!"),
+    ("key.active_cannot_be_retired", "Key {kid} is the active signing key and cannot be retired!"),
+    ("key.scheduled_cannot_be_retired", "Key {kid} is scheduled to become active and cannot be retired!"),
+    ("key.not_loaded_from_directory", "Keys are not loaded from a directory!"),
+    ("key.reloaded", "Keys reloaded successfully."),
+    ("key.promoted", "Key promoted successfully."),
+    ("key.retired", "Key retired successfully."),
+    ("email.outbox_not_found", "Failed email with id {id} could not be found!"),
+    ("email.template_not_found", "Email template {name} could not be found!"),
+    ("email.preview_format_not_supported", "Preview format {format} is not supported!"),
+    ("email.confirmation.subject", "Confirm your email address"),
+    ("email.password_reset.subject", "Reset your password"),
+    ("email.password_reset_unknown.subject", "Password reset requested for your email address"),
+    ("email.welcome.subject", "Welcome to Bulk SMS"),
+    ("email.password_changed.subject", "Your password was changed"),
+    ("email.account_locked.subject", "Your account has been locked"),
+    ("email.new_sign_in.subject", "New sign-in to your account"),
+    ("email.invitation.subject", "You have been invited to Bulk SMS"),
+    ("email.registration_attempt.subject", "Someone tried to register with your email address"),
+];
\ No newline at end of file
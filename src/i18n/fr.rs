@@ -0,0 +1,88 @@
+pub const MESSAGES: &[(&str, &str)] = &[
+    ("error.unexpected", "Une erreur inattendue s'est produite !"),
+    ("error.forbidden", "Vous n'êtes pas autorisé à effectuer cette action !"),
+    ("auth.audience_not_allowed", "L'audience {audience} n'est pas autorisée !"),
+    ("auth.invalid_credentials", "Adresse e-mail ou mot de passe invalide !"),
+    ("auth.account_disabled", "Le compte est désactivé, contactez un administrateur !"),
+    ("auth.email_not_confirmed", "L'adresse e-mail n'a pas encore été confirmée !"),
+    ("auth.credential_exists", "Cet identifiant existe déjà !"),
+    ("auth.sign_up_disabled", "L'inscription est désactivée, contactez un administrateur pour obtenir un compte !"),
+    ("auth.sign_up_domain_not_allowed", "L'inscription n'est pas autorisée pour ce domaine de messagerie !"),
+    ("auth.sign_up_received", "Consultez votre boîte de réception pour terminer l'inscription."),
+    ("auth.email_already_confirmed", "Cette adresse e-mail est déjà confirmée !"),
+    ("auth.confirmation_code_sent", "Un nouveau code de confirmation a été envoyé."),
+    ("auth.confirmation_resend_too_soon", "Attendez {seconds} secondes avant de demander un nouveau code de confirmation !"),
+    ("auth.confirmation_resend_limit_reached", "Trop de codes de confirmation demandés, contactez un administrateur !"),
+    ("auth.no_pending_confirmation", "Aucune confirmation en attente pour l'utilisateur {id} !"),
+    ("auth.confirmation_attempts_exceeded", "Trop de tentatives, demandez un nouveau code de confirmation !"),
+    ("auth.confirmation_code_expired", "Le code de confirmation a expiré, demandez un nouveau code de confirmation !"),
+    ("auth.confirmation_code_invalid", "Le code de confirmation est invalide !"),
+    ("auth.invalid_refresh_token", "Jeton de rafraîchissement invalide !"),
+    ("auth.refresh_token_expired", "Le jeton de rafraîchissement a expiré !"),
+    ("auth.signed_out", "Déconnexion réussie."),
+    ("auth.too_many_attempts", "Trop de tentatives de connexion échouées, réessayez plus tard !"),
+    ("auth.account_locked", "Le compte est verrouillé jusqu'au {until} après trop de tentatives de connexion échouées !"),
+    ("auth.unlock_token_invalid", "Le lien de déverrouillage est invalide ou a expiré !"),
+    ("auth.account_unlocked", "Compte déverrouillé avec succès."),
+    ("user.not_found", "L'utilisateur {id} est introuvable !"),
+    ("user.exists", "Cet utilisateur existe déjà !"),
+    ("user.created", "Création réussie !"),
+    ("user.updated", "Mise à jour réussie !"),
+    ("user.deleted", "Utilisateur supprimé avec succès."),
+    ("user.tokens_revoked", "Jetons de l'utilisateur révoqués avec succès."),
+    ("lockout.not_found", "L'utilisateur {id} n'a aucune tentative de connexion échouée !"),
+    ("lockout.user_unlocked", "Utilisateur déverrouillé avec succès."),
+    ("lockout.ip_not_found", "L'adresse {ip} n'a aucune tentative de connexion échouée !"),
+    ("lockout.ip_unlocked", "Adresse déverrouillée avec succès."),
+    ("credential.not_found", "Cet identifiant n'existe pas !"),
+    ("credential.mismatch", "Les identifiants ne correspondent pas !"),
+    ("password.too_short", "Le mot de passe doit contenir au moins {min} caractères !"),
+    ("password.too_long", "Le mot de passe doit contenir au plus {max} caractères !"),
+    ("password.missing_lowercase", "Le mot de passe doit contenir une lettre minuscule !"),
+    ("password.missing_uppercase", "Le mot de passe doit contenir une lettre majuscule !"),
+    ("password.missing_digit", "Le mot de passe doit contenir un chiffre !"),
+    ("password.missing_symbol", "Le mot de passe doit contenir un symbole !"),
+    ("password.contains_personal_information", "Le mot de passe ne doit pas contenir votre nom ni votre adresse e-mail !"),
+    ("password.breached", "Ce mot de passe est apparu dans une fuite de données, choisissez-en un autre !"),
+    ("role.not_found", "Le rôle {id} est introuvable !"),
+    ("role.exists", "Ce rôle existe déjà !"),
+    ("role.deleted", "Rôle supprimé avec succès."),
+    ("role.in_use", "Le rôle {id} est encore utilisé !"),
+    ("role.reassign_invalid", "Les utilisateurs ne peuvent pas être réaffectés au rôle {id} !"),
+    ("role.reassign_privileged", "Seuls les rôles dont vous détenez les permissions peuvent recevoir des utilisateurs, le rôle {id} accorde aussi {permissions} !"),
+    ("role.permissions_not_held", "Seules les permissions que vous détenez peuvent être accordées, il manque {permissions} !"),
+    ("role.system", "Le rôle système {id} ne peut être ni supprimé ni renommé !"),
+    ("role.core_permissions", "Les permissions essentielles {ids} ne peuvent pas être retirées du rôle système {id} !"),
+    ("role.permission_not_assigned", "La permission {permission_id} n'est pas attribuée au rôle {role_id} !"),
+    ("role.permission_removed", "Permission retirée du rôle avec succès."),
+    ("role.unknown_permissions", "Une ou plusieurs permissions sont introuvables !"),
+    ("permission.not_found", "La permission {id} est introuvable !"),
+    ("permission.exists", "Cette permission existe déjà !"),
+    ("permission.deleted", "Permission supprimée avec succès."),
+    ("permission.in_use", "La permission {id} est encore attribuée à des rôles !"),
+    ("permission.system", "La permission système {id} ne peut être ni supprimée ni renommée !"),
+    ("password.reset_requested", "Si l'adresse e-mail est enregistrée, un lien de réinitialisation du mot de passe a été envoyé."),
+    ("password.reset_too_soon", "Attendez {seconds} secondes avant de demander une nouvelle réinitialisation du mot de passe !"),
+    ("password.reset_token_invalid", "Le jeton de réinitialisation est invalide ou a expiré !"),
+    ("password.reset", "Le mot de passe a été réinitialisé avec succès."),
+    ("key.not_found", "La clé {kid} est introuvable !"),
+    ("key.no_private_key", "La clé {kid} n'a pas de clé privée et ne peut pas signer de jetons !"),
+    ("key.active_cannot_be_retired", "La clé {kid} est la clé de signature active et ne peut pas être retirée !"),
+    ("key.scheduled_cannot_be_retired", "La clé {kid} doit devenir active et ne peut pas être retirée !"),
+    ("key.not_loaded_from_directory", "Les clés ne sont pas chargées depuis un répertoire !"),
+    ("key.reloaded", "Clés rechargées avec succès."),
+    ("key.promoted", "Clé promue avec succès."),
+    ("key.retired", "Clé retirée avec succès."),
+    ("email.outbox_not_found", "L'e-mail en échec {id} est introuvable !"),
+    ("email.template_not_found", "Le modèle d'e-mail {name} est introuvable !"),
+    ("email.preview_format_not_supported", "Le format d'aperçu {format} n'est pas pris en charge !"),
+    ("email.confirmation.subject", "Confirmez votre adresse e-mail"),
+    ("email.password_reset.subject", "Réinitialisez votre mot de passe"),
+    ("email.password_reset_unknown.subject", "Réinitialisation du mot de passe demandée pour votre adresse e-mail"),
+    ("email.welcome.subject", "Bienvenue sur Bulk SMS"),
+    ("email.password_changed.subject", "Votre mot de passe a été modifié"),
+    ("email.account_locked.subject", "Votre compte a été verrouillé"),
+    ("email.new_sign_in.subject", "Nouvelle connexion à votre compte"),
+    ("email.invitation.subject", "Vous avez été invité sur Bulk SMS"),
+    ("email.registration_attempt.subject", "Quelqu'un a tenté de s'inscrire avec votre adresse e-mail"),
+];
\ No newline at end of file
@@ -0,0 +1,222 @@
+mod en;
+mod fr;
+
+use std::{fmt::Display, future::{ready, Future, Ready}, pin::Pin, rc::Rc};
+
+use actix_web::{body::{EitherBody, MessageBody}, dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform}, http::header::ACCEPT_LANGUAGE, Error as ActixWebError, FromRequest, HttpRequest, HttpResponse, ResponseError};
+use validator::ValidationError;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Fr,
+}
+
+impl Locale {
+    pub const ALL: &'static [Locale] = &[Locale::En, Locale::Fr];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Fr => "fr",
+        }
+    }
+
+    // matches on the primary subtag so regional tags like fr-CA resolve to fr
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let primary = tag.trim().split(['-', '_']).next().unwrap_or_default();
+
+        Locale::ALL.iter()
+            .find(|locale| locale.as_str().eq_ignore_ascii_case(primary))
+            .copied()
+    }
+
+    // picks the supported locale with the highest quality value, e.g. "fr-CH, fr;q=0.9, en;q=0.8"
+    pub fn negotiate(accept_language: &str) -> Option<Locale> {
+        let mut candidates = accept_language.split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map(|quality| quality.trim().parse::<f32>().unwrap_or(0.0))
+                    .unwrap_or(1.0);
+
+                if quality <= 0.0 {
+                    return None;
+                }
+
+                Locale::from_tag(tag).map(|locale| (locale, quality))
+            })
+            .collect::<Vec<(Locale, f32)>>();
+
+        // stable sort keeps header order between equal quality values
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.first().map(|(locale, _)| *locale)
+    }
+
+    pub fn from_accept_language(req: &HttpRequest) -> Locale {
+        req.headers().get(ACCEPT_LANGUAGE)
+            .and_then(|header| header.to_str().ok())
+            .and_then(Locale::negotiate)
+            .unwrap_or_default()
+    }
+
+    fn messages(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::En => en::MESSAGES,
+            Locale::Fr => fr::MESSAGES,
+        }
+    }
+
+    // falls back to english, then to the key itself, when a translation is missing
+    pub fn message(&self, key: &'static str) -> &'static str {
+        let find = |messages: &'static [(&'static str, &'static str)]| messages.iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, text)| *text);
+
+        find(self.messages())
+            .or_else(|| find(Locale::En.messages()))
+            .unwrap_or(key)
+    }
+}
+
+impl FromRequest for Locale {
+    type Error = ActixWebError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(Ok(Locale::from_accept_language(req)))
+    }
+}
+
+pub fn validate_locale(locale: &str) -> Result<(), ValidationError> {
+    match Locale::from_tag(locale) {
+        Some(_) => Ok(()),
+        None => {
+            let mut error = ValidationError::new("locale");
+            error.message = Some("Locale is not supported!".into());
+            Err(error)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub key: &'static str,
+    pub args: Vec<(&'static str, String)>,
+}
+
+impl Message {
+    pub fn new(key: &'static str) -> Self {
+        Message { key, args: Vec::new() }
+    }
+
+    pub fn arg(mut self, name: &'static str, value: impl Display) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+
+    pub fn render(&self, locale: Locale) -> String {
+        self.args.iter().fold(locale.message(self.key).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+    }
+}
+
+// re-renders AppError responses in the locale negotiated from the Accept-Language header
+pub struct Localise;
+
+impl<S, B> Transform<S, ServiceRequest> for Localise
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = ActixWebError> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = ActixWebError;
+    type Transform = LocaliseMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(LocaliseMiddleware { service: Rc::new(service) }))
+    }
+}
+
+pub struct LocaliseMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for LocaliseMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = ActixWebError> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = ActixWebError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let locale = Locale::from_accept_language(req.request());
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let response = service.call(req).await?;
+
+            let localised = response.response().error()
+                .and_then(|error| error.as_error::<AppError>())
+                .map(|error| HttpResponse::build(error.status_code()).json(error.response_error(locale)));
+
+            match localised {
+                Some(localised) => Ok(response.into_response(localised).map_into_right_body()),
+                None => Ok(response.map_into_left_body()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod i18n_tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_picks_highest_quality_supported_locale() {
+        assert_eq!(Locale::negotiate("de-DE, en;q=0.5, fr-CA;q=0.8"), Some(Locale::Fr));
+        assert_eq!(Locale::negotiate("fr;q=0, en"), Some(Locale::En));
+        assert_eq!(Locale::negotiate("de, *;q=0.1"), None);
+    }
+
+    #[test]
+    fn message_falls_back_to_english_then_key() {
+        assert_eq!(Locale::Fr.message("auth.signed_out"), "Déconnexion réussie.");
+        assert_eq!(Locale::Fr.message("missing.key"), "missing.key");
+    }
+
+    #[test]
+    fn render_substitutes_arguments() {
+        let message = Message::new("user.not_found").arg("id", 7);
+
+        assert_eq!(message.render(Locale::En), "User with id 7 could not be found!");
+        assert_eq!(message.render(Locale::Fr), "L'utilisateur 7 est introuvable !");
+    }
+
+    #[test]
+    fn every_locale_translates_every_english_message() {
+        for locale in Locale::ALL {
+            for (key, text) in en::MESSAGES {
+                let translated = locale.messages().iter().find(|(name, _)| name == key);
+                assert!(translated.is_some(), "{} is missing {}", locale.as_str(), key);
+
+                // placeholders must survive translation
+                for placeholder in text.split('{').skip(1).filter_map(|part| part.split('}').next()) {
+                    assert!(translated.unwrap().1.contains(&format!("{{{}}}", placeholder)), "{} {} is missing {{{}}}", locale.as_str(), key, placeholder);
+                }
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -1,26 +1,220 @@
-use chrono::{Duration, Utc};
-use jsonwebtoken::{encode, decode, Header, Validation, EncodingKey, DecodingKey};
+use std::{env, path::PathBuf, sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard}, time::Duration as StdDuration};
 
-use crate::{entity::{permission::Permission, role::Role, user::User}, error::{AppError, AppErrorType}, model::claims::Claims, JwtConfig};
+use actix_web::rt;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{encode, decode, decode_header, Algorithm, Header, Validation};
+use log::{error, info, warn};
 
-pub async fn generate_token(user: User, role: Role, permissions: Vec<Permission>, config: &JwtConfig) -> Result<String , AppError> {
+use crate::{dao::Database, entity::{permission::Permission, role::Role, user::User}, error::{AppError, AppErrorType}, i18n::Message, keyring::{self, JwtKey, JwtKeyring}, model::claims::{Claims, CLAIMS_VERSION}, util};
+
+const DEFAULT_ISSUER: &str = "bulk_sms_api";
+const DEFAULT_AUDIENCE: &str = "bulk_sms_api";
+const DEFAULT_LEEWAY: u64 = 60;
+const DEFAULT_KEY_SYNC_INTERVAL: u64 = 30;
+
+pub struct JwtConfig {
+    pub keyring: RwLock<JwtKeyring>,
+    pub keys_dir: Option<PathBuf>,
+    pub algorithm: Algorithm,
+    pub issuer: String,
+    pub audience: String,
+    pub allowed_audiences: Vec<String>,
+    pub leeway: u64,
+    pub key_sync_interval: u64,
+    pub expires_in: i64,
+    pub refresh_expires_in: i64,
+}
+
+impl JwtConfig {
+
+    pub fn new(keyring: JwtKeyring, expires_in: i64, refresh_expires_in: i64) -> Self {
+        let algorithm = keyring.active().algorithm;
+
+        JwtConfig {
+            keyring: RwLock::new(keyring),
+            keys_dir: None,
+            algorithm,
+            issuer: DEFAULT_ISSUER.to_string(),
+            audience: DEFAULT_AUDIENCE.to_string(),
+            allowed_audiences: vec![DEFAULT_AUDIENCE.to_string()],
+            leeway: DEFAULT_LEEWAY,
+            key_sync_interval: DEFAULT_KEY_SYNC_INTERVAL,
+            expires_in,
+            refresh_expires_in,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let expires_in = env::var("JWT_EXPIRES_IN").expect("JWT_EXPIRES_IN was not provided.").parse::<i64>().expect("JWT_EXPIRES_IN should be an i64.");
+        let refresh_expires_in = env::var("JWT_REFRESH_EXPIRES_IN").expect("JWT_REFRESH_EXPIRES_IN was not provided.").parse::<i64>().expect("JWT_REFRESH_EXPIRES_IN should be an i64.");
+
+        let algorithm = env::var("JWT_ALGORITHM")
+            .unwrap_or_else(|_| "HS256".to_string())
+            .parse::<Algorithm>()
+            .expect("JWT_ALGORITHM should be one of HS256, RS256, ES256 or EdDSA.");
+
+        let config = match algorithm {
+            Algorithm::HS256 => {
+                let secret = env::var("JWT_SECRET").expect("JWT_SECRET was not provided.");
+                let mut keyring = JwtKeyring::new(JwtKey::from_secret(&secret));
+
+                // secrets that were rotated out but may still have unexpired tokens in circulation
+                for previous in env::var("JWT_PREVIOUS_SECRETS").unwrap_or_default().split(',').filter(|s| !s.is_empty()) {
+                    keyring.insert(JwtKey::from_secret(previous));
+                }
+
+                JwtConfig::new(keyring, expires_in, refresh_expires_in)
+            },
+            _ => {
+                let keys_dir = PathBuf::from(env::var("JWT_KEYS_DIR").expect("JWT_KEYS_DIR was not provided."));
+                let active_kid = env::var("JWT_ACTIVE_KID").expect("JWT_ACTIVE_KID was not provided.");
+
+                let keys = keyring::load_keys_dir(&keys_dir, algorithm)
+                    .unwrap_or_else(|error| panic!("Unable to load JWT keys: {:?}", error.cause));
+                let keyring = JwtKeyring::from_keys(keys, &active_kid)
+                    .unwrap_or_else(|error| panic!("Unable to load JWT keys: {:?}", error.message));
+
+                JwtConfig { keys_dir: Some(keys_dir), ..JwtConfig::new(keyring, expires_in, refresh_expires_in) }
+            }
+        };
+
+        let issuer = env::var("JWT_ISSUER").unwrap_or_else(|_| DEFAULT_ISSUER.to_string());
+        let audience = env::var("JWT_AUDIENCE").unwrap_or_else(|_| DEFAULT_AUDIENCE.to_string());
+        let leeway = env::var("JWT_LEEWAY").map(|leeway| leeway.parse::<u64>().expect("JWT_LEEWAY should be a u64.")).unwrap_or(DEFAULT_LEEWAY);
+        let key_sync_interval = env::var("JWT_KEY_SYNC_INTERVAL").map(|interval| interval.parse::<u64>().expect("JWT_KEY_SYNC_INTERVAL should be a u64.")).unwrap_or(DEFAULT_KEY_SYNC_INTERVAL);
+
+        let mut allowed_audiences: Vec<String> = env::var("JWT_ALLOWED_AUDIENCES").unwrap_or_default()
+            .split(',')
+            .map(|audience| audience.trim().to_string())
+            .filter(|audience| !audience.is_empty())
+            .collect();
+        if !allowed_audiences.contains(&audience) {
+            allowed_audiences.push(audience.clone());
+        }
+
+        JwtConfig { issuer, audience, allowed_audiences, leeway, key_sync_interval, ..config }
+    }
+
+    pub fn is_allowed_audience(&self, audience: &str) -> bool {
+        self.allowed_audiences.iter().any(|allowed| allowed == audience)
+    }
+
+    pub fn keyring(&self) -> RwLockReadGuard<'_, JwtKeyring> {
+        self.keyring.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn keyring_mut(&self) -> RwLockWriteGuard<'_, JwtKeyring> {
+        self.keyring.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn reload_keys(&self) -> Result<usize, AppError> {
+        let keys_dir = self.keys_dir.as_ref()
+            .ok_or_else(|| AppError::new(Some(Message::new("key.not_loaded_from_directory")), None, AppErrorType::BadRequestError))?;
+
+        let keys = keyring::load_keys_dir(keys_dir, self.algorithm)?;
+
+        let mut keyring = self.keyring_mut();
+        Ok(keys.into_iter().map(|key| keyring.insert(key)).filter(|inserted| *inserted).count())
+    }
+
+    // applies the rotations recorded by any server, loading keys the schedule refers to when they were added since
+    pub async fn sync_keys(&self, context: &Database<'_>) -> Result<(), AppError> {
+        let schedules = context.signing_key_schedules.find_all().await
+            .map_err(|error| {
+                error!("Error occured: {:?}", error); 
+                AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            })?;
+
+        let unknown = self.keyring().unknown(&schedules).len();
+        if unknown > 0 && self.keys_dir.is_some() {
+            self.reload_keys()?;
+        }
+
+        let mut keyring = self.keyring_mut();
+        keyring.apply(&schedules);
+
+        for kid in keyring.unknown(&schedules) {
+            warn!("Signing key {} is scheduled but was not loaded by this server", kid);
+        }
+        Ok(())
+    }
+
+    pub async fn promote_key(&self, context: &Database<'_>, kid: &str, activate_at: DateTime<Utc>, retire_previous_after: Duration) -> Result<(), AppError> {
+        let previous_kid = {
+            let keyring = self.keyring();
+            keyring.check_promotable(kid)?;
+            keyring.active().kid.clone()
+        };
+
+        context.signing_key_schedules.promote(kid, &activate_at, &previous_kid, &(activate_at + retire_previous_after)).await
+            .map_err(|error| {
+                error!("Error occured: {:?}", error); 
+                AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            })?;
+
+        self.sync_keys(context).await
+    }
+
+    pub async fn retire_key(&self, context: &Database<'_>, kid: &str, retire_at: DateTime<Utc>) -> Result<(), AppError> {
+        self.keyring().check_retirable(kid)?;
+
+        context.signing_key_schedules.retire(kid, &retire_at).await
+            .map_err(|error| {
+                error!("Error occured: {:?}", error); 
+                AppError::new(None, Some(error.to_string()), AppErrorType::InternalServerError)
+            })?;
+
+        self.sync_keys(context).await
+    }
+}
+
+// other servers pick up a rotation within key_sync_interval, the previous key verifies their tokens meanwhile
+pub fn start_key_sync(context: Arc<Database<'static>>, config: Arc<JwtConfig>) {
+    info!("Syncing signing keys every {}s", config.key_sync_interval);
+
+    rt::spawn(async move {
+        let mut interval = rt::time::interval(StdDuration::from_secs(config.key_sync_interval));
+        loop {
+            interval.tick().await;
+            if let Err(error) = config.sync_keys(&context).await {
+                error!("Error occured: {:?}", error);
+            }
+        }
+    });
+}
+
+pub async fn generate_token(user: User, role: Role, permissions: Vec<Permission>, audience: &str, config: &JwtConfig) -> Result<String , AppError> {
     let now = Utc::now();
     let iat = now.timestamp() as usize;
     let exp = (now + Duration::minutes(config.expires_in)).timestamp() as usize;
 
     let claims: Claims = Claims {
-        sub: user.email_address.to_string(),
-        user,
-        role,
-        permissions,
-        exp,
+        ver: CLAIMS_VERSION,
+        sub: user.user_id.to_string(),
+        jti: util::generate_token_id().await,
+        role: role.name,
+        permissions: permissions.into_iter().map(|permission| permission.name).collect(),
+        iss: config.issuer.clone(),
+        aud: audience.to_string(),
         iat,
+        iat_ms: now.timestamp_millis(),
+        nbf: iat,
+        exp,
     };
 
+    let keyring = config.keyring();
+    let key = keyring.active();
+
+    let mut header = Header::new(key.algorithm);
+    header.kid = Some(key.kid.clone());
+
+    let encoding_key = key.encoding_key.as_ref()
+        .ok_or_else(|| AppError::new(None, Some(format!("Key {} cannot sign tokens!", key.kid)), AppErrorType::InternalServerError))?;
+
     encode(
-        &Header::default(),
+        &header,
         &claims,
-        &EncodingKey::from_secret(config.secret.as_ref()),
+        encoding_key,
     )
     .map_err(|e| {
         AppError::new(None, Some(e.to_string()), AppErrorType::UnAuthorisedError)
@@ -28,20 +222,217 @@ pub async fn generate_token(user: User, role: Role, permissions: Vec<Permission>
 }
 
 pub fn validate_token(token:&str, config: &JwtConfig) -> Result<Claims, AppError> {
+    let header = decode_header(token)
+        .map_err(|e| AppError::new(None, Some(e.to_string()), AppErrorType::UnAuthorisedError))?;
+
+    let keyring = config.keyring();
+
+    // tokens issued before key ids were introduced can only have been signed by the active key
+    let key = match &header.kid {
+        Some(kid) => keyring.find(kid),
+        None => Some(keyring.active()),
+    }
+    .ok_or_else(|| AppError::new(None, Some(format!("Unknown signing key {:?}", header.kid)), AppErrorType::UnAuthorisedError))?;
+
+    let mut validation = Validation::new(key.algorithm);
+    validation.set_issuer(&[&config.issuer]);
+    validation.set_audience(&[&config.audience]);
+    validation.set_required_spec_claims(&["exp", "nbf", "iss", "aud", "sub"]);
+    validation.validate_nbf = true;
+    validation.leeway = config.leeway;
+
     decode::<Claims>(
         token,
-        &DecodingKey::from_secret(config.secret.as_ref()),
-        &Validation::default(),
+        &key.decoding_key,
+        &validation,
     )
-    .map(|r| r.claims)
     .map_err(|e| {
         AppError::new(None, Some(e.to_string()), AppErrorType::UnAuthorisedError)
     })
+    .and_then(|r| {
+        if r.claims.ver != CLAIMS_VERSION || r.claims.user_id().is_none() {
+            Err(AppError::new(None, Some(format!("Unsupported claims version {}", r.claims.ver)), AppErrorType::UnAuthorisedError))
+        } else {
+            Ok(r.claims)
+        }
+    })
 }
 
 
 #[cfg(test)]
 mod jwt_tests {
-    // use super::*;
+    use crate::entity::signing_key_schedule::SigningKeySchedule;
+
+    use super::*;
+
+    fn user() -> User {
+        User {
+            user_id: 1,
+            first_name: "John".into(),
+            middle_name: None,
+            surname: "Smith".into(),
+            email_address: "jsmith@test.com".into(),
+            mobile_number: None,
+            enabled: true,
+            email_confirmed: true,
+            role_id: 1,
+            created_at: Utc::now(),
+            locale: "en".into(),
+        }
+    }
+
+    fn role() -> Role {
+        Role {
+            role_id: 1,
+            name: "SUPER_ADMIN".into(),
+            created_at: Utc::now(),
+            description: None,
+            is_system: true,
+        }
+    }
+
+    fn pem_config(kid: &str, algorithm: Algorithm, private_key: &[u8], public_key: &[u8]) -> JwtConfig {
+        let key = JwtKey::from_pem(kid, algorithm, Some(private_key), public_key).unwrap();
+        JwtConfig::new(JwtKeyring::new(key), 60, 60)
+    }
+
+    async fn assert_round_trip(config: &JwtConfig) {
+        let token = generate_token(user(), role(), vec![], &config.audience, config).await.unwrap();
+
+        let header = decode_header(&token).unwrap();
+        assert_eq!(header.kid.as_ref(), Some(&config.keyring().active().kid));
+
+        let claims = validate_token(&token, config).unwrap();
+
+        assert_eq!(claims.user_id(), Some(1));
+        assert_eq!(claims.role, "SUPER_ADMIN");
+    }
+
+    #[actix_rt::test]
+    async fn hs256_token_round_trips() {
+        let config = JwtConfig::new(JwtKeyring::new(JwtKey::from_secret("secret")), 60, 60);
+
+        assert_round_trip(&config).await;
+        assert!(config.keyring().active().public_jwk.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn rs256_token_round_trips() {
+        let config = pem_config("rs256", Algorithm::RS256,
+            include_bytes!("../tests/fixtures/keys/rs256_private.pem"),
+            include_bytes!("../tests/fixtures/keys/rs256_public.pem"));
+
+        assert_round_trip(&config).await;
+
+        let jwk = config.keyring().active().public_jwk.clone().unwrap();
+        assert_eq!(jwk.kty, "RSA");
+        assert_eq!(jwk.kid.unwrap(), "rs256");
+        assert_eq!(jwk.e.unwrap(), "AQAB");
+    }
+
+    #[actix_rt::test]
+    async fn es256_token_round_trips() {
+        let config = pem_config("es256", Algorithm::ES256,
+            include_bytes!("../tests/fixtures/keys/es256_private.pem"),
+            include_bytes!("../tests/fixtures/keys/es256_public.pem"));
+
+        assert_round_trip(&config).await;
+
+        let jwk = config.keyring().active().public_jwk.clone().unwrap();
+        assert_eq!(jwk.kty, "EC");
+        assert!(jwk.x.is_some() && jwk.y.is_some());
+    }
+
+    #[actix_rt::test]
+    async fn eddsa_token_round_trips() {
+        let config = pem_config("eddsa", Algorithm::EdDSA,
+            include_bytes!("../tests/fixtures/keys/eddsa_private.pem"),
+            include_bytes!("../tests/fixtures/keys/eddsa_public.pem"));
+
+        assert_round_trip(&config).await;
+
+        let jwk = config.keyring().active().public_jwk.clone().unwrap();
+        assert_eq!(jwk.kty, "OKP");
+        assert_eq!(jwk.crv.unwrap(), "Ed25519");
+    }
+
+    #[actix_rt::test]
+    async fn validate_token_returns_error_when_signed_with_unknown_key() {
+        let config = JwtConfig::new(JwtKeyring::new(JwtKey::from_secret("secret")), 60, 60);
+        let other = JwtConfig::new(JwtKeyring::new(JwtKey::from_secret("other")), 60, 60);
+
+        let token = generate_token(user(), role(), vec![], &other.audience, &other).await.unwrap();
+
+        assert!(validate_token(&token, &config).is_err());
+    }
+
+    #[actix_rt::test]
+    async fn validate_token_accepts_tokens_from_previous_key_after_promotion() {
+        let config = JwtConfig::new(JwtKeyring::new(JwtKey::from_secret("first")), 60, 60);
+        let token = generate_token(user(), role(), vec![], &config.audience, &config).await.unwrap();
+
+        let previous_kid = config.keyring().active().kid.clone();
+        let next = JwtKey::from_secret("second");
+        let next_kid = next.kid.clone();
+        config.keyring_mut().insert(next);
+        config.keyring_mut().apply(&[
+            SigningKeySchedule { kid: next_kid.clone(), activate_at: Some(Utc::now()), retire_at: None, updated_at: Utc::now() },
+            SigningKeySchedule { kid: previous_kid, activate_at: None, retire_at: Some(Utc::now() + Duration::minutes(60)), updated_at: Utc::now() },
+        ]);
+
+        assert!(validate_token(&token, &config).is_ok());
+
+        let token = generate_token(user(), role(), vec![], &config.audience, &config).await.unwrap();
+        assert_eq!(decode_header(&token).unwrap().kid, Some(next_kid));
+    }
+
+    #[actix_rt::test]
+    async fn validate_token_returns_error_when_audience_does_not_match() {
+        let config = JwtConfig::new(JwtKeyring::new(JwtKey::from_secret("secret")), 60, 60);
+
+        let token = generate_token(user(), role(), vec![], "sms_dashboard", &config).await.unwrap();
+
+        assert!(validate_token(&token, &config).is_err());
+    }
+
+    #[actix_rt::test]
+    async fn validate_token_returns_error_when_issuer_does_not_match() {
+        let issuer = JwtConfig::new(JwtKeyring::new(JwtKey::from_secret("secret")), 60, 60);
+        let config = JwtConfig { issuer: "another_issuer".to_string(), ..JwtConfig::new(JwtKeyring::new(JwtKey::from_secret("secret")), 60, 60) };
+
+        let token = generate_token(user(), role(), vec![], &issuer.audience, &issuer).await.unwrap();
+
+        assert!(validate_token(&token, &config).is_err());
+    }
+
+    #[actix_rt::test]
+    async fn generate_token_only_embeds_permission_names() {
+        let config = JwtConfig::new(JwtKeyring::new(JwtKey::from_secret("secret")), 60, 60);
+        let permissions = vec![Permission { permission_id: 1, name: "USER_READ".into(), created_at: Utc::now(), description: None, is_system: true }];
+
+        let token = generate_token(user(), role(), permissions, &config.audience, &config).await.unwrap();
+        let claims = validate_token(&token, &config).unwrap();
+
+        assert_eq!(claims.ver, CLAIMS_VERSION);
+        assert_eq!(claims.permissions, vec!["USER_READ".to_string()]);
+    }
+
+    #[actix_rt::test]
+    async fn validate_token_returns_error_when_claims_version_does_not_match() {
+        let config = JwtConfig::new(JwtKeyring::new(JwtKey::from_secret("secret")), 60, 60);
+        let token = generate_token(user(), role(), vec![], &config.audience, &config).await.unwrap();
+
+        let mut claims = validate_token(&token, &config).unwrap();
+        claims.ver = CLAIMS_VERSION + 1;
+
+        let token = {
+            let keyring = config.keyring();
+            let key = keyring.active();
+            let mut header = Header::new(key.algorithm);
+            header.kid = Some(key.kid.clone());
+            encode(&header, &claims, key.encoding_key.as_ref().unwrap()).unwrap()
+        };
 
+        assert!(validate_token(&token, &config).is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,314 @@
+use std::{fs, path::Path};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::VerifyingKey;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use p256::{elliptic_curve::sec1::ToEncodedPoint, pkcs8::DecodePublicKey};
+use rsa::{traits::PublicKeyParts, RsaPublicKey};
+use sha2::{Digest, Sha256};
+
+use crate::{entity::signing_key_schedule::SigningKeySchedule, error::{AppError, AppErrorType}, i18n::Message, model::jwk::Jwk};
+
+pub struct JwtKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub encoding_key: Option<EncodingKey>,
+    pub decoding_key: DecodingKey,
+    pub public_jwk: Option<Jwk>,
+    pub activate_at: Option<DateTime<Utc>>,
+    pub retire_at: Option<DateTime<Utc>>,
+}
+
+impl JwtKey {
+
+    pub fn from_secret(secret: &str) -> Self {
+        let kid = hex::encode(&Sha256::digest(secret.as_bytes())[..4]);
+
+        JwtKey {
+            kid,
+            algorithm: Algorithm::HS256,
+            encoding_key: Some(EncodingKey::from_secret(secret.as_ref())),
+            decoding_key: DecodingKey::from_secret(secret.as_ref()),
+            public_jwk: None,
+            activate_at: None,
+            retire_at: None,
+        }
+    }
+
+    pub fn from_pem(kid: &str, algorithm: Algorithm, private_key: Option<&[u8]>, public_key: &[u8]) -> Result<Self, AppError> {
+        let to_error = |e: jsonwebtoken::errors::Error| AppError::new(None, Some(format!("Invalid key {}: {}", kid, e)), AppErrorType::InternalServerError);
+
+        let (encoding_key, decoding_key) = match algorithm {
+            Algorithm::RS256 => (private_key.map(EncodingKey::from_rsa_pem).transpose().map_err(to_error)?, DecodingKey::from_rsa_pem(public_key).map_err(to_error)?),
+            Algorithm::ES256 => (private_key.map(EncodingKey::from_ec_pem).transpose().map_err(to_error)?, DecodingKey::from_ec_pem(public_key).map_err(to_error)?),
+            Algorithm::EdDSA => (private_key.map(EncodingKey::from_ed_pem).transpose().map_err(to_error)?, DecodingKey::from_ed_pem(public_key).map_err(to_error)?),
+            _ => return Err(AppError::new(None, Some(format!("Algorithm {:?} is not supported!", algorithm)), AppErrorType::InternalServerError)),
+        };
+
+        let public_key = std::str::from_utf8(public_key)
+            .map_err(|e| AppError::new(None, Some(e.to_string()), AppErrorType::InternalServerError))?;
+
+        Ok(JwtKey {
+            kid: kid.to_string(),
+            algorithm,
+            encoding_key,
+            decoding_key,
+            public_jwk: Some(public_jwk(kid, algorithm, public_key)?),
+            activate_at: None,
+            retire_at: None,
+        })
+    }
+
+    pub fn is_retired(&self) -> bool {
+        self.retire_at.is_some_and(|retire_at| retire_at <= Utc::now())
+    }
+
+    fn is_promoted(&self) -> bool {
+        self.encoding_key.is_some() && !self.is_retired() && self.activate_at.is_some_and(|activate_at| activate_at <= Utc::now())
+    }
+}
+
+// the configured key signs until a promotion read from the signing key schedule takes over,
+// the schedule itself is kept in the database so every server applies the same rotation
+pub struct JwtKeyring {
+    configured_kid: String,
+    keys: Vec<JwtKey>,
+}
+
+impl JwtKeyring {
+
+    pub fn new(active: JwtKey) -> Self {
+        JwtKeyring {
+            configured_kid: active.kid.clone(),
+            keys: vec![active],
+        }
+    }
+
+    pub fn from_keys(keys: Vec<JwtKey>, active_kid: &str) -> Result<Self, AppError> {
+        let active = keys.iter().find(|key| key.kid == active_kid)
+            .ok_or_else(|| AppError::new(Some(Message::new("key.not_found").arg("kid", active_kid)), None, AppErrorType::NotFoundError))?;
+
+        if active.encoding_key.is_none() {
+            return Err(AppError::new(Some(Message::new("key.no_private_key").arg("kid", active_kid)), None, AppErrorType::BadRequestError));
+        }
+
+        Ok(JwtKeyring { configured_kid: active_kid.to_string(), keys })
+    }
+
+    // the most recently activated key, so a promotion scheduled for later takes over on time without a sync
+    pub fn active(&self) -> &JwtKey {
+        self.keys.iter()
+            .filter(|key| key.is_promoted())
+            .max_by_key(|key| key.activate_at)
+            .or_else(|| self.keys.iter().find(|key| key.kid == self.configured_kid))
+            .expect("The configured key is always part of the keyring.")
+    }
+
+    pub fn find(&self, kid: &str) -> Option<&JwtKey> {
+        self.keys.iter().find(|key| key.kid == kid && !key.is_retired())
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &JwtKey> {
+        self.keys.iter().filter(|key| !key.is_retired())
+    }
+
+    pub fn is_active(&self, kid: &str) -> bool {
+        self.active().kid == kid
+    }
+
+    pub fn insert(&mut self, key: JwtKey) -> bool {
+        if self.keys.iter().any(|existing| existing.kid == key.kid) {
+            return false;
+        }
+        self.keys.push(key);
+        true
+    }
+
+    pub fn check_promotable(&self, kid: &str) -> Result<(), AppError> {
+        let key = self.find(kid)
+            .ok_or_else(|| AppError::new(Some(Message::new("key.not_found").arg("kid", kid)), None, AppErrorType::NotFoundError))?;
+
+        if key.encoding_key.is_none() {
+            return Err(AppError::new(Some(Message::new("key.no_private_key").arg("kid", kid)), None, AppErrorType::BadRequestError));
+        }
+        Ok(())
+    }
+
+    pub fn check_retirable(&self, kid: &str) -> Result<(), AppError> {
+        if self.is_active(kid) {
+            return Err(AppError::new(Some(Message::new("key.active_cannot_be_retired").arg("kid", kid)), None, AppErrorType::BadRequestError));
+        }
+
+        let key = self.find(kid)
+            .ok_or_else(|| AppError::new(Some(Message::new("key.not_found").arg("kid", kid)), None, AppErrorType::NotFoundError))?;
+
+        if key.activate_at.is_some_and(|activate_at| activate_at > Utc::now()) {
+            return Err(AppError::new(Some(Message::new("key.scheduled_cannot_be_retired").arg("kid", kid)), None, AppErrorType::BadRequestError));
+        }
+        Ok(())
+    }
+
+    // kids the schedule refers to that this server has not loaded
+    pub fn unknown<'a>(&self, schedules: &'a [SigningKeySchedule]) -> Vec<&'a str> {
+        schedules.iter()
+            .filter(|schedule| !self.keys.iter().any(|key| key.kid == schedule.kid))
+            .map(|schedule| schedule.kid.as_str())
+            .collect()
+    }
+
+    // keys without a schedule fall back to the configuration: the configured key signs, the others only verify
+    pub fn apply(&mut self, schedules: &[SigningKeySchedule]) {
+        for key in self.keys.iter_mut() {
+            let schedule = schedules.iter().find(|schedule| schedule.kid == key.kid);
+            key.activate_at = schedule.and_then(|schedule| schedule.activate_at);
+            key.retire_at = schedule.and_then(|schedule| schedule.retire_at);
+        }
+
+        let configured_kid = &self.configured_kid;
+        self.keys.retain(|key| !key.is_retired() || key.kid == *configured_kid);
+    }
+}
+
+// Keys are read from `<kid>.public.pem` files. A matching `<kid>.private.pem` makes the key able to sign,
+// without one the key is only used to verify tokens.
+pub fn load_keys_dir(dir: &Path, algorithm: Algorithm) -> Result<Vec<JwtKey>, AppError> {
+    let to_error = |e: std::io::Error| AppError::new(None, Some(format!("Unable to read keys from {}: {}", dir.display(), e)), AppErrorType::InternalServerError);
+
+    let mut keys = vec![];
+
+    for entry in fs::read_dir(dir).map_err(to_error)? {
+        let path = entry.map_err(to_error)?.path();
+        let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+
+        if let Some(kid) = file_name.strip_suffix(".public.pem") {
+            let public_key = fs::read(&path).map_err(to_error)?;
+            let private_key_path = dir.join(format!("{}.private.pem", kid));
+            let private_key = if private_key_path.exists() { Some(fs::read(&private_key_path).map_err(to_error)?) } else { None };
+
+            keys.push(JwtKey::from_pem(kid, algorithm, private_key.as_deref(), &public_key)?);
+        }
+    }
+
+    Ok(keys)
+}
+
+fn public_jwk(kid: &str, algorithm: Algorithm, public_key: &str) -> Result<Jwk, AppError> {
+    let invalid_key = |e: String| AppError::new(None, Some(format!("Invalid public key {}: {}", kid, e)), AppErrorType::InternalServerError);
+
+    let jwk = Jwk {
+        kty: String::new(),
+        kid: Some(kid.to_string()),
+        public_key_use: "sig".to_string(),
+        alg: format!("{:?}", algorithm),
+        crv: None,
+        n: None,
+        e: None,
+        x: None,
+        y: None,
+    };
+
+    match algorithm {
+        Algorithm::RS256 => {
+            let key = RsaPublicKey::from_public_key_pem(public_key).map_err(|e| invalid_key(e.to_string()))?;
+            Ok(Jwk {
+                kty: "RSA".to_string(),
+                n: Some(URL_SAFE_NO_PAD.encode(key.n().to_bytes_be())),
+                e: Some(URL_SAFE_NO_PAD.encode(key.e().to_bytes_be())),
+                ..jwk
+            })
+        },
+        Algorithm::ES256 => {
+            let key = p256::PublicKey::from_public_key_pem(public_key).map_err(|e| invalid_key(e.to_string()))?;
+            let point = key.to_encoded_point(false);
+            Ok(Jwk {
+                kty: "EC".to_string(),
+                crv: Some("P-256".to_string()),
+                x: point.x().map(|x| URL_SAFE_NO_PAD.encode(x)),
+                y: point.y().map(|y| URL_SAFE_NO_PAD.encode(y)),
+                ..jwk
+            })
+        },
+        Algorithm::EdDSA => {
+            let key = VerifyingKey::from_public_key_pem(public_key).map_err(|e| invalid_key(e.to_string()))?;
+            Ok(Jwk {
+                kty: "OKP".to_string(),
+                crv: Some("Ed25519".to_string()),
+                x: Some(URL_SAFE_NO_PAD.encode(key.as_bytes())),
+                ..jwk
+            })
+        },
+        _ => Err(invalid_key(format!("algorithm {:?} has no public key", algorithm))),
+    }
+}
+
+#[cfg(test)]
+mod keyring_tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn schedule(kid: &str, activate_at: Option<DateTime<Utc>>, retire_at: Option<DateTime<Utc>>) -> SigningKeySchedule {
+        SigningKeySchedule { kid: kid.to_string(), activate_at, retire_at, updated_at: Utc::now() }
+    }
+
+    #[test]
+    fn apply_makes_promoted_key_active_and_keeps_previous_until_retirement() {
+        let mut keyring = JwtKeyring::new(JwtKey::from_secret("first"));
+        let previous_kid = keyring.active().kid.clone();
+        let next = JwtKey::from_secret("second");
+        let next_kid = next.kid.clone();
+        keyring.insert(next);
+
+        keyring.apply(&[
+            schedule(&next_kid, Some(Utc::now()), None),
+            schedule(&previous_kid, None, Some(Utc::now() + Duration::minutes(60))),
+        ]);
+
+        assert_eq!(keyring.active().kid, next_kid);
+        assert!(keyring.find(&previous_kid).unwrap().retire_at.is_some());
+    }
+
+    #[test]
+    fn apply_waits_for_scheduled_activation() {
+        let mut keyring = JwtKeyring::new(JwtKey::from_secret("first"));
+        let configured_kid = keyring.active().kid.clone();
+        let next = JwtKey::from_secret("second");
+        let next_kid = next.kid.clone();
+        keyring.insert(next);
+
+        keyring.apply(&[schedule(&next_kid, Some(Utc::now() + Duration::minutes(60)), None)]);
+
+        assert_eq!(keyring.active().kid, configured_kid);
+        assert!(keyring.check_retirable(&next_kid).is_err());
+    }
+
+    #[test]
+    fn apply_removes_retired_key_from_keyring() {
+        let mut keyring = JwtKeyring::new(JwtKey::from_secret("first"));
+        let old = JwtKey::from_secret("old");
+        let old_kid = old.kid.clone();
+        keyring.insert(old);
+
+        keyring.apply(&[schedule(&old_kid, None, Some(Utc::now()))]);
+
+        assert!(keyring.find(&old_kid).is_none());
+        assert_eq!(keyring.unknown(&[schedule(&old_kid, None, None)]), vec![old_kid.as_str()]);
+    }
+
+    #[test]
+    fn check_retirable_returns_error_for_active_key() {
+        let keyring = JwtKeyring::new(JwtKey::from_secret("first"));
+        let active_kid = keyring.active().kid.clone();
+
+        assert!(keyring.check_retirable(&active_kid).is_err());
+    }
+
+    #[test]
+    fn check_promotable_returns_error_for_verify_only_key() {
+        let mut keyring = JwtKeyring::new(JwtKey::from_secret("first"));
+        keyring.insert(JwtKey::from_pem("rs256", Algorithm::RS256, None, include_bytes!("../tests/fixtures/keys/rs256_public.pem")).unwrap());
+
+        assert!(keyring.check_promotable("rs256").is_err());
+    }
+}
\ No newline at end of file
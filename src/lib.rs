@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use argon2::Config;
+use config::AccountConfig;
 use dao::Database;
+use password_policy::PasswordPolicy;
+use revocation::RevocationStore;
 
 pub mod handler;
 pub mod entity;
@@ -10,16 +13,24 @@ pub mod error;
 pub mod model;
 pub mod util;
 pub mod jwt;
+pub mod keyring;
 pub mod auth;
 pub mod email;
+pub mod revocation;
+pub mod permissions;
+pub mod config;
+pub mod i18n;
+pub mod bootstrap;
+pub mod admin;
+pub mod password_policy;
+
+pub use jwt::JwtConfig;
 
 pub struct AppState<'a> {
     pub context: Arc<Database<'a>>,
     pub argon_config: Arc<Config<'a>>,
     pub jwt_config: Arc<JwtConfig>,
+    pub revocation_store: Arc<RevocationStore>,
+    pub account_config: Arc<AccountConfig>,
+    pub password_policy: Arc<PasswordPolicy>,
 }
-
-pub struct JwtConfig {
-    pub secret: String,
-    pub expires_in: i64,
-}
\ No newline at end of file
@@ -1,6 +1,6 @@
 use actix_web::{ web, App, HttpServer };
-use bulk_sms_api::{handler, AppState, JwtConfig};
-use bulk_sms_api::dao::Database;
+use bulk_sms_api::{bootstrap::{self, BootstrapAdmin}, config::{AccountConfig, DatabaseConfig}, email::{self, outbox::OutboxConfig}, handler, i18n::Localise, jwt, password_policy::PasswordPolicy, AppState, JwtConfig};
+use bulk_sms_api::revocation::RevocationStore;
 use dotenvy::dotenv;
 use log::{info, warn};
 use std::env;
@@ -15,7 +15,6 @@ async fn main() -> std::io::Result<()> {
     dotenv().ok();
 
     const DEFAULT_SERVER_PORT: u16 = 8080;
-    const DEFAULT_MAX_CONNECTIONS: u32 = 5;
 
     log4rs::init_file("log4rs.yaml", Default::default()).unwrap();
 
@@ -30,18 +29,7 @@ async fn main() -> std::io::Result<()> {
             DEFAULT_SERVER_PORT
         });
 
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL has not been set!");
-
-    let max_connections = env::var("MAX_CONNECTIONS")
-        .unwrap_or_else(|_| {
-            warn!("MAX_CONNECTIONS env variable was not provided. Will default to {}", DEFAULT_MAX_CONNECTIONS);
-            DEFAULT_MAX_CONNECTIONS.to_string()
-        }) // Default to 5 if not present
-        .parse()
-        .unwrap_or_else(|_| {
-            warn!("MAX_CONNECTIONS was not of type u32. Will default to {}", DEFAULT_MAX_CONNECTIONS);
-            DEFAULT_MAX_CONNECTIONS
-        });
+    let database_config = DatabaseConfig::from_env();
         
     let config = Config::default();
     
@@ -49,27 +37,59 @@ async fn main() -> std::io::Result<()> {
     
     info!("Starting server at http://{:?}:{}", localhost, server_port);
 
-    let db_context = Database::new(&database_url, max_connections).await;
+    let db_context = database_config.connect().await;
+
+    let jwt_config = JwtConfig::from_env();
+
+    jwt_config.sync_keys(&db_context).await.unwrap_or_else(|error| panic!("{:?}", error));
+
+    let password_policy = PasswordPolicy::from_env().unwrap_or_else(|error| panic!("{}", error));
+
+    let bootstrap_admin = BootstrapAdmin::from_env().unwrap_or_else(|error| panic!("{}", error));
 
-    let secret = env::var("JWT_SECRET").expect("JWT_SECRET was not provided.");
-    let expires_in = env::var("JWT_EXPIRES_IN").expect("JWT_EXPIRES_IN was not provided.").parse::<i64>().expect("JWT_EXPIRES_IN should be an i32.");
+    let report = bootstrap::run(&db_context, &config, &password_policy, bootstrap_admin.as_ref()).await.unwrap_or_else(|error| panic!("{}", error));
+
+    info!("Bootstrap seeded {} permissions and granted {} role permissions", report.permissions_seeded, report.role_permissions_granted);
+    if let Some(admin_id) = report.admin_id {
+        info!("Bootstrapped administrator {}, who must change their password on first sign-in", admin_id);
+    }
+
+    let mut account_config = AccountConfig::from_env();
+
+    account_config.resolve_sign_up_role(&db_context).await.unwrap_or_else(|error| panic!("{}", error));
+
+    let email_sender = email::sender_from_env();
+
+    let revocation_store = RevocationStore::new(account_config.revocation_cache_ttl);
     
-    let jwt_config = JwtConfig {secret, expires_in};
+    let context = Arc::new(db_context);
+
+    let jwt_config = Arc::new(jwt_config);
+
+    email::outbox::start(context.clone(), email_sender, OutboxConfig::from_env());
+
+    jwt::start_key_sync(context.clone(), jwt_config.clone());
     
     let app_state = web::Data::new(AppState {
-        context: Arc::new(db_context),
+        context,
         argon_config: Arc::new(config),
-        jwt_config: Arc::new(jwt_config),
+        jwt_config,
+        revocation_store: Arc::new(revocation_store),
+        account_config: Arc::new(account_config),
+        password_policy: Arc::new(password_policy),
     });
 
     let server = HttpServer::new(move || {
         App::new()
+            .wrap(Localise)
             .wrap(actix_web::middleware::Logger::default())
             .app_data(app_state.clone())
             .service(
                 web
                     ::scope("")
                     .configure(handler::init_auth_handler)
+                    .configure(handler::init_jwks_handler)
+                    .configure(handler::init_password_handler)
             )
             .service(
                 web
@@ -77,19 +97,17 @@ async fn main() -> std::io::Result<()> {
                     .configure(handler::init_permission_handler)
                     .configure(handler::init_role_handler)
                     .configure(handler::init_user_handler)
+                    .configure(handler::init_key_handler)
+                    .configure(handler::init_email_outbox_handler)
+                    .configure(handler::init_email_template_handler)
+                    .configure(handler::init_lockout_handler)
             )
     }).bind((localhost, server_port))
-    .and_then(|result| {
-        info!("Successfully started the server");
-        Ok(result)
-    })?;
+    .inspect(|_| info!("Successfully started the server"))?;
 
     // Run the server
     server.run().await
-    .and_then(|_| {
-        info!("Stopped the server");
-        Ok(())
-    })?;
+    .inspect(|_| info!("Stopped the server"))?;
 
     Ok(())
 }
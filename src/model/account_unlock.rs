@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlockAccount {
+    #[validate(length(min = 1, message = "Unlock token is required!"))]
+    pub token: String,
+}
\ No newline at end of file
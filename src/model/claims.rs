@@ -1,13 +1,26 @@
 use serde::{Deserialize, Serialize};
 
-use crate::entity::{permission::Permission, role::Role, user::User};
+// bump whenever the shape of the claims changes so older tokens are rejected instead of misread
+pub const CLAIMS_VERSION: u8 = 2;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
+    pub ver: u8,
     pub sub: String,
-    pub user: User,
-    pub role: Role,
-    pub permissions: Vec<Permission>,
+    pub jti: String,
+    pub role: String,
+    pub permissions: Vec<String>,
+    pub iss: String,
+    pub aud: String,
     pub iat: usize,
+    // iat in milliseconds, so revocations tell apart the tokens issued within the same second
+    pub iat_ms: i64,
+    pub nbf: usize,
     pub exp: usize,
+}
+
+impl Claims {
+    pub fn user_id(&self) -> Option<i32> {
+        self.sub.parse::<i32>().ok()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteRoleQuery {
+    #[serde(default)]
+    pub force: bool,
+    pub reassign_to: Option<i16>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletePermissionQuery {
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleDependants {
+    pub users: Vec<i32>,
+    pub permissions: Vec<i16>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionDependants {
+    pub roles: Vec<i16>,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Deletion<T> {
+    Deleted,
+    NotFound,
+    Protected,
+    InUse(T),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletionConflict<T> {
+    pub error: String,
+    #[serde(flatten)]
+    pub dependants: T,
+}
\ No newline at end of file
@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailOutboxQuery {
+    pub status: Option<String>,
+}
\ No newline at end of file
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailTemplateSummary {
+    pub name: String,
+    pub subject: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailTemplatePreviewQuery {
+    pub format: Option<String>,
+    pub locale: Option<String>,
+}
\ No newline at end of file
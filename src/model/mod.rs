@@ -5,4 +5,14 @@ pub mod user_credentials;
 pub mod claims;
 pub mod sign_in;
 pub mod token_response;
-pub mod sign_up;
\ No newline at end of file
+pub mod sign_up;
+pub mod refresh_token;
+pub mod sign_out;
+pub mod jwk;
+pub mod signing_key;
+pub mod password_reset;
+pub mod email_outbox;
+pub mod email_template;
+pub mod role_permission;
+pub mod deletion;
+pub mod account_unlock;
\ No newline at end of file
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPassword {
+    #[validate(email(message = "Email address is not valid!"))]
+    pub email_address: String,
+}
+
+#[derive(Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPassword {
+    #[validate(length(min = 1, message = "Reset token is required!"))]
+    pub token: String,
+    #[validate(length(min = 1, message = "Password is required!"))]
+    pub password: String,
+}
\ No newline at end of file
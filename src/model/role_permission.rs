@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRolePermissions {
+    pub permission_ids: Vec<i16>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RolePermissionChanges {
+    pub added: Vec<i16>,
+    pub removed: Vec<i16>,
+}
\ No newline at end of file
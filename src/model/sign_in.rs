@@ -7,4 +7,5 @@ use validator::Validate;
 pub struct SignIn {
     pub email_address: String,
     pub password: String,
+    pub audience: Option<String>,
 }
\ No newline at end of file
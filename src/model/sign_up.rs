@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::i18n::validate_locale;
+
 
 #[derive(Deserialize, Serialize, Validate)]
 #[serde(rename_all = "camelCase")]
@@ -11,6 +13,8 @@ pub struct SignUp {
     pub surname: String,
     #[validate(email(message = "Email address is not valid!"))]
     pub email_address: String,
-    #[validate(length(min = 3, message = "Password is required!"))]
+    #[validate(length(min = 1, message = "Password is required!"))]
     pub password: String,
+    #[validate(custom = "validate_locale")]
+    pub locale: Option<String>,
 }
\ No newline at end of file
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningKey {
+    pub kid: String,
+    pub algorithm: String,
+    pub active: bool,
+    pub can_sign: bool,
+    pub activate_at: Option<DateTime<Utc>>,
+    pub retire_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromoteSigningKey {
+    pub activate_at: Option<DateTime<Utc>>,
+    pub retire_previous_after: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RetireSigningKeyQuery {
+    pub retire_at: Option<DateTime<Utc>>,
+}
\ No newline at end of file
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::i18n::validate_locale;
+
 #[derive(Deserialize, Serialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateUser {
@@ -13,6 +15,8 @@ pub struct CreateUser {
     pub email_address: String,
     pub mobile_number: Option<String>,
     pub role_id: i16,
+    #[validate(custom = "validate_locale")]
+    pub locale: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Validate)]
@@ -27,4 +31,6 @@ pub struct UpdateUser {
     pub enabled: bool,
     pub email_confirmed: bool,
     pub role_id: i16,
+    #[validate(custom = "validate_locale")]
+    pub locale: Option<String>,
 }
\ No newline at end of file
@@ -6,7 +6,7 @@ use validator::Validate;
 pub struct CreateUserCredential {
     #[validate(length(min = 3, message = "Username is required!"))]
     pub username: String,
-    #[validate(length(min = 3, message = "Password is required!"))]
+    #[validate(length(min = 1, message = "Password is required!"))]
     pub password: String,
 }
 
@@ -15,6 +15,6 @@ pub struct CreateUserCredential {
 pub struct UpdateUserCredential {
     #[validate(length(min = 3, message = "Password is required!"))]
     pub previous_password: String,
-    #[validate(length(min = 3, message = "Password is required!"))]
+    #[validate(length(min = 1, message = "Password is required!"))]
     pub password: String,
 }
\ No newline at end of file
@@ -0,0 +1,261 @@
+use std::{env, f64::consts::LN_2, fs::File, io::{self, BufReader, Read, Write}};
+
+use sha2::{Digest, Sha256};
+
+use crate::{error::{AppError, AppErrorType}, i18n::Message};
+
+const DEFAULT_MIN_LENGTH: usize = 8;
+// argon2 works through the whole password, so the length bounds the cost of every hash
+const DEFAULT_MAX_LENGTH: usize = 128;
+pub const DEFAULT_FALSE_POSITIVE_RATE: f64 = 0.001;
+// shorter parts, like initials, would reject too many reasonable passwords
+const MIN_PERSONAL_PART_LENGTH: usize = 3;
+const FILTER_MAGIC: &[u8; 4] = b"PWBF";
+
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub breached_passwords: Option<BreachedPasswords>,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        PasswordPolicy {
+            min_length: DEFAULT_MIN_LENGTH,
+            max_length: DEFAULT_MAX_LENGTH,
+            require_lowercase: false,
+            require_uppercase: false,
+            require_digit: false,
+            require_symbol: false,
+            breached_passwords: None,
+        }
+    }
+}
+
+impl PasswordPolicy {
+
+    pub fn from_env() -> Result<Self, String> {
+        let parse_usize = |name: &str, default: usize| env::var(name)
+            .map(|value| value.parse::<usize>().unwrap_or_else(|_| panic!("{} should be a usize.", name)))
+            .unwrap_or(default);
+
+        let parse_bool = |name: &str| env::var(name)
+            .map(|value| value.parse::<bool>().unwrap_or_else(|_| panic!("{} should be a bool.", name)))
+            .unwrap_or(false);
+
+        let min_length = parse_usize("PASSWORD_MIN_LENGTH", DEFAULT_MIN_LENGTH);
+        let max_length = parse_usize("PASSWORD_MAX_LENGTH", DEFAULT_MAX_LENGTH);
+
+        if min_length > max_length {
+            return Err(format!("PASSWORD_MIN_LENGTH {} is greater than PASSWORD_MAX_LENGTH {}!", min_length, max_length));
+        }
+
+        // no list is checked unless a filter built with the admin binary is configured
+        let breached_passwords = match env::var("PASSWORD_BREACHED_FILTER") {
+            Ok(path) if !path.trim().is_empty() => Some(BreachedPasswords::load(path.trim())?),
+            _ => None,
+        };
+
+        Ok(PasswordPolicy {
+            min_length,
+            max_length,
+            require_lowercase: parse_bool("PASSWORD_REQUIRE_LOWERCASE"),
+            require_uppercase: parse_bool("PASSWORD_REQUIRE_UPPERCASE"),
+            require_digit: parse_bool("PASSWORD_REQUIRE_DIGIT"),
+            require_symbol: parse_bool("PASSWORD_REQUIRE_SYMBOL"),
+            breached_passwords,
+        })
+    }
+
+    // personal holds what the password must not contain, such as the names and email address of its owner
+    pub fn check(&self, password: &str, personal: &[&str]) -> Result<(), AppError> {
+        let length = password.chars().count();
+
+        if length < self.min_length {
+            return Err(rejected(Message::new("password.too_short").arg("min", self.min_length)));
+        }
+
+        if length > self.max_length {
+            return Err(rejected(Message::new("password.too_long").arg("max", self.max_length)));
+        }
+
+        if self.require_lowercase && !password.chars().any(char::is_lowercase) {
+            return Err(rejected(Message::new("password.missing_lowercase")));
+        }
+
+        if self.require_uppercase && !password.chars().any(char::is_uppercase) {
+            return Err(rejected(Message::new("password.missing_uppercase")));
+        }
+
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            return Err(rejected(Message::new("password.missing_digit")));
+        }
+
+        if self.require_symbol && !password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace()) {
+            return Err(rejected(Message::new("password.missing_symbol")));
+        }
+
+        let lowercase = password.to_lowercase();
+        let contains_personal = personal.iter()
+            .flat_map(|value| personal_parts(value))
+            .any(|part| lowercase.contains(&part));
+
+        if contains_personal {
+            return Err(rejected(Message::new("password.contains_personal_information")));
+        }
+
+        if self.breached_passwords.as_ref().is_some_and(|breached| breached.contains(password)) {
+            return Err(rejected(Message::new("password.breached")));
+        }
+
+        Ok(())
+    }
+}
+
+fn rejected(message: Message) -> AppError {
+    AppError::new(Some(message), None, AppErrorType::BadRequestError)
+}
+
+// an email address counts as a whole and by its local part, since the domain alone says little about the owner
+fn personal_parts(value: &str) -> Vec<String> {
+    let value = value.trim().to_lowercase();
+    let local_part = value.split('@').next().unwrap_or_default().to_string();
+
+    [value, local_part].into_iter()
+        .filter(|part| part.chars().count() >= MIN_PERSONAL_PART_LENGTH)
+        .collect()
+}
+
+// a bloom filter over known breached passwords: it can report a false positive but never misses a listed password
+pub struct BreachedPasswords {
+    hash_count: u32,
+    bits: Vec<u8>,
+}
+
+impl BreachedPasswords {
+
+    pub fn build(passwords: &[String], false_positive_rate: f64) -> Self {
+        let count = passwords.len().max(1) as f64;
+        let bit_count = (-count * false_positive_rate.ln() / (LN_2 * LN_2)).ceil().max(8.0) as u64;
+        let hash_count = (bit_count as f64 / count * LN_2).round().max(1.0) as u32;
+
+        let mut filter = BreachedPasswords { hash_count, bits: vec![0u8; bit_count.div_ceil(8) as usize] };
+
+        for password in passwords {
+            for index in filter.indexes(password) {
+                filter.bits[(index / 8) as usize] |= 1 << (index % 8);
+            }
+        }
+
+        filter
+    }
+
+    pub fn contains(&self, password: &str) -> bool {
+        self.indexes(password).all(|index| self.bits[(index / 8) as usize] & (1 << (index % 8)) != 0)
+    }
+
+    pub fn size(&self) -> usize {
+        FILTER_MAGIC.len() + 4 + self.bits.len()
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        File::open(path)
+            .and_then(|file| BreachedPasswords::read(&mut BufReader::new(file)))
+            .map_err(|error| format!("Unable to load the breached password filter {}: {}", path, error))
+    }
+
+    pub fn read(reader: &mut dyn Read) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+
+        let mut hash_count = [0u8; 4];
+        reader.read_exact(&mut hash_count)?;
+        let hash_count = u32::from_le_bytes(hash_count);
+
+        let mut bits = Vec::new();
+        reader.read_to_end(&mut bits)?;
+
+        if &magic != FILTER_MAGIC || hash_count == 0 || bits.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a breached password filter"));
+        }
+
+        Ok(BreachedPasswords { hash_count, bits })
+    }
+
+    pub fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+        writer.write_all(FILTER_MAGIC)?;
+        writer.write_all(&self.hash_count.to_le_bytes())?;
+        writer.write_all(&self.bits)
+    }
+
+    // double hashing derives every probe from a single sha256 of the password
+    fn indexes(&self, password: &str) -> impl Iterator<Item = u64> {
+        let digest = Sha256::digest(password.as_bytes());
+        let first = u64::from_le_bytes(digest[0..8].try_into().unwrap_or_default());
+        let second = u64::from_le_bytes(digest[8..16].try_into().unwrap_or_default()) | 1;
+        let bit_count = self.bits.len() as u64 * 8;
+
+        (0..self.hash_count as u64).map(move |probe| first.wrapping_add(probe.wrapping_mul(second)) % bit_count)
+    }
+}
+
+#[cfg(test)]
+mod password_policy_tests {
+    use super::*;
+
+    fn key(result: Result<(), AppError>) -> Option<&'static str> {
+        result.err().and_then(|error| error.message).map(|message| message.key)
+    }
+
+    #[test]
+    fn check_enforces_length_and_character_classes() {
+        let policy = PasswordPolicy { require_uppercase: true, require_digit: true, require_symbol: true, ..PasswordPolicy::default() };
+
+        assert_eq!(key(policy.check("Ab1!", &[])), Some("password.too_short"));
+        assert_eq!(key(policy.check(&"Ab1!".repeat(40), &[])), Some("password.too_long"));
+        assert_eq!(key(policy.check("abcdefg1!", &[])), Some("password.missing_uppercase"));
+        assert_eq!(key(policy.check("Abcdefgh!", &[])), Some("password.missing_digit"));
+        assert_eq!(key(policy.check("Abcdefgh1", &[])), Some("password.missing_symbol"));
+        assert_eq!(key(policy.check("Abcdefgh1!", &[])), None);
+    }
+
+    #[test]
+    fn check_rejects_personal_information() {
+        let policy = PasswordPolicy::default();
+        let personal = ["John", "Smith", "jsmith@test.com"];
+
+        assert_eq!(key(policy.check("JohnnyRotten", &personal)), Some("password.contains_personal_information"));
+        assert_eq!(key(policy.check("x-JSMITH-x", &personal)), Some("password.contains_personal_information"));
+        assert_eq!(key(policy.check("correct horse battery", &personal)), None);
+        // too short to count as personal
+        assert_eq!(key(policy.check("Jo-correct-horse", &["Jo"])), None);
+    }
+
+    #[test]
+    fn breached_passwords_round_trip_through_the_file_format() {
+        let passwords: Vec<String> = (0..1000).map(|index| format!("breached{}", index)).collect();
+        let filter = BreachedPasswords::build(&passwords, DEFAULT_FALSE_POSITIVE_RATE);
+
+        let mut file = Vec::new();
+        filter.write(&mut file).unwrap();
+        assert_eq!(file.len(), filter.size());
+
+        let filter = BreachedPasswords::read(&mut file.as_slice()).unwrap();
+
+        assert!(passwords.iter().all(|password| filter.contains(password)));
+        let false_positives = (0..1000).filter(|index| filter.contains(&format!("unlisted{}", index))).count();
+        assert!(false_positives < 20, "{} false positives", false_positives);
+
+        let policy = PasswordPolicy { breached_passwords: Some(filter), ..PasswordPolicy::default() };
+        assert_eq!(key(policy.check("breached42", &[])), Some("password.breached"));
+    }
+
+    #[test]
+    fn read_rejects_other_files() {
+        assert!(BreachedPasswords::read(&mut b"not a filter".as_slice()).is_err());
+    }
+}
\ No newline at end of file
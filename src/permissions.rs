@@ -0,0 +1,34 @@
+use crate::auth::RequiredPermission;
+
+macro_rules! permissions {
+    ($($permission:ident => $name:literal),* $(,)?) => {
+        $(
+            pub struct $permission;
+
+            impl RequiredPermission for $permission {
+                const NAME: &'static str = $name;
+            }
+        )*
+
+        pub const ALL: &[&str] = &[$($name),*];
+    };
+}
+
+permissions! {
+    PermissionRead => "PERMISSION_READ",
+    PermissionWrite => "PERMISSION_WRITE",
+    PermissionUpdate => "PERMISSION_UPDATE",
+    PermissionDelete => "PERMISSION_DELETE",
+    RoleRead => "ROLE_READ",
+    RoleWrite => "ROLE_WRITE",
+    RoleUpdate => "ROLE_UPDATE",
+    RoleDelete => "ROLE_DELETE",
+    UserRead => "USER_READ",
+    UserWrite => "USER_WRITE",
+    UserUpdate => "USER_UPDATE",
+    UserDelete => "USER_DELETE",
+    KeyRead => "KEY_READ",
+    KeyUpdate => "KEY_UPDATE",
+    EmailRead => "EMAIL_READ",
+    EmailUpdate => "EMAIL_UPDATE",
+}
\ No newline at end of file
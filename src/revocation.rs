@@ -0,0 +1,79 @@
+use std::{collections::HashMap, sync::RwLock, time::{Duration, Instant}};
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::{dao::Database, entity::revoked_token::UserRevocations, model::claims::Claims};
+
+// revocations are read from the database, so those made by the admin binary, another server or plain sql
+// are seen here once the cached entry of the user is older than cache_ttl
+pub struct RevocationStore {
+    cache_ttl: Duration,
+    users: RwLock<HashMap<i32, (Instant, UserRevocations)>>,
+}
+
+impl RevocationStore {
+
+    pub fn new(cache_ttl: u64) -> Self {
+        RevocationStore { cache_ttl: Duration::from_secs(cache_ttl), users: RwLock::new(HashMap::new()) }
+    }
+
+    pub async fn revoke_token(&self, context: &Database<'_>, user_id: &i32, jti: &String, exp: &usize) -> Result<(), sqlx::Error> {
+        let expires_at = Utc.timestamp_opt(*exp as i64, 0).single().unwrap_or_else(Utc::now);
+        context.revoked_tokens.delete_expired().await?;
+        context.revoked_tokens.create(jti, user_id, &expires_at).await?;
+
+        self.invalidate(user_id);
+        Ok(())
+    }
+
+    pub async fn revoke_user(&self, context: &Database<'_>, user_id: &i32) -> Result<(), sqlx::Error> {
+        context.user_token_revocations.upsert(user_id).await?;
+        context.refresh_tokens.revoke_by_user_id(user_id).await?;
+
+        self.invalidate(user_id);
+        Ok(())
+    }
+
+    // deactivated users only lose their tokens when check_deactivation is set
+    pub async fn is_revoked(&self, context: &Database<'_>, claims: &Claims, check_deactivation: bool) -> Result<bool, sqlx::Error> {
+        let user_id = match claims.user_id() {
+            Some(user_id) => user_id,
+            None => return Ok(false),
+        };
+
+        let cached = self.users.read().unwrap_or_else(|e| e.into_inner())
+            .get(&user_id)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < self.cache_ttl)
+            .map(|(_, revocations)| is_revoked_by(revocations, claims, check_deactivation));
+
+        if let Some(revoked) = cached {
+            return Ok(revoked);
+        }
+
+        let revocations = context.user_token_revocations.find_by_user_id(&user_id).await?;
+        let revoked = is_revoked_by(&revocations, claims, check_deactivation);
+
+        let mut users = self.users.write().unwrap_or_else(|e| e.into_inner());
+        users.retain(|_, (fetched_at, _)| fetched_at.elapsed() < self.cache_ttl);
+        users.insert(user_id, (Instant::now(), revocations));
+
+        Ok(revoked)
+    }
+
+    fn invalidate(&self, user_id: &i32) {
+        self.users.write().unwrap_or_else(|e| e.into_inner()).remove(user_id);
+    }
+
+    // for revocations written along with other changes, such as the users moved off a deleted role
+    pub fn invalidate_all(&self) {
+        self.users.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+fn is_revoked_by(revocations: &UserRevocations, claims: &Claims, check_deactivation: bool) -> bool {
+    let issued_before = |at: Option<&DateTime<Utc>>| at.is_some_and(|at| claims.iat_ms <= at.timestamp_millis());
+
+    revocations.revoked_jtis.contains(&claims.jti)
+        || issued_before(revocations.revoked_at.as_ref())
+        || (check_deactivation && issued_before(revocations.deactivated_at.as_ref()))
+}
@@ -1,7 +1,9 @@
 use rand::RngCore;
 use rand::prelude::*;
+use sha2::{Digest, Sha256};
 
 use argon2::{self, Config};
+use once_cell::sync::OnceCell;
 
 use crate::error::{AppError, AppErrorType};
 
@@ -11,11 +13,26 @@ pub async fn hash_password(plain_password: &String, config: &Config<'_>) -> Resu
         .map_err(|_| AppError::new(None, Some(String::from("Could not encode password!")), AppErrorType::InternalServerError))
 }
 
-pub async fn verify_password(hash: &String, password: &String) -> Result<bool , AppError> {
+pub async fn verify_password(hash: &str, password: &str) -> Result<bool , AppError> {
     argon2::verify_encoded(hash, password.as_bytes())
     .map_err(|_| AppError::new(None, Some(String::from("Could not decode password!")), AppErrorType::InternalServerError))
 }
 
+static DUMMY_PASSWORD_HASH: OnceCell<String> = OnceCell::new();
+
+// spends the same argon2 work as a real check, for accounts that have no password to compare against
+pub async fn verify_dummy_password(password: &str, config: &Config<'_>) -> Result<bool , AppError> {
+    let hash = match DUMMY_PASSWORD_HASH.get() {
+        Some(hash) => hash,
+        None => {
+            let hash = hash_password(&generate_random_hex(16), config).await?;
+            DUMMY_PASSWORD_HASH.get_or_init(|| hash)
+        }
+    };
+
+    verify_password(hash, password).await
+}
+
 async fn generate_salt() -> [u8; 16] {
     let mut rng = rand::thread_rng();
     let mut salt = [0u8; 16];
@@ -28,6 +45,37 @@ pub async fn generate_confirmation_code() -> i32 {
     rng.gen_range(1000..=9999)
 }
 
+pub async fn generate_refresh_token() -> String {
+    generate_random_hex(32)
+}
+
+pub async fn generate_token_family() -> String {
+    generate_random_hex(16)
+}
+
+pub async fn generate_token_id() -> String {
+    generate_random_hex(16)
+}
+
+pub async fn generate_password_reset_token() -> String {
+    generate_random_hex(32)
+}
+
+pub async fn generate_account_unlock_token() -> String {
+    generate_random_hex(32)
+}
+
+pub async fn hash_token(token: &String) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn generate_random_hex(length: usize) -> String {
+    let mut rng = rand::thread_rng();
+    let mut bytes = vec![0u8; length];
+    rng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
 #[cfg(test)]
 mod util_tests {
     use super::*;
@@ -62,4 +110,24 @@ mod util_tests {
         // Ensure salts are not equal
         assert_ne!(salt1, salt2);
     }
+
+    #[actix_rt::test]
+    async fn generate_refresh_token_returns_unique_tokens() {
+        let token1 = generate_refresh_token().await;
+        let token2 = generate_refresh_token().await;
+
+        assert_eq!(token1.len(), 64);
+        assert_ne!(token1, token2);
+    }
+
+    #[actix_rt::test]
+    async fn hash_token_is_deterministic() {
+        let token = generate_refresh_token().await;
+
+        let hash1 = hash_token(&token).await;
+        let hash2 = hash_token(&token).await;
+
+        assert_eq!(hash1, hash2);
+        assert_ne!(hash1, token);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,115 @@
+use argon2::Config;
+use bulk_sms_api::{admin::{self, Command}, dao::Database, password_policy::PasswordPolicy, util};
+use sqlx::Pool;
+
+async fn run(db: &Database<'_>, command: Command) -> Result<String, String> {
+    let mut out = Vec::new();
+    admin::execute(db, &Config::default(), &PasswordPolicy::default(), command, &mut out).await?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn create_user_creates_active_user_with_credential(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let command = Command::CreateUser {
+        first_name: "Ann".to_string(),
+        surname: "Lee".to_string(),
+        email_address: "alee@test.com".to_string(),
+        role: "MARKETER".to_string(),
+        password: Some("Pass12345".to_string()),
+        require_password_change: true,
+    };
+
+    // when
+    let result = run(&db, command).await;
+
+    // then
+    assert!(result.is_ok());
+
+    let user = db.users.find_by_email_address(&"alee@test.com".to_string()).await.unwrap();
+    assert_eq!(user.role_id, 3);
+    assert!(user.enabled);
+    assert!(user.email_confirmed);
+
+    let credential = db.user_credentials.find_by_user_id(&user.user_id).await.unwrap();
+    assert!(credential.password_change_required);
+    assert!(util::verify_password(&credential.password, &"Pass12345".to_string()).await.unwrap());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn set_password_creates_missing_credential(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let command = Command::SetPassword { email_address: "jsmith@test.com".to_string(), password: "Pass12345".to_string(), require_password_change: false };
+
+    // when
+    let result = run(&db, command).await;
+
+    // then
+    assert!(result.is_ok());
+
+    let credential = db.user_credentials.find_by_user_id(&1).await.unwrap();
+    assert!(!credential.password_change_required);
+    assert!(util::verify_password(&credential.password, &"Pass12345".to_string()).await.unwrap());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn disable_user_disables_and_revokes_tokens(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // when
+    let result = run(&db, Command::DisableUser { email_address: "jpope@test.com".to_string() }).await;
+
+    // then
+    assert!(result.is_ok());
+    assert!(!db.users.find_by_id(&2).await.unwrap().enabled);
+    assert!(db.user_token_revocations.find_by_user_id(&2).await.unwrap().revoked_at.is_some());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission")))]
+pub async fn grant_and_revoke_permission_by_name(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // when
+    let granted = run(&db, Command::GrantPermission { role: "ADMIN".to_string(), permission: "PERMISSION_READ".to_string() }).await;
+
+    // then
+    assert!(granted.is_ok());
+    assert_eq!(db.role_permissions.find_role_permissions(&2).await.unwrap().len(), 1);
+
+    // when
+    let revoked = run(&db, Command::RevokePermission { role: "ADMIN".to_string(), permission: "PERMISSION_READ".to_string() }).await;
+
+    // then
+    assert!(revoked.is_ok());
+    assert!(db.role_permissions.find_role_permissions(&2).await.unwrap().is_empty());
+}
+
+#[sqlx::test]
+pub async fn revoke_permission_refuses_core_permissions(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // when
+    let result = run(&db, Command::RevokePermission { role: "SUPER_ADMIN".to_string(), permission: "USER_READ".to_string() }).await;
+
+    // then
+    assert!(result.is_err());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn export_users_prints_csv(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // when
+    let result = run(&db, Command::ExportUsers).await.unwrap();
+
+    // then
+    let lines: Vec<&str> = result.lines().collect();
+
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("user_id,first_name"));
+    assert!(lines[1].starts_with("1,John,,Smith,jsmith@test.com,,false,false,SUPER_ADMIN,en,"));
+}
\ No newline at end of file
@@ -0,0 +1,104 @@
+use argon2::Config;
+use bulk_sms_api::{bootstrap::{self, BootstrapAdmin, BootstrapReport}, dao::Database, password_policy::PasswordPolicy, permissions};
+use sqlx::Pool;
+
+fn admin() -> BootstrapAdmin {
+    BootstrapAdmin {
+        first_name: "System".to_string(),
+        surname: "Administrator".to_string(),
+        email_address: "admin@test.com".to_string(),
+        password: "Pass12345".to_string(),
+    }
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission")))]
+pub async fn run_seeds_system_permissions_and_roles(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let config = Config::default();
+
+    // when
+    let result = bootstrap::run(&db, &config, &PasswordPolicy::default(), None).await;
+
+    // then
+    assert_eq!(result, Ok(BootstrapReport { permissions_seeded: permissions::ALL.len() as u64, role_permissions_granted: permissions::ALL.len() as u64, admin_id: None }));
+
+    let super_admin = db.roles.find_by_name(bootstrap::SUPER_ADMIN_ROLE).await.unwrap();
+    assert!(super_admin.is_system);
+    assert_eq!(db.role_permissions.find_core_permissions(&super_admin.role_id).await.unwrap().len(), permissions::ALL.len());
+
+    let user_role = db.roles.find_by_name("USER").await.unwrap();
+    assert!(!user_role.is_system);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission")))]
+pub async fn run_is_idempotent(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let config = Config::default();
+    bootstrap::run(&db, &config, &PasswordPolicy::default(), Some(&admin())).await.unwrap();
+
+    // when
+    let result = bootstrap::run(&db, &config, &PasswordPolicy::default(), Some(&admin())).await;
+
+    // then
+    assert_eq!(result, Ok(BootstrapReport::default()));
+    assert_eq!(db.users.find_all().await.unwrap().len(), 1);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission")))]
+pub async fn run_creates_admin_who_must_change_password(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let config = Config::default();
+
+    // when
+    let report = bootstrap::run(&db, &config, &PasswordPolicy::default(), Some(&admin())).await.unwrap();
+
+    // then
+    let admin_id = report.admin_id.unwrap();
+    let user = db.users.find_by_id(&admin_id).await.unwrap();
+
+    assert_eq!(user.email_address, "admin@test.com");
+    assert_eq!(user.role_id, db.roles.find_by_name(bootstrap::SUPER_ADMIN_ROLE).await.unwrap().role_id);
+    assert!(user.enabled);
+    assert!(user.email_confirmed);
+
+    let credential = db.user_credentials.find_by_user_id(&admin_id).await.unwrap();
+    assert!(credential.password_change_required);
+    assert_ne!(credential.password, "Pass12345");
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn run_skips_admin_when_a_super_admin_exists(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let config = Config::default();
+
+    // when
+    let report = bootstrap::run(&db, &config, &PasswordPolicy::default(), Some(&admin())).await.unwrap();
+
+    // then
+    assert_eq!(report.admin_id, None);
+    assert!(db.users.find_by_email_address(&"admin@test.com".to_string()).await.is_err());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission")))]
+pub async fn run_rejects_admin_password_the_policy_forbids(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let config = Config::default();
+    let admin = BootstrapAdmin { password: "short".to_string(), ..admin() };
+
+    // when
+    let result = bootstrap::run(&db, &config, &PasswordPolicy::default(), Some(&admin)).await;
+
+    // then
+    assert!(result.is_err());
+    assert!(db.users.find_by_email_address(&"admin@test.com".to_string()).await.is_err());
+}
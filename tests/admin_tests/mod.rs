@@ -0,0 +1,5 @@
+#[cfg(test)]
+mod bootstrap_test;
+
+#[cfg(test)]
+mod admin_test;
\ No newline at end of file
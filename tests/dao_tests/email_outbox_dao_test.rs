@@ -0,0 +1,69 @@
+use bulk_sms_api::{dao::Database, email::{EmailContent, EmailDetails}};
+use chrono::{Duration, Utc};
+use sqlx::Pool;
+
+fn details() -> EmailDetails<'static> {
+    EmailDetails { subject: "Subject", to: "jsmith@test.com", from: "no-reply@bulksms.local" }
+}
+
+fn content() -> EmailContent {
+    EmailContent { html: "<p>Hello</p>".to_string(), text: "Hello".to_string() }
+}
+
+#[sqlx::test]
+pub async fn create_returns_a_pending_email(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let content = content();
+
+    // when
+    let result = db.email_outbox.create(&details(), &content).await;
+
+    // then
+    assert!(result.is_ok());
+
+    let result = result.unwrap();
+
+    assert!(result.email_outbox_id.is_positive());
+    assert_eq!(result.status, "PENDING");
+    assert_eq!(result.recipient, "jsmith@test.com");
+    assert_eq!(result.attempts, 0);
+}
+
+#[sqlx::test]
+pub async fn claim_due_does_not_return_leased_emails(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    db.email_outbox.create(&details(), &content()).await.unwrap();
+    let lease_until = Utc::now() + Duration::minutes(5);
+
+    // when
+    let first = db.email_outbox.claim_due(&10, &lease_until).await.unwrap();
+    let second = db.email_outbox.claim_due(&10, &lease_until).await.unwrap();
+
+    // then
+    assert_eq!(first.len(), 1);
+    assert!(second.is_empty());
+}
+
+#[sqlx::test]
+pub async fn mark_sent_removes_email_from_pending(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let email = db.email_outbox.create(&details(), &content()).await.unwrap();
+
+    // when
+    let result = db.email_outbox.mark_sent(&email.email_outbox_id).await.unwrap();
+
+    // then
+    assert_eq!(result, 1);
+
+    let pending = db.email_outbox.find_by_status(&"PENDING".to_string()).await.unwrap();
+    assert!(pending.is_empty());
+
+    let email = db.email_outbox.find_by_id(&email.email_outbox_id).await.unwrap();
+    assert!(email.sent_at.is_some());
+}
\ No newline at end of file
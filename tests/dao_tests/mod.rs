@@ -15,4 +15,13 @@ mod role_permission_dao_test;
 mod user_dao_test;
 
 #[cfg(test)]
-mod user_credentials_dao_test;
\ No newline at end of file
+mod user_credentials_dao_test;
+
+#[cfg(test)]
+mod refresh_token_dao_test;
+
+#[cfg(test)]
+mod password_reset_token_dao_test;
+
+#[cfg(test)]
+mod email_outbox_dao_test;
\ No newline at end of file
@@ -0,0 +1,80 @@
+use bulk_sms_api::dao::Database;
+use chrono::{Duration, Utc};
+use sqlx::Pool;
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn create_returns_a_password_reset_token(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let user_id = 1;
+    let expires_at = Utc::now() + Duration::minutes(30);
+
+    // when
+    let result = db.password_reset_tokens.create(Some(&user_id), &"jsmith@test.com".to_string(), &"token_hash".to_string(), &expires_at, &0).await;
+
+    // then
+    assert!(result.is_ok());
+
+    let result = result.unwrap();
+
+    assert!(result.password_reset_token_id.is_positive());
+    assert_eq!(result.token_hash, "token_hash");
+    assert!(result.used_at.is_none());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn mark_used_returns_zero_when_token_already_used(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let user_id = 1;
+    let expires_at = Utc::now() + Duration::minutes(30);
+    let token = db.password_reset_tokens.create(Some(&user_id), &"jsmith@test.com".to_string(), &"token_hash".to_string(), &expires_at, &0).await.unwrap();
+
+    // when
+    let first = db.password_reset_tokens.mark_used(&token.password_reset_token_id).await.unwrap();
+    let second = db.password_reset_tokens.mark_used(&token.password_reset_token_id).await.unwrap();
+
+    // then
+    assert_eq!(first, 1);
+    assert_eq!(second, 0);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn create_returns_row_not_found_within_cooldown(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let expires_at = Utc::now() + Duration::minutes(30);
+    db.password_reset_tokens.create(None, &"unknown@test.com".to_string(), &"token_hash_1".to_string(), &expires_at, &60).await.unwrap();
+
+    // when
+    let result = db.password_reset_tokens.create(None, &"unknown@test.com".to_string(), &"token_hash_2".to_string(), &expires_at, &60).await;
+
+    // then
+    assert!(matches!(result, Err(sqlx::Error::RowNotFound)));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn invalidate_others_marks_other_unused_tokens_as_used(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let expires_at = Utc::now() + Duration::minutes(30);
+    db.password_reset_tokens.create(Some(&1), &"jsmith@test.com".to_string(), &"token_hash_1".to_string(), &expires_at, &0).await.unwrap();
+    db.password_reset_tokens.create(Some(&2), &"jpope@test.com".to_string(), &"token_hash_2".to_string(), &expires_at, &0).await.unwrap();
+    let latest = db.password_reset_tokens.create(Some(&1), &"jsmith@test.com".to_string(), &"token_hash_3".to_string(), &expires_at, &0).await.unwrap();
+
+    // when
+    let result = db.password_reset_tokens.invalidate_others(&latest).await.unwrap();
+
+    // then
+    assert_eq!(result, 1);
+
+    let other = db.password_reset_tokens.find_by_token_hash(&"token_hash_2".to_string()).await.unwrap();
+    assert!(other.used_at.is_none());
+
+    let latest = db.password_reset_tokens.find_by_token_hash(&"token_hash_3".to_string()).await.unwrap();
+    assert!(latest.used_at.is_none());
+}
\ No newline at end of file
@@ -1,4 +1,4 @@
-use bulk_sms_api::{entity::permission::CreatePermission, dao::Database};
+use bulk_sms_api::{entity::permission::{CreatePermission, PatchPermission, UpdatePermission}, dao::Database, permissions, model::deletion::{DeletePermissionQuery, Deletion, PermissionDependants}};
 use sqlx::Pool;
 
 #[sqlx::test(fixtures(path = "../fixtures", scripts("permission")))]
@@ -49,7 +49,7 @@ pub async fn find_all_returns_permissions_when_permissions_exists(pool: Pool<sql
 }
 
 #[sqlx::test]
-pub async fn find_all_returns_empty_when_permissions_do_not_exist(pool: Pool<sqlx::Postgres>) {
+pub async fn find_all_returns_system_permissions_when_no_permissions_were_created(pool: Pool<sqlx::Postgres>) {
     let db = Database::test(pool).await;
 
     // when
@@ -60,7 +60,8 @@ pub async fn find_all_returns_empty_when_permissions_do_not_exist(pool: Pool<sql
 
     let permissions = result.unwrap();
 
-    assert_eq!(permissions.len(), 0);
+    assert_eq!(permissions.len(), permissions::ALL.len());
+    assert!(permissions.iter().all(|permission| permission.is_system && permissions::ALL.contains(&permission.name.as_str())));
 }
 
 #[sqlx::test(fixtures(path = "../fixtures", scripts("permission")))]
@@ -93,7 +94,7 @@ pub async fn create_returns_a_permission_when_permission_name_does_not_exist(poo
     let db = Database::test(pool).await;
 
     // given
-    let name = "PERMISSION_READ".to_string();
+    let name = "REPORT_READ".to_string();
     
     let permission = CreatePermission {
         name: name.clone()
@@ -130,37 +131,115 @@ pub async fn create_returns_an_error_when_permission_name_already_exists(pool: P
 }
 
 #[sqlx::test(fixtures(path = "../fixtures", scripts("permission")))]
-pub async fn delete_by_id_returns_rows_affected_eq_one_when_id_exists(pool: Pool<sqlx::Postgres>) {
+pub async fn delete_by_id_returns_deleted_when_id_exists(pool: Pool<sqlx::Postgres>) {
     let db = Database::test(pool).await;
 
     // given
     let permission_id = 1;
 
     // when
-    let result = db.permissions.delete(&permission_id).await;
+    let result = db.permissions.delete(&permission_id, &DeletePermissionQuery::default()).await;
     
     // then
     assert!(result.is_ok());
 
     let result = result.unwrap();
 
-    assert_eq!(result.rows_affected(),  1);
+    assert_eq!(result, Deletion::Deleted);
 }
 
 #[sqlx::test]
-pub async fn delete_by_id_returns_rows_affected_eq_zero_when_id_does_not_exist(pool: Pool<sqlx::Postgres>) {
+pub async fn delete_by_id_returns_not_found_when_id_does_not_exist(pool: Pool<sqlx::Postgres>) {
     let db = Database::test(pool).await;
 
     // given
     let permission_id = 2001;
 
     // when
-    let result = db.permissions.delete(&permission_id).await;
+    let result = db.permissions.delete(&permission_id, &DeletePermissionQuery::default()).await;
     
     // then
     assert!(result.is_ok());
 
     let result = result.unwrap();
 
-    assert_eq!(result.rows_affected(),  0);
+    assert_eq!(result, Deletion::NotFound);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("permission")))]
+pub async fn update_returns_error_when_name_is_taken(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let permission_id = 2;
+    let request = UpdatePermission {
+        name: "PERMISSION_READ".to_string(),
+        description: None,
+    };
+
+    // when
+    let result = db.permissions.update(&permission_id, &request).await;
+
+    // then
+    assert!(matches!(result, Err(sqlx::Error::Database(_))));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("permission")))]
+pub async fn patch_renames_permission_and_keeps_description(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let permission_id = 1;
+    db.permissions.patch(&permission_id, &PatchPermission { name: None, description: Some("List permissions".to_string()) }).await.unwrap();
+
+    // when
+    let result = db.permissions.patch(&permission_id, &PatchPermission { name: Some("PERMISSION_LIST".to_string()), description: None }).await.unwrap();
+
+    // then
+    assert_eq!(result.name, "PERMISSION_LIST");
+    assert_eq!(result.description, Some("List permissions".to_string()));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn delete_returns_in_use_when_permission_is_assigned_to_roles(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let permission_id = 1;
+
+    // when
+    let result = db.permissions.delete(&permission_id, &DeletePermissionQuery::default()).await.unwrap();
+
+    // then
+    assert_eq!(result, Deletion::InUse(PermissionDependants { roles: vec![1] }));
+    assert!(db.permissions.find_by_id(&permission_id).await.is_ok());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn delete_with_force_unlinks_permission_from_roles(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let permission_id = 1;
+
+    // when
+    let result = db.permissions.delete(&permission_id, &DeletePermissionQuery { force: true }).await.unwrap();
+
+    // then
+    assert_eq!(result, Deletion::Deleted);
+    assert_eq!(db.role_permissions.find_role_permissions(&1).await.unwrap().len(), 3);
+}
+
+#[sqlx::test]
+pub async fn delete_returns_protected_for_system_permission(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let permission = db.permissions.find_all().await.unwrap().remove(0);
+
+    // when
+    let result = db.permissions.delete(&permission.permission_id, &DeletePermissionQuery { force: true }).await.unwrap();
+
+    // then
+    assert_eq!(result, Deletion::Protected);
 }
\ No newline at end of file
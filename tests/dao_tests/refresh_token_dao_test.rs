@@ -0,0 +1,82 @@
+use bulk_sms_api::dao::Database;
+use chrono::{Duration, Utc};
+use sqlx::Pool;
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn create_returns_a_refresh_token(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let user_id = 1;
+    let token_hash = "token_hash".to_string();
+    let family_id = "family_id".to_string();
+    let expires_at = Utc::now() + Duration::minutes(60);
+
+    // when
+    let result = db.refresh_tokens.create(&user_id, &token_hash, &family_id, &"bulk_sms_api".to_string(), &expires_at).await;
+
+    // then
+    assert!(result.is_ok());
+
+    let result = result.unwrap();
+
+    assert!(result.refresh_token_id.is_positive());
+    assert_eq!(result.token_hash, "token_hash");
+    assert_eq!(result.family_id, "family_id");
+    assert!(!result.revoked);
+}
+
+#[sqlx::test]
+pub async fn create_returns_error_when_user_id_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let user_id = 1;
+    let expires_at = Utc::now() + Duration::minutes(60);
+
+    // when
+    let result = db.refresh_tokens.create(&user_id, &"token_hash".to_string(), &"family_id".to_string(), &"bulk_sms_api".to_string(), &expires_at).await;
+
+    // then
+    assert!(result.is_err());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn revoke_returns_zero_when_token_already_revoked(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let user_id = 1;
+    let expires_at = Utc::now() + Duration::minutes(60);
+    let token = db.refresh_tokens.create(&user_id, &"token_hash".to_string(), &"family_id".to_string(), &"bulk_sms_api".to_string(), &expires_at).await.unwrap();
+
+    // when
+    let first = db.refresh_tokens.revoke(&token.refresh_token_id).await.unwrap();
+    let second = db.refresh_tokens.revoke(&token.refresh_token_id).await.unwrap();
+
+    // then
+    assert_eq!(first, 1);
+    assert_eq!(second, 0);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn revoke_family_revokes_every_token_in_family(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let user_id = 1;
+    let family_id = "family_id".to_string();
+    let expires_at = Utc::now() + Duration::minutes(60);
+    db.refresh_tokens.create(&user_id, &"token_hash_1".to_string(), &family_id, &"bulk_sms_api".to_string(), &expires_at).await.unwrap();
+    db.refresh_tokens.create(&user_id, &"token_hash_2".to_string(), &family_id, &"bulk_sms_api".to_string(), &expires_at).await.unwrap();
+    db.refresh_tokens.create(&user_id, &"token_hash_3".to_string(), &"other_family".to_string(), &"bulk_sms_api".to_string(), &expires_at).await.unwrap();
+
+    // when
+    let result = db.refresh_tokens.revoke_family(&family_id).await.unwrap();
+
+    // then
+    assert_eq!(result, 2);
+
+    let other = db.refresh_tokens.find_by_token_hash(&"token_hash_3".to_string()).await.unwrap();
+    assert!(!other.revoked);
+}
\ No newline at end of file
@@ -1,4 +1,4 @@
-use bulk_sms_api::{dao::Database, entity::role::CreateRole};
+use bulk_sms_api::{dao::Database, entity::role::{CreateRole, PatchRole, UpdateRole}, model::deletion::{DeleteRoleQuery, Deletion, RoleDependants}};
 use sqlx::Pool;
 
 #[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
@@ -51,7 +51,7 @@ pub async fn find_all_returns_roles_when_roles_exists(pool: Pool<sqlx::Postgres>
 }
 
 #[sqlx::test]
-pub async fn find_all_returns_empty_when_roles_do_not_exist(pool: Pool<sqlx::Postgres>) {
+pub async fn find_all_returns_seeded_roles_when_no_roles_were_created(pool: Pool<sqlx::Postgres>) {
     let db = Database::test(pool).await;
 
     // when
@@ -62,7 +62,9 @@ pub async fn find_all_returns_empty_when_roles_do_not_exist(pool: Pool<sqlx::Pos
 
     let roles = result.unwrap();
 
-    assert_eq!(roles.len(), 0);
+    assert_eq!(roles.len(), 2);
+    assert!(roles.iter().any(|role| role.name == "SUPER_ADMIN" && role.is_system));
+    assert!(roles.iter().any(|role| role.name == "USER" && !role.is_system));
 }
 
 #[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
@@ -95,7 +97,7 @@ pub async fn create_returns_a_role_when_role_name_does_not_exist(pool: Pool<sqlx
     let db = Database::test(pool).await;
 
     // given
-    let name = "SUPER_ADMIN";
+    let name = "SUPPORT";
     
     let role = CreateRole {
         name: name.to_string()
@@ -132,37 +134,230 @@ pub async fn create_returns_an_error_when_role_name_already_exists(pool: Pool<sq
 }
 
 #[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
-pub async fn delete_by_id_returns_rows_affected_eq_one_when_id_exists(pool: Pool<sqlx::Postgres>) {
+pub async fn delete_by_id_returns_deleted_when_id_exists(pool: Pool<sqlx::Postgres>) {
     let db = Database::test(pool).await;
 
     // given
     let role_id = 1;
 
     // when
-    let result = db.roles.delete(&role_id).await;
+    let result = db.roles.delete(&role_id, &DeleteRoleQuery::default()).await;
     
     // then
     assert!(result.is_ok());
 
     let result = result.unwrap();
 
-    assert_eq!(result.rows_affected(),  1);
+    assert_eq!(result, Deletion::Deleted);
 }
 
 #[sqlx::test]
-pub async fn delete_by_id_returns_rows_affected_eq_zero_when_id_does_not_exist(pool: Pool<sqlx::Postgres>) {
+pub async fn delete_by_id_returns_not_found_when_id_does_not_exist(pool: Pool<sqlx::Postgres>) {
     let db = Database::test(pool).await;
 
     // given
     let role_id = 20001;
 
     // when
-    let result = db.roles.delete(&role_id).await;
+    let result = db.roles.delete(&role_id, &DeleteRoleQuery::default()).await;
     
     // then
     assert!(result.is_ok());
 
     let result = result.unwrap();
 
-    assert_eq!(result.rows_affected(),  0);
+    assert_eq!(result, Deletion::NotFound);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn delete_returns_in_use_when_role_has_permissions(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 1;
+
+    // when
+    let result = db.roles.delete(&role_id, &DeleteRoleQuery::default()).await.unwrap();
+    
+    // then
+    assert_eq!(result, Deletion::InUse(RoleDependants { users: vec![], permissions: vec![1, 2, 3, 4] }));
+    assert_eq!(db.role_permissions.find_role_permissions(&role_id).await.unwrap().len(), 4);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn delete_with_force_removes_role_permissions_with_role(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 1;
+    let options = DeleteRoleQuery { force: true, reassign_to: None };
+
+    // when
+    let result = db.roles.delete(&role_id, &options).await.unwrap();
+    
+    // then
+    assert_eq!(result, Deletion::Deleted);
+
+    let result = db.role_permissions.delete_role_permissions(&role_id).await.unwrap();
+    assert_eq!(result, 0);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission", "user")))]
+pub async fn delete_keeps_role_permissions_when_role_is_assigned_to_users(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 1;
+    let options = DeleteRoleQuery { force: true, reassign_to: None };
+
+    // when
+    let result = db.roles.delete(&role_id, &options).await.unwrap();
+    
+    // then
+    assert_eq!(result, Deletion::InUse(RoleDependants { users: vec![1, 2], permissions: vec![] }));
+
+    let permissions = db.role_permissions.find_role_permissions(&role_id).await.unwrap();
+    assert_eq!(permissions.len(), 4);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn delete_with_reassign_to_moves_users_before_deleting(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 1;
+    let options = DeleteRoleQuery { force: false, reassign_to: Some(2) };
+
+    // when
+    let result = db.roles.delete(&role_id, &options).await.unwrap();
+    
+    // then
+    assert_eq!(result, Deletion::Deleted);
+    assert_eq!(db.users.find_by_id(&1).await.unwrap().role_id, 2);
+    assert_eq!(db.users.find_by_id(&2).await.unwrap().role_id, 2);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission", "user")))]
+pub async fn delete_with_reassign_to_keeps_users_when_role_is_still_in_use(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 1;
+    let options = DeleteRoleQuery { force: false, reassign_to: Some(2) };
+
+    // when
+    let result = db.roles.delete(&role_id, &options).await.unwrap();
+    
+    // then
+    assert_eq!(result, Deletion::InUse(RoleDependants { users: vec![], permissions: vec![1, 2, 3, 4] }));
+    assert_eq!(db.users.find_by_id(&1).await.unwrap().role_id, 1);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn update_renames_role_and_sets_description(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 3;
+    let request = UpdateRole {
+        name: "MARKETING".to_string(),
+        description: Some("Runs SMS campaigns".to_string()),
+    };
+
+    // when
+    let result = db.roles.update(&role_id, &request).await.unwrap();
+
+    // then
+    assert_eq!(result.name, "MARKETING");
+    assert_eq!(result.description, Some("Runs SMS campaigns".to_string()));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn update_returns_error_when_name_is_taken(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 3;
+    let request = UpdateRole {
+        name: "ADMIN".to_string(),
+        description: None,
+    };
+
+    // when
+    let result = db.roles.update(&role_id, &request).await;
+
+    // then
+    assert!(matches!(result, Err(sqlx::Error::Database(_))));
+}
+
+#[sqlx::test]
+pub async fn update_returns_row_not_found_when_id_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 20001;
+    let request = UpdateRole {
+        name: "MARKETING".to_string(),
+        description: None,
+    };
+
+    // when
+    let result = db.roles.update(&role_id, &request).await;
+
+    // then
+    assert!(matches!(result, Err(sqlx::Error::RowNotFound)));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn patch_changes_only_provided_fields(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 4;
+    let request = PatchRole {
+        name: None,
+        description: Some("Manages billing".to_string()),
+    };
+
+    // when
+    let result = db.roles.patch(&role_id, &request).await.unwrap();
+
+    // then
+    assert_eq!(result.name, "ACCOUNTANT");
+    assert_eq!(result.description, Some("Manages billing".to_string()));
+}
+
+#[sqlx::test]
+pub async fn delete_returns_protected_for_system_role(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 1;
+    let options = DeleteRoleQuery { force: true, reassign_to: None };
+
+    // when
+    let result = db.roles.delete(&role_id, &options).await.unwrap();
+
+    // then
+    assert_eq!(result, Deletion::Protected);
+    assert!(db.roles.find_by_id(&role_id).await.unwrap().is_system);
+}
+
+#[sqlx::test]
+pub async fn update_keeps_name_of_system_role_but_sets_description(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 1;
+
+    // when
+    let renamed = db.roles.update(&role_id, &UpdateRole { name: "ROOT".to_string(), description: None }).await;
+    let described = db.roles.patch(&role_id, &PatchRole { name: None, description: Some("Full access".to_string()) }).await;
+
+    // then
+    assert!(matches!(renamed, Err(sqlx::Error::RowNotFound)));
+
+    let described = described.unwrap();
+    assert_eq!(described.name, "SUPER_ADMIN");
+    assert_eq!(described.description, Some("Full access".to_string()));
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use bulk_sms_api::dao::db_context::Database;
+use bulk_sms_api::{dao::db_context::Database, model::role_permission::RolePermissionChanges, permissions};
 use sqlx::Pool;
 
 
@@ -72,7 +72,8 @@ pub async fn update_role_permissions_returns_ne_zero_when_updated(pool: Pool<sql
     let result = db.role_permissions.update_role_permissions(&role_id, &vec![]).await.unwrap();
 
     // then
-    assert_eq!(result, 4);
+    assert_eq!(result.removed, vec![1, 2, 3, 4]);
+    assert!(result.added.is_empty());
     let permissions = db.role_permissions.find_role_permissions(&role_id).await.unwrap();
 
     assert_eq!(permissions.len(), 0);
@@ -89,7 +90,7 @@ pub async fn update_role_permissions_returns_eq_zero_when_not_updated(pool: Pool
     let result = db.role_permissions.update_role_permissions(&role_id, &vec![]).await.unwrap();
 
     // then
-    assert_eq!(result, 0);
+    assert_eq!(result, RolePermissionChanges::default());
 
 }
 
@@ -125,4 +126,123 @@ pub async fn delete_role_permissions_returns_eq_zero_when_role_id_does_not_exist
 
     let result = result.unwrap();
     assert_eq!(result, 0);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn update_role_permissions_keeps_existing_permissions_when_insert_fails(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 1;
+    let permission_ids = vec![1, 20001];
+
+    // when
+    let result = db.role_permissions.update_role_permissions(&role_id, &permission_ids).await;
+
+    // then
+    assert!(result.is_err());
+
+    let permissions = db.role_permissions.find_role_permissions(&role_id).await.unwrap();
+    assert_eq!(permissions.len(), 4);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn update_role_permissions_applies_only_the_difference(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 1;
+    let before = db.role_permissions.find_role_permissions(&role_id).await.unwrap();
+
+    // when
+    let result = db.role_permissions.update_role_permissions(&role_id, &vec![2, 3, 3]).await.unwrap();
+
+    // then
+    assert_eq!(result, RolePermissionChanges { added: vec![], removed: vec![1, 4] });
+
+    let after = db.role_permissions.find_role_permissions(&role_id).await.unwrap();
+    let kept = before.iter().filter(|p| p.permission_id == 2 || p.permission_id == 3).collect::<Vec<_>>();
+
+    assert_eq!(after.len(), 2);
+    assert!(after.iter().all(|p| kept.iter().any(|k| k.permission_id == p.permission_id && k.created_at == p.created_at)));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn update_role_permissions_adds_missing_and_removes_extra(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 2;
+    db.role_permissions.update_role_permissions(&role_id, &vec![1, 2]).await.unwrap();
+
+    // when
+    let result = db.role_permissions.update_role_permissions(&role_id, &vec![2, 4, 3]).await.unwrap();
+
+    // then
+    assert_eq!(result, RolePermissionChanges { added: vec![3, 4], removed: vec![1] });
+    assert_eq!(db.role_permissions.find_role_permissions(&role_id).await.unwrap().len(), 3);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("permission")))]
+pub async fn update_role_permissions_returns_error_when_role_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 2000;
+
+    // when
+    let result = db.role_permissions.update_role_permissions(&role_id, &vec![1]).await;
+
+    // then
+    assert!(matches!(result, Err(sqlx::Error::RowNotFound)));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn add_role_permission_ignores_permission_already_assigned(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 1;
+
+    // when
+    let existing = db.role_permissions.add_role_permission(&role_id, &1).await.unwrap();
+    let added = db.role_permissions.add_role_permission(&2, &1).await.unwrap();
+
+    // then
+    assert_eq!(existing, 0);
+    assert_eq!(added, 1);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn remove_role_permission_removes_only_that_permission(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 1;
+
+    // when
+    let result = db.role_permissions.remove_role_permission(&role_id, &2).await.unwrap();
+
+    // then
+    assert_eq!(result, 1);
+    assert_eq!(db.role_permissions.find_role_permissions(&role_id).await.unwrap().len(), 3);
+}
+
+#[sqlx::test]
+pub async fn update_role_permissions_keeps_core_permissions_of_system_role(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let role_id = 1;
+    let core_permissions = db.role_permissions.find_core_permissions(&role_id).await.unwrap();
+
+    // when
+    let result = db.role_permissions.update_role_permissions(&role_id, &vec![]).await.unwrap();
+    let removed = db.role_permissions.remove_role_permission(&role_id, &core_permissions[0]).await.unwrap();
+
+    // then
+    assert_eq!(core_permissions.len(), permissions::ALL.len());
+    assert!(result.removed.is_empty());
+    assert_eq!(removed, 0);
+    assert_eq!(db.role_permissions.find_role_permissions(&role_id).await.unwrap().len(), permissions::ALL.len());
 }
\ No newline at end of file
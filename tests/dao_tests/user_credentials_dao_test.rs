@@ -130,4 +130,18 @@ pub async fn update_returns_a_error_when_user_credential_does_not_exist(pool: Po
     dbg!(":?", &result);
     // then
     assert!(result.is_err());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn create_for_email_address_appends_number_when_username_is_taken(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    db.user_credentials.create_for_email_address(&1, "tester@test.com", &"1234567".to_string()).await.unwrap();
+
+    // when
+    let result = db.user_credentials.create_for_email_address(&2, "tester@other.com", &"1234567".to_string()).await;
+
+    // then
+    assert_eq!(result.unwrap().username, "tester2");
 }
\ No newline at end of file
@@ -98,7 +98,8 @@ pub async fn create_returns_a_user_when_ok(pool: Pool<sqlx::Postgres>) {
         surname: "Doe".to_string(),
         email_address: "jsmith@test.com".to_string(),
         mobile_number: None,
-        role_id: 1
+        role_id: 1,
+        locale: None,
     };
 
     // when
@@ -121,7 +122,8 @@ pub async fn create_returns_an_error_when_user_email_address_already_exists(pool
         surname: "Doe".to_string(),
         email_address: "jsmith@test.com".to_string(),
         mobile_number: None,
-        role_id: 1
+        role_id: 1,
+        locale: None,
     };
 
     // when
@@ -144,7 +146,8 @@ pub async fn update_returns_a_user_when_user_id_exists(pool: Pool<sqlx::Postgres
         mobile_number: Some("0700000000".to_string()),
         enabled: false,
         email_confirmed: false,
-        role_id: 1
+        role_id: 1,
+        locale: None,
     };
 
     // when
@@ -163,6 +166,34 @@ pub async fn update_returns_a_user_when_user_id_exists(pool: Pool<sqlx::Postgres
     assert_eq!(update_user.mobile_number.unwrap(), "0700000000");
 }
 
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn update_changes_locale_only_when_provided(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given 
+    let user_id = 1;   
+    let mut request = UpdateUser {
+        first_name: "John".to_string(),
+        middle_name: None,
+        surname: "Doe".to_string(),
+        mobile_number: None,
+        enabled: false,
+        email_confirmed: false,
+        role_id: 1,
+        locale: Some("fr".to_string()),
+    };
+
+    // when
+    let updated = db.users.update(&user_id, &request).await.unwrap();
+
+    request.locale = None;
+    let unchanged = db.users.update(&user_id, &request).await.unwrap();
+
+    // then
+    assert_eq!(updated.locale, "fr");
+    assert_eq!(unchanged.locale, "fr");
+}
+
 #[sqlx::test]
 pub async fn update_return_error_when_user_id_does_not_exist(pool: Pool<sqlx::Postgres>) {
     let db = Database::test(pool).await;
@@ -176,7 +207,8 @@ pub async fn update_return_error_when_user_id_does_not_exist(pool: Pool<sqlx::Po
         mobile_number: Some("0700000000".to_string()),
         enabled: false,
         email_confirmed: false,
-        role_id: 1
+        role_id: 1,
+        locale: None,
     };
 
     // when
@@ -220,4 +252,39 @@ pub async fn delete_by_id_returns_rows_affected_eq_zero_when_id_does_not_exist(p
     let result = result.unwrap();
 
     assert_eq!(result.rows_affected(),  0);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user", "user_credential")))]
+pub async fn delete_removes_pending_confirmation_code(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let user_id = 1;
+    db.user_code.create(&user_id, &123456).await.unwrap();
+
+    // when
+    let result = db.users.delete(&user_id).await;
+    
+    // then
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap().rows_affected(), 1);
+    assert!(db.user_code.find_by_user_id(&user_id).await.is_err());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user", "user_credential")))]
+pub async fn delete_with_is_undone_when_transaction_rolls_back(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let user_id = 1;
+    let mut transaction = db.begin().await.unwrap();
+
+    // when
+    let result = db.users.delete_with(&mut transaction, &user_id).await;
+    transaction.rollback().await.unwrap();
+    
+    // then
+    assert_eq!(result.unwrap().rows_affected(), 1);
+    assert!(db.users.find_by_id(&user_id).await.is_ok());
+    assert!(db.user_credentials.find_by_user_id(&user_id).await.is_ok());
 }
\ No newline at end of file
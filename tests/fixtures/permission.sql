@@ -1,3 +1,6 @@
+-- replaces the system permissions seeded by migration with known test data
+DELETE FROM "SMS_GATEWAY_USER"."ROLE_PERMISSION";
+DELETE FROM "SMS_GATEWAY_USER"."PERMISSION";
 INSERT INTO "SMS_GATEWAY_USER"."PERMISSION" (permission_id, name) VALUES (1, 'PERMISSION_READ');
 INSERT INTO "SMS_GATEWAY_USER"."PERMISSION" (permission_id, name) VALUES (2, 'PERMISSION_WRITE');
 INSERT INTO "SMS_GATEWAY_USER"."PERMISSION" (permission_id, name) VALUES (3, 'PERMISSION_UPDATE');
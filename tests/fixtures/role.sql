@@ -1,4 +1,9 @@
+-- replaces the system role seeded by migration with known test data
+DELETE FROM "SMS_GATEWAY_USER"."ROLE_PERMISSION";
+DELETE FROM "SMS_GATEWAY_USER"."ROLE";
 INSERT INTO "SMS_GATEWAY_USER"."ROLE"(role_id, name) VALUES (1, 'SUPER_ADMIN');
 INSERT INTO "SMS_GATEWAY_USER"."ROLE"(role_id, name) VALUES (2, 'ADMIN');
 INSERT INTO "SMS_GATEWAY_USER"."ROLE"(role_id, name) VALUES (3, 'MARKETER');
-INSERT INTO "SMS_GATEWAY_USER"."ROLE"(role_id, name) VALUES (4, 'ACCOUNTANT');
\ No newline at end of file
+INSERT INTO "SMS_GATEWAY_USER"."ROLE"(role_id, name) VALUES (4, 'ACCOUNTANT');
+-- explicit ids do not advance the sequence, which still points at the seeded rows
+SELECT setval(pg_get_serial_sequence('"SMS_GATEWAY_USER"."ROLE"', 'role_id'), (SELECT MAX(role_id) FROM "SMS_GATEWAY_USER"."ROLE"));
\ No newline at end of file
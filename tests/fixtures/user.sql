@@ -1,2 +1,4 @@
 INSERT INTO "SMS_GATEWAY_USER"."USER" (user_id, first_name, surname, email_address, role_id) VALUES (1, 'John', 'Smith', 'jsmith@test.com', 1);
 INSERT INTO "SMS_GATEWAY_USER"."USER" (user_id, first_name, surname, email_address, role_id) VALUES (2, 'Jane', 'Pope', 'jpope@test.com', 1);
+-- explicit ids do not advance the sequence, so users created by a test would collide with these
+SELECT setval(pg_get_serial_sequence('"SMS_GATEWAY_USER"."USER"', 'user_id'), (SELECT MAX(user_id) FROM "SMS_GATEWAY_USER"."USER"));
\ No newline at end of file
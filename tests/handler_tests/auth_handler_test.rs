@@ -1,8 +1,10 @@
-use actix_web::{test, App, http};
-use bulk_sms_api::{handler, model::{sign_in::SignIn, sign_up::SignUp, token_response::TokenResponse, user_credentials::CreateUserCredential}, util};
+use actix_web::{test, web::Data, App, http};
+use bulk_sms_api::{bootstrap::{self, BootstrapAdmin}, config::AccountConfig, dao::Database, email::{memory::InMemoryEmailSender, outbox::{self, OutboxConfig}}, error::AppResponseError, handler, i18n::Localise, jwt, AppState, model::{account_unlock::UnlockAccount, app_response::AppResponse, refresh_token::RefreshTokenRequest, sign_in::SignIn, sign_out::SignOut, sign_up::SignUp, token_response::TokenResponse, user_credentials::{CreateUserCredential, UpdateUserCredential}}, util};
+use chrono::{Duration, Utc};
 use sqlx::Pool;
+use std::sync::Arc;
 
-use crate::handler_tests::init_app_state;
+use crate::handler_tests::{activate_user, init_app_state, init_app_state_with_account_config};
 
 #[sqlx::test]
 pub async fn sign_in_returns_unauthorised_when_email_address_does_not_exist(pool: Pool<sqlx::Postgres>) {
@@ -15,7 +17,7 @@ pub async fn sign_in_returns_unauthorised_when_email_address_does_not_exist(pool
     )
     .await;
 
-    let payload = SignIn{email_address: "jsmith@test.com".to_string(), password: "1234567".to_string()};
+    let payload = SignIn{email_address: "jsmith@test.com".to_string(), password: "1234567".to_string(), audience: None};
 
     let request = test::TestRequest::post().uri("/sign-in")
         .set_json(&payload)
@@ -26,6 +28,38 @@ pub async fn sign_in_returns_unauthorised_when_email_address_does_not_exist(pool
     assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
 }
 
+#[sqlx::test]
+pub async fn sign_in_returns_error_in_negotiated_locale(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    
+    let mut app = test::init_service(
+        App::new()
+            .wrap(Localise)
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let payload = SignIn{email_address: "jsmith@test.com".to_string(), password: "1234567".to_string(), audience: None};
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-in")
+        .insert_header(("Accept-Language", "fr-FR, fr;q=0.9, en;q=0.8"))
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(error.error, "Adresse e-mail ou mot de passe invalide !");
+}
+
 #[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
 pub async fn sign_in_returns_unauthorised_when_credentials_do_not_exist(pool: Pool<sqlx::Postgres>) {
     let app_state = init_app_state(pool).await;
@@ -37,7 +71,7 @@ pub async fn sign_in_returns_unauthorised_when_credentials_do_not_exist(pool: Po
     )
     .await;
 
-    let payload = SignIn{email_address: "jsmith@test.com".to_string(), password: "1234567".to_string()};
+    let payload = SignIn{email_address: "jsmith@test.com".to_string(), password: "1234567".to_string(), audience: None};
 
     let request = test::TestRequest::post().uri("/sign-in")
         .set_json(&payload)
@@ -66,7 +100,7 @@ pub async fn sign_in_returns_unauthorised_when_password_does_not_match(pool: Poo
     )
     .await;
 
-    let payload = SignIn{email_address: "jsmith@test.com".to_string(), password: "wrong_password".to_string()};
+    let payload = SignIn{email_address: "jsmith@test.com".to_string(), password: "wrong_password".to_string(), audience: None};
 
     let request = test::TestRequest::post().uri("/sign-in")
         .set_json(&payload)
@@ -86,6 +120,8 @@ pub async fn sign_in_returns_ok(pool: Pool<sqlx::Postgres>) {
     let user_id = 1;
     let password =  "1234567".to_string();
 
+    activate_user(&app_state, user_id).await;
+
     let hashed_password = util::hash_password(&password, &app_state.argon_config).await.unwrap();
 
     app_state.context.user_credentials.create(&user_id, &CreateUserCredential{ username: "tester".into(), password: hashed_password }).await.unwrap();
@@ -97,7 +133,7 @@ pub async fn sign_in_returns_ok(pool: Pool<sqlx::Postgres>) {
     )
     .await;
 
-    let payload = SignIn{email_address: "jsmith@test.com".to_string(), password};
+    let payload = SignIn{email_address: "jsmith@test.com".to_string(), password, audience: None};
 
     let request = test::TestRequest::post().uri("/sign-in")
         .set_json(&payload)
@@ -116,6 +152,47 @@ pub async fn sign_in_returns_ok(pool: Pool<sqlx::Postgres>) {
     dbg!("Token: ", &result.token);
 
     assert!(!result.token.is_empty());
+    assert!(!result.refresh_token.is_empty());
+    assert_eq!(result.token_type, "Bearer");
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn sign_in_rejects_disabled_and_unconfirmed_users(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let password = "1234567".to_string();
+    let hashed_password = util::hash_password(&password, &app_state.argon_config).await.unwrap();
+    app_state.context.user_credentials.create(&1, &CreateUserCredential{ username: "tester".into(), password: hashed_password }).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    let sign_in = || test::TestRequest::post().uri("/sign-in")
+        .set_json(&SignIn{email_address: "jsmith@test.com".to_string(), password: password.clone(), audience: None})
+        .to_request();
+
+    // when
+    let disabled = test::call_service(&mut app, sign_in()).await;
+    let disabled_status = disabled.status();
+    let disabled: AppResponseError = serde_json::from_slice(&test::read_body(disabled).await).unwrap();
+
+    let mut user = app_state.context.users.find_by_id(&1).await.unwrap();
+    user.enabled = true;
+    app_state.context.users.update_user(&user).await.unwrap();
+
+    let unconfirmed = test::call_service(&mut app, sign_in()).await;
+    let unconfirmed_status = unconfirmed.status();
+    let unconfirmed: AppResponseError = serde_json::from_slice(&test::read_body(unconfirmed).await).unwrap();
+
+    // then
+    assert_eq!(disabled_status, http::StatusCode::FORBIDDEN);
+    assert_eq!(disabled.code.as_deref(), Some("auth.account_disabled"));
+    assert_eq!(unconfirmed_status, http::StatusCode::FORBIDDEN);
+    assert_eq!(unconfirmed.code.as_deref(), Some("auth.email_not_confirmed"));
 }
 
 #[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
@@ -134,7 +211,8 @@ pub async fn sign_up_returns_ok(pool: Pool<sqlx::Postgres>) {
         first_name: "John".to_string(),
         surname: "Smith".to_string(),
         email_address: "kanyijavaguru@gmail.com".to_string(),
-        password: "1234567".to_string()
+        password: "Pass12345".to_string(),
+        locale: None,
     };
     // when
 
@@ -147,12 +225,184 @@ pub async fn sign_up_returns_ok(pool: Pool<sqlx::Postgres>) {
 
     dbg!(&response);
 
-    assert_eq!(response.status(), http::StatusCode::CREATED);
+    assert_eq!(response.status(), http::StatusCode::ACCEPTED);
 }
 
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn sign_up_assigns_configured_sign_up_role(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
 
-#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
-pub async fn sign_up_returns_bad_request_when_email_address_exists(pool: Pool<sqlx::Postgres>) {
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let email_address = "jsmith@example.com".to_string();
+    let sign_up_request = SignUp {
+        first_name: "John".to_string(),
+        surname: "Smith".to_string(),
+        email_address: email_address.clone(),
+        password: "Pass12345".to_string(),
+        locale: None,
+    };
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-up")
+        .set_json(&sign_up_request)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::ACCEPTED);
+
+    let user = app_state.context.users.find_by_email_address(&email_address).await.unwrap();
+    assert_eq!(Some(user.role_id), app_state.account_config.sign_up_role_id);
+    assert_eq!(user.role_id, 3);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn sign_up_returns_forbidden_when_sign_up_is_disabled(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state_with_account_config(pool, |config| config.sign_up_enabled = false).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let sign_up_request = SignUp {
+        first_name: "John".to_string(),
+        surname: "Smith".to_string(),
+        email_address: "jsmith@example.com".to_string(),
+        password: "Pass12345".to_string(),
+        locale: None,
+    };
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-up")
+        .set_json(&sign_up_request)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
+
+    assert_eq!(error.error, "Sign-up is disabled, contact an administrator for an account!");
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn sign_up_only_accepts_allowed_email_domains(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state_with_account_config(pool, |config| config.sign_up_allowed_domains = vec!["example.com".to_string()]).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let sign_up = |email_address: &str| SignUp {
+        first_name: "John".to_string(),
+        surname: "Smith".to_string(),
+        email_address: email_address.to_string(),
+        password: "Pass12345".to_string(),
+        locale: None,
+    };
+
+    // when
+    let rejected = test::TestRequest::post().uri("/sign-up")
+        .set_json(&sign_up("jsmith@gmail.com"))
+        .to_request();
+    let rejected = test::call_service(&mut app, rejected).await;
+
+    let accepted = test::TestRequest::post().uri("/sign-up")
+        .set_json(&sign_up("jdoe@Example.com"))
+        .to_request();
+    let accepted = test::call_service(&mut app, accepted).await;
+
+    // then
+    assert_eq!(rejected.status(), http::StatusCode::FORBIDDEN);
+    assert_eq!(accepted.status(), http::StatusCode::ACCEPTED);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn sign_up_rejects_password_against_policy(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let sign_up = |password: &str| SignUp {
+        first_name: "John".to_string(),
+        surname: "Smith".to_string(),
+        email_address: "jsmith@example.com".to_string(),
+        password: password.to_string(),
+        locale: None,
+    };
+
+    // when
+    let short = test::TestRequest::post().uri("/sign-up")
+        .set_json(&sign_up("1234567"))
+        .to_request();
+    let short: AppResponseError = test::call_and_read_body_json(&mut app, short).await;
+
+    let personal = test::TestRequest::post().uri("/sign-up")
+        .set_json(&sign_up("jsmith-rocks"))
+        .to_request();
+    let personal: AppResponseError = test::call_and_read_body_json(&mut app, personal).await;
+
+    // then
+    assert_eq!(short.code.as_deref(), Some("password.too_short"));
+    assert_eq!(short.error, "Password must be at least 8 characters long!");
+    assert_eq!(personal.code.as_deref(), Some("password.contains_personal_information"));
+    assert!(app_state.context.users.find_by_email_address(&"jsmith@example.com".to_string()).await.is_err());
+}
+
+#[sqlx::test]
+pub async fn resolve_sign_up_role_rejects_missing_and_system_roles(pool: Pool<sqlx::Postgres>) {
+    let db = Database::test(pool).await;
+
+    // given
+    let mut missing = AccountConfig::from_env();
+    missing.sign_up_role = "MISSING".to_string();
+
+    let mut system = AccountConfig::from_env();
+    system.sign_up_role = "SUPER_ADMIN".to_string();
+
+    let mut seeded = AccountConfig::from_env();
+    seeded.sign_up_role = "USER".to_string();
+
+    // when
+    let missing_result = missing.resolve_sign_up_role(&db).await;
+    let system_result = system.resolve_sign_up_role(&db).await;
+    let seeded_result = seeded.resolve_sign_up_role(&db).await;
+
+    // then
+    assert!(missing_result.is_err());
+    assert!(system_result.is_err());
+    assert!(seeded_result.is_ok());
+    assert!(seeded.sign_up_role_id.is_some());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn sign_up_sends_confirmation_email(pool: Pool<sqlx::Postgres>) {
+    let email_sender = Arc::new(InMemoryEmailSender::default());
     let app_state = init_app_state(pool).await;
 
     let mut app = test::init_service(
@@ -167,18 +417,925 @@ pub async fn sign_up_returns_bad_request_when_email_address_exists(pool: Pool<sq
         first_name: "John".to_string(),
         surname: "Smith".to_string(),
         email_address: "jsmith@test.com".to_string(),
-        password: "1234567".to_string()
+        password: "Pass12345".to_string(),
+        locale: None,
     };
-    // when
 
+    // when
     let request = test::TestRequest::post().uri("/sign-up")
         .set_json(&sign_up_request)
         .to_request();
 
+    let response = test::call_service(&mut app, request).await;
+
     // then
+    assert_eq!(response.status(), http::StatusCode::ACCEPTED);
+
+    let user = app_state.context.users.find_by_email_address(&"jsmith@test.com".to_string()).await.unwrap();
+    let user_code = app_state.context.user_code.find_by_user_id(&user.user_id).await.unwrap();
+
+    let drained = outbox::drain(&app_state.context, email_sender.as_ref(), &OutboxConfig::from_env()).await.unwrap();
+    assert_eq!(drained, 1);
+
+    let sent = email_sender.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].to, "jsmith@test.com");
+    assert!(sent[0].html.contains(&user_code.code.to_string()));
+    assert!(sent[0].text.contains(&user_code.code.to_string()));
+    assert!(sent[0].text.contains(&user.user_id.to_string()));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn sign_up_stores_negotiated_locale_and_sends_localised_email(pool: Pool<sqlx::Postgres>) {
+    let email_sender = Arc::new(InMemoryEmailSender::default());
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let sign_up_request = SignUp {
+        first_name: "John".to_string(),
+        surname: "Smith".to_string(),
+        email_address: "jsmith@test.com".to_string(),
+        password: "Pass12345".to_string(),
+        locale: None,
+    };
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-up")
+        .insert_header(("Accept-Language", "fr-CA"))
+        .set_json(&sign_up_request)
+        .to_request();
+
     let response = test::call_service(&mut app, request).await;
 
-    dbg!(&response);
+    // then
+    assert_eq!(response.status(), http::StatusCode::ACCEPTED);
+
+    let body = test::read_body(response).await;
+    let result: AppResponse = serde_json::from_slice(&body).unwrap();
+    let user = app_state.context.users.find_by_email_address(&"jsmith@test.com".to_string()).await.unwrap();
+
+    assert_eq!(result.message, "Consultez votre boîte de réception pour terminer l'inscription.");
+    assert_eq!(user.locale, "fr");
+
+    outbox::drain(&app_state.context, email_sender.as_ref(), &OutboxConfig::from_env()).await.unwrap();
+
+    let sent = email_sender.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].subject, "Confirmez votre adresse e-mail");
+    assert!(sent[0].text.contains("Bonjour John"));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn sign_up_rejects_unsupported_locale(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let sign_up_request = SignUp {
+        first_name: "John".to_string(),
+        surname: "Smith".to_string(),
+        email_address: "jsmith@test.com".to_string(),
+        password: "Pass12345".to_string(),
+        locale: Some("xx".to_string()),
+    };
 
+    // when
+    let request = test::TestRequest::post().uri("/sign-up")
+        .set_json(&sign_up_request)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
     assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+
+    let user = app_state.context.users.find_by_email_address(&"jsmith@test.com".to_string()).await;
+    assert!(user.is_err());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user", "user_credential")))]
+pub async fn sign_up_derives_free_username_when_local_part_is_taken(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let sign_up_request = SignUp {
+        first_name: "Tess".to_string(),
+        surname: "Tester".to_string(),
+        email_address: "tester@test.com".to_string(),
+        password: "Pass12345".to_string(),
+        locale: None,
+    };
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-up")
+        .set_json(&sign_up_request)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::ACCEPTED);
+
+    let user = app_state.context.users.find_by_email_address(&"tester@test.com".to_string()).await.unwrap();
+    let credential = app_state.context.user_credentials.find_by_user_id(&user.user_id).await.unwrap();
+    assert_eq!(credential.username, "tester2");
+}
+
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn sign_up_responds_identically_when_email_address_exists(pool: Pool<sqlx::Postgres>) {
+    let email_sender = Arc::new(InMemoryEmailSender::default());
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let sign_up = |email_address: &str| SignUp {
+        first_name: "John".to_string(),
+        surname: "Smith".to_string(),
+        email_address: email_address.to_string(),
+        password: "Pass12345".to_string(),
+        locale: None,
+    };
+
+    // when
+    let existing = test::TestRequest::post().uri("/sign-up")
+        .set_json(&sign_up("jsmith@test.com"))
+        .to_request();
+    let existing = test::call_service(&mut app, existing).await;
+    let existing_status = existing.status();
+    let existing_body = test::read_body(existing).await;
+
+    let created = test::TestRequest::post().uri("/sign-up")
+        .set_json(&sign_up("jdoe@test.com"))
+        .to_request();
+    let created = test::call_service(&mut app, created).await;
+    let created_status = created.status();
+    let created_body = test::read_body(created).await;
+
+    // then
+    assert_eq!(existing_status, http::StatusCode::ACCEPTED);
+    assert_eq!(existing_status, created_status);
+    assert_eq!(existing_body, created_body);
+
+    outbox::drain(&app_state.context, email_sender.as_ref(), &OutboxConfig::from_env()).await.unwrap();
+
+    let sent = email_sender.sent();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[0].to, "jsmith@test.com");
+    assert_eq!(sent[0].subject, "Someone tried to register with your email address");
+    assert_eq!(sent[1].to, "jdoe@test.com");
+    assert_eq!(sent[1].subject, "Confirm your email address");
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn sign_in_responds_identically_for_unknown_address_and_wrong_password(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let hashed_password = util::hash_password(&"1234567".to_string(), &app_state.argon_config).await.unwrap();
+    app_state.context.user_credentials.create(&1, &CreateUserCredential{ username: "tester".to_string(), password: hashed_password }).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let sign_in = |email_address: &str| SignIn{email_address: email_address.to_string(), password: "wrong_password".to_string(), audience: None};
+
+    // when
+    let unknown = test::TestRequest::post().uri("/sign-in")
+        .set_json(&sign_in("nobody@test.com"))
+        .to_request();
+    let unknown = test::call_service(&mut app, unknown).await;
+    let unknown_status = unknown.status();
+    let unknown_body = test::read_body(unknown).await;
+
+    let wrong_password = test::TestRequest::post().uri("/sign-in")
+        .set_json(&sign_in("jsmith@test.com"))
+        .to_request();
+    let wrong_password = test::call_service(&mut app, wrong_password).await;
+    let wrong_password_status = wrong_password.status();
+    let wrong_password_body = test::read_body(wrong_password).await;
+
+    // then
+    assert_eq!(unknown_status, http::StatusCode::UNAUTHORIZED);
+    assert_eq!(unknown_status, wrong_password_status);
+    assert_eq!(unknown_body, wrong_password_body);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission")))]
+pub async fn sign_in_withholds_permissions_until_bootstrapped_admin_changes_password(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    // given
+    let admin = BootstrapAdmin {
+        first_name: "System".to_string(),
+        surname: "Administrator".to_string(),
+        email_address: "admin@test.com".to_string(),
+        password: "Pass12345".to_string(),
+    };
+    let admin_id = bootstrap::run(&app_state.context, &app_state.argon_config, &app_state.password_policy, Some(&admin)).await.unwrap().admin_id.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler)
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-in")
+        .set_json(&SignIn{email_address: admin.email_address.clone(), password: admin.password.clone(), audience: None})
+        .to_request();
+
+    let tokens: TokenResponse = test::call_and_read_body_json(&mut app, request).await;
+
+    // then
+    assert!(tokens.password_change_required);
+
+    let claims = jwt::validate_token(&tokens.token, &app_state.jwt_config).unwrap();
+    assert!(claims.permissions.is_empty());
+
+    // a new password lifts the restriction
+    let credential = app_state.context.user_credentials.find_by_user_id(&admin_id).await.unwrap();
+
+    let request = test::TestRequest::put().uri(&format!("/users/{}/credentials/{}", admin_id, credential.user_credential_id))
+        .insert_header(("Authorization", format!("Bearer {}", tokens.token)))
+        .set_json(&UpdateUserCredential{previous_password: admin.password.clone(), password: "NewPass12345".to_string()})
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = test::TestRequest::post().uri("/sign-in")
+        .set_json(&SignIn{email_address: admin.email_address, password: "NewPass12345".to_string(), audience: None})
+        .to_request();
+
+    let tokens: TokenResponse = test::call_and_read_body_json(&mut app, request).await;
+
+    assert!(!tokens.password_change_required);
+}
+
+fn sign_in_request(password: &str, peer_addr: &str) -> test::TestRequest {
+    test::TestRequest::post().uri("/sign-in")
+        .peer_addr(peer_addr.parse().unwrap())
+        .set_json(&SignIn{email_address: "jsmith@test.com".to_string(), password: password.to_string(), audience: None})
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn sign_in_locks_account_after_repeated_failures(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state_with_account_config(pool, |config| {
+        config.lockout_threshold = 2;
+        config.sign_in_delay_base = 0;
+    }).await;
+
+    let hashed_password = util::hash_password(&"1234567".to_string(), &app_state.argon_config).await.unwrap();
+    app_state.context.user_credentials.create(&1, &CreateUserCredential{ username: "tester".into(), password: hashed_password }).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let first = test::call_service(&mut app, sign_in_request("wrong", "10.0.0.1:4000").to_request()).await;
+    assert_eq!(first.status(), http::StatusCode::UNAUTHORIZED);
+
+    // when
+    let second = test::call_service(&mut app, sign_in_request("wrong", "10.0.0.2:4000").to_request()).await;
+
+    // then
+    assert_eq!(second.status(), http::StatusCode::TOO_MANY_REQUESTS);
+
+    // the right password no longer helps while the lock lasts
+    let correct = test::call_service(&mut app, sign_in_request("1234567", "10.0.0.3:4000").to_request()).await;
+    assert_eq!(correct.status(), http::StatusCode::TOO_MANY_REQUESTS);
+
+    let pending = app_state.context.email_outbox.find_by_status(&"PENDING".to_string()).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].recipient, "jsmith@test.com");
+    assert!(pending[0].plain_text.contains(&app_state.account_config.account_unlock_url));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn sign_in_locks_unknown_addresses_like_registered_ones(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state_with_account_config(pool, |config| {
+        config.lockout_threshold = 2;
+        config.sign_in_delay_base = 0;
+    }).await;
+
+    let hashed_password = util::hash_password(&"1234567".to_string(), &app_state.argon_config).await.unwrap();
+    app_state.context.user_credentials.create(&1, &CreateUserCredential{ username: "tester".into(), password: hashed_password }).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    let sign_in = |email_address: &str, peer_addr: &str| test::TestRequest::post().uri("/sign-in")
+        .peer_addr(peer_addr.parse().unwrap())
+        .set_json(&SignIn{email_address: email_address.to_string(), password: "wrong".to_string(), audience: None})
+        .to_request();
+
+    // given
+    for (email_address, peer_addr) in [("jsmith@test.com", "10.0.0.1:4000"), ("nobody@test.com", "10.0.0.2:4000")] {
+        let response = test::call_service(&mut app, sign_in(email_address, peer_addr)).await;
+        assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+    }
+
+    // when
+    let registered = test::call_service(&mut app, sign_in("jsmith@test.com", "10.0.0.3:4000")).await;
+    let registered_status = registered.status();
+    let registered: AppResponseError = serde_json::from_slice(&test::read_body(registered).await).unwrap();
+
+    let unknown = test::call_service(&mut app, sign_in("Nobody@Test.com", "10.0.0.4:4000")).await;
+    let unknown_status = unknown.status();
+    let unknown: AppResponseError = serde_json::from_slice(&test::read_body(unknown).await).unwrap();
+
+    // then
+    assert_eq!(registered_status, http::StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(unknown_status, registered_status);
+    assert_eq!(unknown.code, registered.code);
+
+    // only the owner of the registered address hears about it
+    let pending = app_state.context.email_outbox.find_by_status(&"PENDING".to_string()).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].recipient, "jsmith@test.com");
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn sign_in_blocks_address_after_repeated_failures(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state_with_account_config(pool, |config| {
+        config.ip_lockout_threshold = 2;
+        config.sign_in_delay_base = 0;
+    }).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    for email_address in ["nobody@test.com", "someone@test.com"] {
+        let request = test::TestRequest::post().uri("/sign-in")
+            .peer_addr("10.0.0.1:4000".parse().unwrap())
+            .set_json(&SignIn{email_address: email_address.to_string(), password: "wrong".to_string(), audience: None})
+            .to_request();
+
+        assert_eq!(test::call_service(&mut app, request).await.status(), http::StatusCode::UNAUTHORIZED);
+    }
+
+    // when
+    let blocked = test::call_service(&mut app, sign_in_request("wrong", "10.0.0.1:4001").to_request()).await;
+    let other = test::call_service(&mut app, sign_in_request("wrong", "10.0.0.2:4000").to_request()).await;
+
+    // then
+    assert_eq!(blocked.status(), http::StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(other.status(), http::StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn unlock_account_clears_lockout_and_consumes_token(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let config = &app_state.account_config;
+    for _ in 0..config.lockout_threshold {
+        app_state.context.email_lockouts.record_failure("jsmith@test.com", &config.lockout_window, &config.lockout_threshold, &config.lockout_duration).await.unwrap();
+    }
+
+    let token = util::generate_account_unlock_token().await;
+    let token_hash = util::hash_token(&token).await;
+    app_state.context.account_unlock_tokens.create(&1, &token_hash, &(Utc::now() + Duration::minutes(15))).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post().uri("/account/unlock")
+        .set_json(&UnlockAccount{ token: token.clone() })
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert!(app_state.context.email_lockouts.find_by_email_address("jsmith@test.com").await.unwrap().is_none());
+
+    let request = test::TestRequest::post().uri("/account/unlock")
+        .set_json(&UnlockAccount{ token })
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+}
+
+async fn sign_in_for_tokens(app_state: &Data<AppState<'static>>) -> TokenResponse {
+    let password =  "1234567".to_string();
+    activate_user(app_state, 1).await;
+    let hashed_password = util::hash_password(&password, &app_state.argon_config).await.unwrap();
+
+    app_state.context.user_credentials.create(&1, &CreateUserCredential{ username: "tester".into(), password: hashed_password }).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    let request = test::TestRequest::post().uri("/sign-in")
+        .set_json(&SignIn{email_address: "jsmith@test.com".to_string(), password, audience: None})
+        .to_request();
+
+    test::call_and_read_body_json(&mut app, request).await
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn refresh_returns_ok_with_rotated_token(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let tokens = sign_in_for_tokens(&app_state).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::post().uri("/refresh")
+        .set_json(&RefreshTokenRequest{ refresh_token: tokens.refresh_token.clone() })
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let result: TokenResponse = serde_json::from_slice(&body).expect("Failed to deserialize error");
+
+    assert!(!result.token.is_empty());
+    assert_ne!(result.refresh_token, tokens.refresh_token);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn refresh_returns_unauthorised_and_revokes_family_when_token_is_reused(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let tokens = sign_in_for_tokens(&app_state).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let request = test::TestRequest::post().uri("/refresh")
+        .set_json(&RefreshTokenRequest{ refresh_token: tokens.refresh_token.clone() })
+        .to_request();
+    let rotated: TokenResponse = test::call_and_read_body_json(&mut app, request).await;
+
+    // when
+    let request = test::TestRequest::post().uri("/refresh")
+        .set_json(&RefreshTokenRequest{ refresh_token: tokens.refresh_token.clone() })
+        .to_request();
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+
+    let request = test::TestRequest::post().uri("/refresh")
+        .set_json(&RefreshTokenRequest{ refresh_token: rotated.refresh_token })
+        .to_request();
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+pub async fn refresh_returns_unauthorised_when_token_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    let request = test::TestRequest::post().uri("/refresh")
+        .set_json(&RefreshTokenRequest{ refresh_token: "does_not_exist".to_string() })
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn sign_out_revokes_access_and_refresh_tokens(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let tokens = sign_in_for_tokens(&app_state).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-out")
+        .insert_header(("Authorization", format!("Bearer {}", tokens.token)))
+        .set_json(&SignOut{ refresh_token: Some(tokens.refresh_token.clone()) })
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = test::TestRequest::post().uri("/sign-out")
+        .insert_header(("Authorization", format!("Bearer {}", tokens.token)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+
+    let request = test::TestRequest::post().uri("/refresh")
+        .set_json(&RefreshTokenRequest{ refresh_token: tokens.refresh_token })
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn sign_in_returns_bad_request_when_audience_is_not_allowed(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    let payload = SignIn{email_address: "jsmith@test.com".to_string(), password: "1234567".to_string(), audience: Some("billing_api".to_string())};
+
+    let request = test::TestRequest::post().uri("/sign-in")
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn sign_in_returns_bad_request_when_password_is_too_long(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let password = "a".repeat(app_state.password_policy.max_length + 1);
+    let payload = SignIn{email_address: "jsmith@test.com".to_string(), password, audience: None};
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-in")
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
+    assert_eq!(error.code.as_deref(), Some("password.too_long"));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn confirm_email_address_returns_ok_when_code_matches(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let user_id = 1;
+    let code = 1234;
+    app_state.context.user_code.create(&user_id, &code).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-up/1/verify/1234")
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let user = app_state.context.users.find_by_id(&user_id).await.unwrap();
+    assert!(user.email_confirmed);
+    assert!(user.enabled);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn confirm_email_address_returns_bad_request_when_email_is_already_confirmed(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    activate_user(&app_state, 1).await;
+    app_state.context.user_code.create(&1, &1234).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-up/1/verify/1234")
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
+    assert_eq!(error.code.as_deref(), Some("auth.email_already_confirmed"));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn confirm_email_address_returns_bad_request_after_too_many_attempts(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let user_id = 1;
+    let code = 1234;
+    app_state.context.user_code.create(&user_id, &code).await.unwrap();
+
+    for _ in 0..app_state.account_config.confirmation_code_max_attempts {
+        let request = test::TestRequest::post().uri("/sign-up/1/verify/4321")
+            .to_request();
+
+        let response = test::call_service(&mut app, request).await;
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+    }
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-up/1/verify/1234")
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+
+    let user = app_state.context.users.find_by_id(&user_id).await.unwrap();
+    assert!(!user.email_confirmed);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn confirm_email_address_returns_bad_request_when_code_has_expired(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let user_id = 1;
+    let code = 1234;
+    app_state.context.user_code.create(&user_id, &code).await.unwrap();
+
+    sqlx::query(r#"UPDATE "SMS_GATEWAY_USER"."USER_CODE" SET created_at = created_at - interval '1 day' WHERE user_id = $1 "#)
+        .bind(user_id)
+        .execute(&*app_state.context.user_code.pool)
+        .await
+        .unwrap();
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-up/1/verify/1234")
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn resend_confirmation_code_regenerates_the_code(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state_with_account_config(pool, |config| config.confirmation_code_resend_cooldown = 0).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let user_id = 1;
+    app_state.context.user_code.create(&user_id, &1234).await.unwrap();
+    app_state.context.user_code.record_attempt(&user_id).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-up/1/verify/resend")
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let user_code = app_state.context.user_code.find_by_user_id(&user_id).await.unwrap();
+    assert_eq!(user_code.attempts, 0);
+    assert_eq!(user_code.resend_count, 1);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn resend_confirmation_code_lets_users_confirm_after_exhausting_attempts(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state_with_account_config(pool, |config| config.confirmation_code_resend_cooldown = 0).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let user_id = 1;
+    app_state.context.user_code.create(&user_id, &1234).await.unwrap();
+    for _ in 0..app_state.account_config.confirmation_code_max_attempts + 1 {
+        app_state.context.user_code.record_attempt(&user_id).await.unwrap();
+    }
+
+    let response = test::call_service(&mut app, test::TestRequest::post().uri("/sign-up/1/verify/resend").to_request()).await;
+    assert_eq!(response.status(), http::StatusCode::OK);
+    let user_code = app_state.context.user_code.find_by_user_id(&user_id).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post().uri(&format!("/sign-up/1/verify/{}", user_code.code))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert!(app_state.context.users.find_by_id(&user_id).await.unwrap().email_confirmed);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn resend_confirmation_code_waits_for_the_cooldown(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    app_state.context.user_code.create(&1, &1234).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post().uri("/sign-up/1/verify/resend")
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::TOO_MANY_REQUESTS);
+
+    let result: AppResponseError = serde_json::from_slice(&test::read_body(response).await).unwrap();
+    assert_eq!(result.code, Some("auth.confirmation_resend_too_soon".to_string()));
+
+    let user_code = app_state.context.user_code.find_by_user_id(&1).await.unwrap();
+    assert_eq!(user_code.code, 1234);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn resend_confirmation_code_stops_after_max_resends(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state_with_account_config(pool, |config| {
+        config.confirmation_code_resend_cooldown = 0;
+        config.confirmation_code_max_resends = 1;
+    }).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    app_state.context.user_code.create(&1, &1234).await.unwrap();
+
+    let first = test::call_service(&mut app, test::TestRequest::post().uri("/sign-up/1/verify/resend").to_request()).await;
+    assert_eq!(first.status(), http::StatusCode::OK);
+
+    // when
+    let response = test::call_service(&mut app, test::TestRequest::post().uri("/sign-up/1/verify/resend").to_request()).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::TOO_MANY_REQUESTS);
+
+    let result: AppResponseError = serde_json::from_slice(&test::read_body(response).await).unwrap();
+    assert_eq!(result.code, Some("auth.confirmation_resend_limit_reached".to_string()));
+}
+
+#[sqlx::test]
+pub async fn resend_confirmation_code_returns_not_found_when_user_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::post().uri("/sign-up/1/verify/resend")
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
 }
\ No newline at end of file
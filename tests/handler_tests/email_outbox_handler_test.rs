@@ -0,0 +1,210 @@
+use actix_web::{test, App, http};
+use async_trait::async_trait;
+use bulk_sms_api::{email::{memory::InMemoryEmailSender, outbox::{self, OutboxConfig}, EmailContent, EmailDetails, EmailSender}, entity::email_outbox::EmailOutbox, error::{AppError, AppErrorType}, handler};
+use sqlx::Pool;
+
+use crate::handler_tests::{generate_token, generate_token_with_permissions, init_app_state};
+
+struct FailingEmailSender;
+
+#[async_trait]
+impl EmailSender for FailingEmailSender {
+    async fn send(&self, _: &EmailDetails<'_>, _: &EmailContent) -> Result<(), AppError> {
+        Err(AppError::new(None, Some("Connection refused".to_string()), AppErrorType::InternalServerError))
+    }
+}
+
+fn config(max_attempts: i32) -> OutboxConfig {
+    OutboxConfig { poll_interval: 1, batch_size: 10, max_attempts, backoff_base: 0, backoff_max: 0, lease: 0, dead_retention: 30 }
+}
+
+fn details() -> EmailDetails<'static> {
+    EmailDetails { subject: "Subject", to: "jsmith@test.com", from: "no-reply@bulksms.local" }
+}
+
+fn content() -> EmailContent {
+    EmailContent { html: "<p>Hello</p>".to_string(), text: "Hello".to_string() }
+}
+
+#[sqlx::test]
+pub async fn drain_marks_email_dead_after_max_attempts(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    // given
+    let email = app_state.context.email_outbox.create(&details(), &content()).await.unwrap();
+
+    // when
+    outbox::drain(&app_state.context, &FailingEmailSender, &config(2)).await.unwrap();
+    let first = app_state.context.email_outbox.find_by_id(&email.email_outbox_id).await.unwrap();
+
+    outbox::drain(&app_state.context, &FailingEmailSender, &config(2)).await.unwrap();
+    let second = app_state.context.email_outbox.find_by_id(&email.email_outbox_id).await.unwrap();
+
+    // then
+    assert_eq!(first.status, "PENDING");
+    assert_eq!(first.attempts, 1);
+    assert_eq!(first.last_error, Some("Connection refused".to_string()));
+
+    assert_eq!(second.status, "DEAD");
+    assert_eq!(second.attempts, 2);
+}
+
+#[sqlx::test]
+pub async fn drain_clears_the_bodies_of_sent_emails(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let email_sender = InMemoryEmailSender::default();
+
+    // given
+    let email = app_state.context.email_outbox.create(&details(), &content()).await.unwrap();
+
+    // when
+    outbox::drain(&app_state.context, &email_sender, &config(2)).await.unwrap();
+
+    // then
+    let sent = app_state.context.email_outbox.find_by_id(&email.email_outbox_id).await.unwrap();
+
+    assert_eq!(sent.status, "SENT");
+    assert!(sent.html.is_empty());
+    assert!(sent.plain_text.is_empty());
+    assert_eq!(email_sender.sent()[0].text, "Hello");
+}
+
+#[sqlx::test]
+pub async fn purge_deletes_dead_emails_past_retention(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool.clone()).await;
+
+    // given
+    let expired = app_state.context.email_outbox.create(&details(), &content()).await.unwrap();
+    let recent = app_state.context.email_outbox.create(&details(), &content()).await.unwrap();
+    outbox::drain(&app_state.context, &FailingEmailSender, &config(1)).await.unwrap();
+
+    sqlx::query!(r#"UPDATE "SMS_GATEWAY_USER"."EMAIL_OUTBOX" SET created_at = CURRENT_TIMESTAMP - INTERVAL '31 days' WHERE email_outbox_id = $1 "#, expired.email_outbox_id)
+        .execute(&pool)
+        .await.unwrap();
+
+    // when
+    let purged = outbox::purge(&app_state.context, &config(1)).await.unwrap();
+
+    // then
+    assert_eq!(purged, 1);
+    assert!(app_state.context.email_outbox.find_by_id(&expired.email_outbox_id).await.is_err());
+    assert_eq!(app_state.context.email_outbox.find_by_id(&recent.email_outbox_id).await.unwrap().status, "DEAD");
+}
+
+#[sqlx::test]
+pub async fn get_email_outbox_returns_failed_emails(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_email_outbox_handler),
+    )
+    .await;
+
+    // given
+    app_state.context.email_outbox.create(&details(), &content()).await.unwrap();
+    outbox::drain(&app_state.context, &FailingEmailSender, &config(1)).await.unwrap();
+
+    // when
+    let request = test::TestRequest::get()
+        .uri("/email-outbox")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let emails: Vec<EmailOutbox> = serde_json::from_slice(&body).expect("Failed to deserialize emails");
+
+    assert_eq!(emails.len(), 1);
+    assert_eq!(emails[0].status, "DEAD");
+}
+
+#[sqlx::test]
+pub async fn retry_email_requeues_failed_email(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_email_outbox_handler),
+    )
+    .await;
+
+    // given
+    let email = app_state.context.email_outbox.create(&details(), &content()).await.unwrap();
+    outbox::drain(&app_state.context, &FailingEmailSender, &config(1)).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post()
+        .uri(&format!("/email-outbox/{}/retry", email.email_outbox_id))
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let email = app_state.context.email_outbox.find_by_id(&email.email_outbox_id).await.unwrap();
+    assert_eq!(email.status, "PENDING");
+    assert_eq!(email.attempts, 0);
+}
+
+#[sqlx::test]
+pub async fn retry_email_returns_not_found_when_email_has_not_failed(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_email_outbox_handler),
+    )
+    .await;
+
+    // given
+    let email = app_state.context.email_outbox.create(&details(), &content()).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post()
+        .uri(&format!("/email-outbox/{}/retry", email.email_outbox_id))
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+pub async fn get_email_outbox_returns_forbidden_without_permission(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &[]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_email_outbox_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::get()
+        .uri("/email-outbox")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+}
\ No newline at end of file
@@ -0,0 +1,175 @@
+use actix_web::{test, App, http};
+use bulk_sms_api::{email::catalogue, handler};
+use serde_json::Value;
+use sqlx::Pool;
+
+use crate::handler_tests::{generate_token, generate_token_with_permissions, init_app_state};
+
+#[sqlx::test]
+pub async fn get_email_templates_lists_every_template(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_email_template_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::get()
+        .uri("/email-templates")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let templates: Vec<Value> = serde_json::from_slice(&body).expect("Failed to deserialize templates");
+
+    assert_eq!(templates.len(), catalogue::TEMPLATES.len());
+    assert_eq!(templates[0]["name"], "confirmation");
+    assert_eq!(templates[0]["subject"], "Confirm your email address");
+}
+
+#[sqlx::test]
+pub async fn preview_email_template_renders_html_by_default(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_email_template_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::get()
+        .uri("/email-templates/welcome/preview")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert!(response.headers().get("content-type").unwrap().to_str().unwrap().starts_with("text/html"));
+
+    let body = test::read_body(response).await;
+    let body = String::from_utf8(body.to_vec()).unwrap();
+
+    assert!(body.contains("<html"));
+}
+
+#[sqlx::test]
+pub async fn preview_email_template_renders_plain_text(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_email_template_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::get()
+        .uri("/email-templates/password_reset/preview?format=text")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert!(response.headers().get("content-type").unwrap().to_str().unwrap().starts_with("text/plain"));
+
+    let body = test::read_body(response).await;
+    let body = String::from_utf8(body.to_vec()).unwrap();
+
+    assert!(!body.contains('<'));
+}
+
+#[sqlx::test]
+pub async fn preview_email_template_renders_requested_locale(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_email_template_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::get()
+        .uri("/email-templates/welcome/preview?locale=fr")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let body = String::from_utf8(body.to_vec()).unwrap();
+
+    assert!(body.contains("lang=\"fr\""));
+    assert!(body.contains("Bienvenue sur Bulk SMS"));
+}
+
+#[sqlx::test]
+pub async fn preview_email_template_returns_not_found_for_unknown_template(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_email_template_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::get()
+        .uri("/email-templates/unknown/preview")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+pub async fn get_email_templates_returns_forbidden_without_permission(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &[]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_email_template_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::get()
+        .uri("/email-templates")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+}
\ No newline at end of file
@@ -0,0 +1,31 @@
+use actix_web::{test, App, http};
+use bulk_sms_api::{handler, model::jwk::JwkSet};
+use sqlx::Pool;
+
+use crate::handler_tests::init_app_state;
+
+#[sqlx::test]
+pub async fn get_jwks_returns_ok(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_jwks_handler),
+    )
+    .await;
+
+    let request = test::TestRequest::get()
+        .uri("/.well-known/jwks.json")
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let jwks: JwkSet = serde_json::from_slice(&body).expect("Failed to deserialize error");
+
+    // the test environment signs with a shared secret, which is never published
+    assert!(jwks.keys.is_empty());
+}
\ No newline at end of file
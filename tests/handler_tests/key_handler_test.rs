@@ -0,0 +1,185 @@
+use actix_web::{test, App, http};
+use bulk_sms_api::{handler, keyring::JwtKey, model::signing_key::SigningKey, JwtConfig};
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::Pool;
+
+use crate::handler_tests::{generate_token, init_app_state};
+
+#[sqlx::test]
+pub async fn get_keys_returns_active_key(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_key_handler),
+    )
+    .await;
+
+    let request = test::TestRequest::get()
+        .uri("/keys")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let keys: Vec<SigningKey> = serde_json::from_slice(&body).expect("Failed to deserialize error");
+
+    assert!(keys.iter().any(|key| key.active && key.can_sign));
+}
+
+#[sqlx::test]
+pub async fn retire_key_returns_bad_request_when_key_is_active(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+    let active_kid = app_state.jwt_config.keyring().active().kid.clone();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_key_handler),
+    )
+    .await;
+
+    let request = test::TestRequest::delete()
+        .uri(&format!("/keys/{}", active_kid))
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+pub async fn promote_key_returns_not_found_when_key_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_key_handler),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/keys/unknown/promote")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+pub async fn promote_key_is_applied_by_other_servers(pool: Pool<sqlx::Postgres>) {
+    // given
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+    let next = JwtKey::from_secret("next");
+    let next_kid = next.kid.clone();
+    app_state.jwt_config.keyring_mut().insert(next);
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_key_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::post()
+        .uri(&format!("/keys/{}/promote", next_kid))
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert_eq!(app_state.jwt_config.keyring().active().kid, next_kid);
+
+    let other_server = JwtConfig::from_env();
+    other_server.keyring_mut().insert(JwtKey::from_secret("next"));
+    other_server.sync_keys(&app_state.context).await.unwrap();
+
+    assert_eq!(other_server.keyring().active().kid, next_kid);
+}
+
+#[sqlx::test]
+pub async fn promote_key_keeps_current_key_active_until_activation(pool: Pool<sqlx::Postgres>) {
+    // given
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+    let active_kid = app_state.jwt_config.keyring().active().kid.clone();
+    let next = JwtKey::from_secret("next");
+    let next_kid = next.kid.clone();
+    app_state.jwt_config.keyring_mut().insert(next);
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_key_handler),
+    )
+    .await;
+
+    let body = json!({ "activateAt": Utc::now() + Duration::hours(1) });
+
+    // when
+    let request = test::TestRequest::post()
+        .uri(&format!("/keys/{}/promote", next_kid))
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert_eq!(app_state.jwt_config.keyring().active().kid, active_kid);
+
+    let schedules = app_state.context.signing_key_schedules.find_all().await.unwrap();
+    assert!(schedules.iter().any(|schedule| schedule.kid == next_kid && schedule.activate_at.is_some()));
+}
+
+#[sqlx::test]
+pub async fn retire_key_is_applied_by_other_servers(pool: Pool<sqlx::Postgres>) {
+    // given
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+    let next = JwtKey::from_secret("next");
+    let next_kid = next.kid.clone();
+    app_state.jwt_config.keyring_mut().insert(next);
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_key_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::delete()
+        .uri(&format!("/keys/{}", next_kid))
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert!(app_state.jwt_config.keyring().find(&next_kid).is_none());
+
+    let other_server = JwtConfig::from_env();
+    other_server.keyring_mut().insert(JwtKey::from_secret("next"));
+    other_server.sync_keys(&app_state.context).await.unwrap();
+
+    assert!(other_server.keyring().find(&next_kid).is_none());
+}
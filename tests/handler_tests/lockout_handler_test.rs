@@ -0,0 +1,121 @@
+use actix_web::{test, App, http};
+use bulk_sms_api::{entity::lockout::EmailLockout, handler};
+use sqlx::Pool;
+
+use crate::handler_tests::{generate_token, generate_token_with_permissions, init_app_state};
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn get_user_lockout_returns_failed_attempts(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_lockout_handler),
+    )
+    .await;
+
+    // given
+    app_state.context.email_lockouts.record_failure("jsmith@test.com", &15, &5, &15).await.unwrap();
+
+    // when
+    let request = test::TestRequest::get()
+        .uri("/users/1/lockout")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let lockout: EmailLockout = test::call_and_read_body_json(&mut app, request).await;
+
+    // then
+    assert_eq!(lockout.failed_attempts, 1);
+    assert!(lockout.locked_until.is_none());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn unlock_user_clears_lockout(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_lockout_handler),
+    )
+    .await;
+
+    // given
+    app_state.context.email_lockouts.record_failure("jsmith@test.com", &15, &1, &15).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post()
+        .uri("/users/1/unlock")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert!(app_state.context.email_lockouts.find_by_email_address("jsmith@test.com").await.unwrap().is_none());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn unlock_user_returns_forbidden_without_user_update(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &["USER_READ"]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_lockout_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::post()
+        .uri("/users/1/unlock")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test]
+pub async fn unlock_ip_address_clears_lockout_or_returns_not_found(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_lockout_handler),
+    )
+    .await;
+
+    // given
+    app_state.context.ip_lockouts.record_failure(&"10.0.0.1".to_string(), &15, &1, &15).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post()
+        .uri("/ip-lockouts/10.0.0.1/unlock")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/ip-lockouts/10.0.0.1/unlock")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+}
\ No newline at end of file
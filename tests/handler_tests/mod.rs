@@ -1,8 +1,8 @@
-use std::{sync::Arc, env};
+use std::sync::Arc;
 use argon2::Config;
 
 use actix_web::web::{self, Data};
-use bulk_sms_api::{dao::Database, entity::{permission::Permission, role::Role, user::User}, error::AppError, jwt, AppState, JwtConfig};
+use bulk_sms_api::{config::AccountConfig, dao::Database, entity::{permission::Permission, role::Role, user::User}, error::AppError, jwt, password_policy::PasswordPolicy, permissions, revocation::RevocationStore, AppState, JwtConfig};
 use chrono::Utc;
 //configure_log,
 use dotenvy::dotenv;
@@ -16,27 +16,66 @@ mod role_handler_test;
 mod user_handler_test;
 #[cfg(test)]
 mod auth_handler_test;
+#[cfg(test)]
+mod jwks_handler_test;
+#[cfg(test)]
+mod key_handler_test;
+#[cfg(test)]
+mod password_handler_test;
+#[cfg(test)]
+mod email_outbox_handler_test;
+#[cfg(test)]
+mod email_template_handler_test;
+#[cfg(test)]
+mod lockout_handler_test;
 
 pub async fn init_app_state(pool: Pool<sqlx::Postgres>) -> Data<AppState<'static>> {
+    init_app_state_with_account_config(pool, |_| {}).await
+}
+
+pub async fn init_app_state_with_account_config(pool: Pool<sqlx::Postgres>, configure: impl FnOnce(&mut AccountConfig)) -> Data<AppState<'static>> {
     dotenv().ok();
 
     let db_context = Database::test(pool).await;
 
     let config = Config::default();
 
-    let secret = env::var("JWT_SECRET").expect("JWT_SECRET was not provided.");
-    let expires_in = env::var("JWT_EXPIRES_IN").expect("JWT_EXPIRES_IN was not provided.").parse::<i64>().expect("JWT_EXPIRES_IN should be an i32.");
+    let jwt_config = JwtConfig::from_env();
+
+    let mut account_config = AccountConfig::from_env();
+
+    // the role fixture replaces the seeded USER role, so self-registered users become marketers
+    account_config.sign_up_role = "MARKETER".to_string();
+    configure(&mut account_config);
+
+    // not every test loads roles, those that sign up do
+    account_config.resolve_sign_up_role(&db_context).await.ok();
 
-    let jwt_config = JwtConfig {secret, expires_in};
+    let revocation_store = RevocationStore::new(account_config.revocation_cache_ttl);
     
     web::Data::new(AppState {
         context: Arc::new(db_context),
         argon_config: Arc::new(config),
         jwt_config: Arc::new(jwt_config),
+        revocation_store: Arc::new(revocation_store),
+        account_config: Arc::new(account_config),
+        password_policy: Arc::new(PasswordPolicy::default()),
     })
 }
 
+// the fixture users have not confirmed their email address, so they cannot sign in until activated
+pub async fn activate_user(app_state: &AppState<'_>, user_id: i32) {
+    let mut user = app_state.context.users.find_by_id(&user_id).await.unwrap();
+    user.enabled = true;
+    user.email_confirmed = true;
+    app_state.context.users.update_user(&user).await.unwrap();
+}
+
 pub async fn generate_token(config: &JwtConfig) -> Result<String , AppError> {
+    generate_token_with_permissions(config, permissions::ALL).await
+}
+
+pub async fn generate_token_with_permissions(config: &JwtConfig, names: &[&str]) -> Result<String , AppError> {
     let user = User {
         user_id: 1,
         first_name: "John".into(),
@@ -48,38 +87,27 @@ pub async fn generate_token(config: &JwtConfig) -> Result<String , AppError> {
         email_confirmed: true,
         role_id: 1,
         created_at: Utc::now(),
-
+        locale: "en".into(),
     };
 
     let role = Role {
         role_id: 1,
         name: "SUPER_ADMIN".into(),
         created_at: Utc::now(),
+        description: None,
+        is_system: true,
     };
 
-    let read = Permission {
-        permission_id: 1,
-        name: "PERMISSION_READ".into(),
-        created_at: Utc::now(),
-    };
-
-    let write = Permission {
-        permission_id: 1,
-        name: "PERMISSION_WRITE".into(),
-        created_at: Utc::now(),
-    };
-
-    let update = Permission {
-        permission_id: 1,
-        name: "PERMISSION_UPDATE".into(),
-        created_at: Utc::now(),
-    };
-
-    let delete = Permission {
-        permission_id: 1,
-        name: "PERMISSION_DELETE".into(),
-        created_at: Utc::now(),
-    };
+    let permissions = names.iter()
+        .enumerate()
+        .map(|(index, name)| Permission {
+            permission_id: index as i16 + 1,
+            name: name.to_string(),
+            created_at: Utc::now(),
+            description: None,
+            is_system: true,
+        })
+        .collect();
     
-    jwt::generate_token(user, role, vec![read, write, update, delete], config).await
+    jwt::generate_token(user, role, permissions, &config.audience, config).await
 }
\ No newline at end of file
@@ -0,0 +1,380 @@
+use actix_web::{test, App, http};
+use bulk_sms_api::{email::{memory::InMemoryEmailSender, outbox::{self, OutboxConfig}}, error::AppResponseError, handler, model::{app_response::AppResponse, password_reset::{ForgotPassword, ResetPassword}, sign_in::SignIn}, util};
+use chrono::{Duration, Utc};
+use sqlx::Pool;
+use std::sync::Arc;
+
+use crate::handler_tests::{activate_user, init_app_state};
+
+#[sqlx::test]
+pub async fn forgot_password_returns_ok_when_email_address_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let email_sender = Arc::new(InMemoryEmailSender::default());
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_password_handler),
+    )
+    .await;
+
+    // given
+    let payload = ForgotPassword{email_address: "unknown@test.com".to_string()};
+
+    // when
+    let request = test::TestRequest::post().uri("/password/forgot")
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    outbox::drain(&app_state.context, email_sender.as_ref(), &OutboxConfig::from_env()).await.unwrap();
+
+    let sent = email_sender.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].to, "unknown@test.com");
+    assert!(!sent[0].text.contains(&app_state.account_config.password_reset_url));
+}
+
+#[sqlx::test]
+pub async fn forgot_password_responds_in_negotiated_locale(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_password_handler),
+    )
+    .await;
+
+    // given
+    let payload = ForgotPassword{email_address: "jsmith@test.com".to_string()};
+
+    // when
+    let request = test::TestRequest::post().uri("/password/forgot")
+        .insert_header(("Accept-Language", "fr"))
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let result: AppResponse = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(result.message, "Si l'adresse e-mail est enregistrée, un lien de réinitialisation du mot de passe a été envoyé.");
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user", "user_credential")))]
+pub async fn forgot_password_sends_reset_link_when_email_address_exists(pool: Pool<sqlx::Postgres>) {
+    let email_sender = Arc::new(InMemoryEmailSender::default());
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_password_handler),
+    )
+    .await;
+
+    // given
+    let payload = ForgotPassword{email_address: "jsmith@test.com".to_string()};
+
+    // when
+    let request = test::TestRequest::post().uri("/password/forgot")
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    outbox::drain(&app_state.context, email_sender.as_ref(), &OutboxConfig::from_env()).await.unwrap();
+
+    let sent = email_sender.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].to, "jsmith@test.com");
+    assert!(sent[0].html.contains(&app_state.account_config.password_reset_url));
+    assert!(sent[0].text.contains(&app_state.account_config.password_reset_url));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user", "user_credential")))]
+pub async fn forgot_password_returns_too_many_requests_within_cooldown(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_password_handler),
+    )
+    .await;
+
+    // given
+    let payload = ForgotPassword{email_address: "jsmith@test.com".to_string()};
+
+    let request = test::TestRequest::post().uri("/password/forgot")
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    // when
+    let request = test::TestRequest::post().uri("/password/forgot")
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::TOO_MANY_REQUESTS);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("password.reset_too_soon"));
+}
+
+#[sqlx::test]
+pub async fn forgot_password_applies_cooldown_to_unknown_email_address(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_password_handler),
+    )
+    .await;
+
+    // given
+    let payload = ForgotPassword{email_address: "unknown@test.com".to_string()};
+
+    let request = test::TestRequest::post().uri("/password/forgot")
+        .set_json(&payload)
+        .to_request();
+
+    test::call_service(&mut app, request).await;
+
+    // when
+    let request = test::TestRequest::post().uri("/password/forgot")
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user", "user_credential")))]
+pub async fn reset_password_returns_bad_request_when_token_belongs_to_unknown_email_address(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_password_handler),
+    )
+    .await;
+
+    // given
+    let token = util::generate_password_reset_token().await;
+    let token_hash = util::hash_token(&token).await;
+
+    app_state.context.password_reset_tokens.create(None, &"unknown@test.com".to_string(), &token_hash, &(Utc::now() + Duration::minutes(30)), &0).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post().uri("/password/reset")
+        .set_json(&ResetPassword{token, password: "new_password".to_string()})
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user", "user_credential")))]
+pub async fn reset_password_updates_the_password_and_consumes_the_token(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_password_handler)
+            .configure(handler::init_auth_handler),
+    )
+    .await;
+
+    // given
+    let user_id = 1;
+    activate_user(&app_state, user_id).await;
+
+    let token = util::generate_password_reset_token().await;
+    let token_hash = util::hash_token(&token).await;
+    let expires_at = Utc::now() + Duration::minutes(30);
+
+    app_state.context.password_reset_tokens.create(Some(&user_id), &"jsmith@test.com".to_string(), &token_hash, &expires_at, &0).await.unwrap();
+
+    let payload = ResetPassword{token: token.clone(), password: "new_password".to_string()};
+
+    // when
+    let request = test::TestRequest::post().uri("/password/reset")
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = test::TestRequest::post().uri("/sign-in")
+        .set_json(&SignIn{email_address: "jsmith@test.com".to_string(), password: "new_password".to_string(), audience: None})
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = test::TestRequest::post().uri("/password/reset")
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user", "user_credential")))]
+pub async fn reset_password_sends_password_changed_notification(pool: Pool<sqlx::Postgres>) {
+    let email_sender = Arc::new(InMemoryEmailSender::default());
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_password_handler),
+    )
+    .await;
+
+    // given
+    let user_id = 1;
+    let token = util::generate_password_reset_token().await;
+    let token_hash = util::hash_token(&token).await;
+    let expires_at = Utc::now() + Duration::minutes(30);
+
+    app_state.context.password_reset_tokens.create(Some(&user_id), &"jsmith@test.com".to_string(), &token_hash, &expires_at, &0).await.unwrap();
+
+    let payload = ResetPassword{token, password: "new_password".to_string()};
+
+    // when
+    let request = test::TestRequest::post().uri("/password/reset")
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    outbox::drain(&app_state.context, email_sender.as_ref(), &OutboxConfig::from_env()).await.unwrap();
+
+    let sent = email_sender.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].to, "jsmith@test.com");
+    assert_eq!(sent[0].subject, "Your password was changed");
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user", "user_credential")))]
+pub async fn reset_password_returns_bad_request_when_token_has_expired(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_password_handler),
+    )
+    .await;
+
+    // given
+    let user_id = 1;
+    let token = util::generate_password_reset_token().await;
+    let token_hash = util::hash_token(&token).await;
+    let expires_at = Utc::now() - Duration::minutes(1);
+
+    app_state.context.password_reset_tokens.create(Some(&user_id), &"jsmith@test.com".to_string(), &token_hash, &expires_at, &0).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post().uri("/password/reset")
+        .set_json(&ResetPassword{token, password: "new_password".to_string()})
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+pub async fn reset_password_returns_bad_request_when_token_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_password_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::post().uri("/password/reset")
+        .set_json(&ResetPassword{token: "unknown".to_string(), password: "new_password".to_string()})
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user", "user_credential")))]
+pub async fn reset_password_rejects_password_against_policy_without_using_the_token(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_password_handler),
+    )
+    .await;
+
+    // given
+    let token = util::generate_password_reset_token().await;
+    let token_hash = util::hash_token(&token).await;
+
+    app_state.context.password_reset_tokens.create(Some(&1), &"jsmith@test.com".to_string(), &token_hash, &(Utc::now() + Duration::minutes(30)), &0).await.unwrap();
+
+    // when
+    let request = test::TestRequest::post().uri("/password/reset")
+        .set_json(&ResetPassword{token: token.clone(), password: "JohnSmith2024".to_string()})
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("password.contains_personal_information"));
+
+    let reset_token = app_state.context.password_reset_tokens.find_by_token_hash(&token_hash).await.unwrap();
+    assert!(reset_token.used_at.is_none());
+}
\ No newline at end of file
@@ -1,9 +1,9 @@
 use actix_web::{test, App, http};
-use bulk_sms_api::{handler, entity::permission::{CreatePermission, Permission}, error::AppResponseError, model::app_response::AppResponse};
+use bulk_sms_api::{handler, entity::permission::{CreatePermission, PatchPermission, Permission, UpdatePermission}, error::AppResponseError, model::{app_response::AppResponse, deletion::{DeletionConflict, PermissionDependants}}};
 use sqlx::Pool;
 use serde_json::json;
 
-use crate::handler_tests::{generate_token, init_app_state};
+use crate::handler_tests::{generate_token, generate_token_with_permissions, init_app_state};
 
 #[sqlx::test]
 pub async fn get_permissions_returns_ok(pool: Pool<sqlx::Postgres>) {
@@ -120,7 +120,7 @@ pub async fn create_permission_returns_ok_when_name_does_not_exist(pool: Pool<sq
     )
     .await;
 
-    let name = "PERMISSION_READ";
+    let name = "REPORT_READ";
     let body = CreatePermission {
         name: name.to_string()
     };
@@ -139,7 +139,7 @@ pub async fn create_permission_returns_ok_when_name_does_not_exist(pool: Pool<sq
     let body = test::read_body(response).await;
     let permission: Permission =     serde_json::from_slice(&body).expect("Failed to deserialize error");
 
-    assert_eq!(permission.permission_id, 1);
+    assert!(!permission.is_system);
     assert_eq!(permission.name, name);
 }
 
@@ -222,7 +222,7 @@ pub async fn delete_permission_with_id_returns_not_found_when_id_does_not_exist(
     .await;
 
     // given
-    let permission_id = 1;
+    let permission_id = 2001;
 
     // when
     let request = test::TestRequest::delete().uri(format!("/permissions/{}", permission_id).as_str())
@@ -237,6 +237,148 @@ pub async fn delete_permission_with_id_returns_not_found_when_id_does_not_exist(
 
     let result: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
 
-    assert_eq!(result.error, "Permission with id 1 could not be found!");
+    assert_eq!(result.error, "Permission with id 2001 could not be found!");
+    // then
+}
+
+#[sqlx::test]
+pub async fn create_permission_returns_forbidden_when_permission_is_missing(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &["PERMISSION_READ"]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_permission_handler),
+    )
+    .await;
+
+    let payload = json!(CreatePermission { name: "PERMISSION_READ".to_string() });
+
+    let request = test::TestRequest::post().uri("/permissions")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
+
+    assert_eq!(error.error, "You are not allowed to perform this action!");
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("permission")))]
+pub async fn update_permission_returns_conflict_when_name_is_taken(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_permission_handler),
+    )
+    .await;
+
+    // given
+    let payload = json!(UpdatePermission { name: "PERMISSION_READ".to_string(), description: None });
+
+    // when
+    let request = test::TestRequest::put().uri("/permissions/2")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::CONFLICT);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("permission")))]
+pub async fn patch_permission_returns_ok(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_permission_handler),
+    )
+    .await;
+
+    // given
+    let payload = json!(PatchPermission { name: None, description: Some("Delete permissions".to_string()) });
+
+    // when
+    let request = test::TestRequest::patch().uri("/permissions/4")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let permission: Permission = serde_json::from_slice(&body).expect("Failed to deserialize permission");
+
+    assert_eq!(permission.name, "PERMISSION_DELETE");
+    assert_eq!(permission.description, Some("Delete permissions".to_string()));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn delete_permission_with_id_returns_conflict_when_assigned_to_roles(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_permission_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::delete().uri("/permissions/2")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
     // then
+    assert_eq!(response.status(), http::StatusCode::CONFLICT);
+
+    let body = test::read_body(response).await;
+    let conflict: DeletionConflict<PermissionDependants> = serde_json::from_slice(&body).expect("Failed to deserialize conflict");
+
+    assert_eq!(conflict.dependants.roles, vec![1]);
 }
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn delete_permission_with_id_and_force_returns_ok(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_permission_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::delete().uri("/permissions/2?force=true")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+}
\ No newline at end of file
@@ -1,9 +1,9 @@
 use actix_web::{test, App, http};
-use bulk_sms_api::{handler, entity::{role::{Role, CreateRole}, permission::Permission}, error::AppResponseError, model::app_response::AppResponse};
+use bulk_sms_api::{handler, entity::{role::{Role, CreateRole, PatchRole, UpdateRole}, permission::Permission}, error::AppResponseError, model::{app_response::AppResponse, deletion::{DeletionConflict, RoleDependants}, role_permission::RolePermissionChanges}};
 use serde_json::json;
 use sqlx::Pool;
 
-use crate::handler_tests::{generate_token, init_app_state};
+use crate::handler_tests::{generate_token, generate_token_with_permissions, init_app_state};
 
 #[sqlx::test]
 pub async fn get_roles_returns_ok(pool: Pool<sqlx::Postgres>) {
@@ -142,7 +142,7 @@ pub async fn create_role_returns_ok_when_name_does_not_exist(pool: Pool<sqlx::Po
     let body = test::read_body(response).await;
     let role: Role =     serde_json::from_slice(&body).expect("Failed to deserialize error");
 
-    assert_eq!(role.role_id, 1);
+    assert!(!role.is_system);
     assert_eq!(role.name, name);
 }
 
@@ -158,7 +158,7 @@ pub async fn create_role_returns_bad_request_when_name_exists(pool: Pool<sqlx::P
     )
     .await;
 
-    let name = "ROLE_READ";
+    let name = "ADMIN";
     let body = CreateRole {
         name: name.to_string()
     };
@@ -225,7 +225,7 @@ pub async fn delete_role_with_id_returns_not_found_when_id_does_not_exist(pool:
     .await;
 
     // given
-    let role_id = 1;
+    let role_id = 101;
 
     // when
     let request = test::TestRequest::delete().uri(format!("/roles/{}", role_id).as_str())
@@ -240,7 +240,7 @@ pub async fn delete_role_with_id_returns_not_found_when_id_does_not_exist(pool:
 
     let result: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
 
-    assert_eq!(result.error, "Role with id 1 could not be found!");
+    assert_eq!(result.error, "Role with id 101 could not be found!");
 }
 
 #[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
@@ -328,4 +328,687 @@ pub async fn get_role_permissions_returns_not_found_when_role_id_does_not_exist(
     let error: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
 
     assert_eq!(error.error, "Role with id 101 could not be found!");
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn delete_role_with_id_returns_forbidden_when_permission_is_missing(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &["ROLE_READ"]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    let request = test::TestRequest::delete()
+        .uri("/roles/4")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn update_role_permissions_returns_changes(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    let body = json!({ "permissionIds": [1, 2] });
+
+    // when
+    let request = test::TestRequest::put().uri("/roles/1/permissions")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let changes: RolePermissionChanges = serde_json::from_slice(&body).expect("Failed to deserialize changes");
+
+    assert_eq!(changes, RolePermissionChanges { added: vec![], removed: vec![3, 4] });
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn update_role_permissions_returns_bad_request_and_keeps_permissions_when_permission_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    let body = json!({ "permissionIds": [1, 20001] });
+
+    // when
+    let request = test::TestRequest::put().uri("/roles/1/permissions")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+
+    let permissions = app_state.context.role_permissions.find_role_permissions(&1).await.unwrap();
+    assert_eq!(permissions.len(), 4);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("permission")))]
+pub async fn update_role_permissions_returns_not_found_when_role_id_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    let body = json!({ "permissionIds": [1] });
+
+    // when
+    let request = test::TestRequest::put().uri("/roles/101/permissions")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission")))]
+pub async fn add_role_permission_returns_created(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::post().uri("/roles/2/permissions/3")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::CREATED);
+
+    let body = test::read_body(response).await;
+    let changes: RolePermissionChanges = serde_json::from_slice(&body).expect("Failed to deserialize changes");
+
+    assert_eq!(changes.added, vec![3]);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission")))]
+pub async fn add_role_permission_returns_not_found_when_permission_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::post().uri("/roles/2/permissions/101")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
+
+    assert_eq!(error.error, "Permission with id 101 could not be found!");
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn remove_role_permission_returns_ok(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::delete().uri("/roles/1/permissions/2")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let result: AppResponse = serde_json::from_slice(&body).expect("Failed to deserialize response");
+
+    assert_eq!(result.message, "Permission removed from role successfully.");
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission")))]
+pub async fn remove_role_permission_returns_not_found_when_permission_is_not_assigned(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::delete().uri("/roles/2/permissions/1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn update_role_permissions_returns_forbidden_when_granting_permissions_the_caller_lacks(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &["ROLE_UPDATE", "PERMISSION_READ"]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    let body = json!({ "permissionIds": [1, 2, 3] });
+
+    // when
+    let request = test::TestRequest::put().uri("/roles/2/permissions")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
+
+    assert_eq!(error.error, "Only permissions you hold can be granted, missing PERMISSION_WRITE, PERMISSION_UPDATE!");
+    assert!(app_state.context.role_permissions.find_role_permissions(&2).await.unwrap().is_empty());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn update_role_permissions_allows_keeping_permissions_the_caller_lacks(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &["ROLE_UPDATE"]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    let body = json!({ "permissionIds": [1, 2] });
+
+    // when
+    let request = test::TestRequest::put().uri("/roles/1/permissions")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn add_role_permission_returns_forbidden_when_the_caller_lacks_the_permission(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &["ROLE_UPDATE"]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::post().uri("/roles/2/permissions/4")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
+
+    assert_eq!(error.code, Some("role.permissions_not_held".to_string()));
+    assert!(app_state.context.role_permissions.find_role_permissions(&2).await.unwrap().is_empty());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission")))]
+pub async fn update_role_permissions_returns_forbidden_when_permission_is_missing(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &["ROLE_READ", "ROLE_WRITE"]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    let body = json!({ "permissionIds": [] });
+
+    // when
+    let request = test::TestRequest::put().uri("/roles/1/permissions")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn update_role_returns_ok(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    let payload = json!(UpdateRole { name: "MARKETING".to_string(), description: Some("Runs SMS campaigns".to_string()) });
+
+    // when
+    let request = test::TestRequest::put().uri("/roles/3")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let role: Role = serde_json::from_slice(&body).expect("Failed to deserialize role");
+
+    assert_eq!(role.name, "MARKETING");
+    assert_eq!(role.description, Some("Runs SMS campaigns".to_string()));
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role")))]
+pub async fn update_role_returns_conflict_when_name_is_taken(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    let payload = json!(UpdateRole { name: "ADMIN".to_string(), description: None });
+
+    // when
+    let request = test::TestRequest::put().uri("/roles/3")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::CONFLICT);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
+
+    assert_eq!(error.error, "Role already exists!");
+}
+
+#[sqlx::test]
+pub async fn patch_role_returns_not_found_when_role_id_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    let payload = json!(PatchRole { name: None, description: Some("Unused".to_string()) });
+
+    // when
+    let request = test::TestRequest::patch().uri("/roles/101")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission", "user")))]
+pub async fn delete_role_with_id_returns_conflict_listing_dependants(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::delete().uri("/roles/1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::CONFLICT);
+
+    let body = test::read_body(response).await;
+    let conflict: DeletionConflict<RoleDependants> = serde_json::from_slice(&body).expect("Failed to deserialize conflict");
+
+    assert_eq!(conflict.error, "Role with id 1 is still in use!");
+    assert_eq!(conflict.dependants, RoleDependants { users: vec![1, 2], permissions: vec![1, 2, 3, 4] });
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission", "user")))]
+pub async fn delete_role_with_id_reassigns_users_and_unlinks_permissions(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::delete().uri("/roles/1?force=true&reassignTo=3")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert_eq!(app_state.context.users.find_by_id(&1).await.unwrap().role_id, 3);
+
+    // tokens issued under the deleted role no longer apply
+    for user_id in [1, 2] {
+        assert!(app_state.context.user_token_revocations.find_by_user_id(&user_id).await.unwrap().revoked_at.is_some());
+    }
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn delete_role_with_id_returns_bad_request_when_reassign_target_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::delete().uri("/roles/1?reassignTo=101")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+    assert_eq!(app_state.context.users.find_by_id(&1).await.unwrap().role_id, 1);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn delete_role_with_id_returns_forbidden_when_reassigning_without_user_update(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &["ROLE_DELETE"]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::delete().uri("/roles/1?reassignTo=2")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "role_permission", "user")))]
+pub async fn delete_role_with_id_returns_forbidden_when_reassigning_to_a_role_with_permissions_the_caller_lacks(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &["ROLE_DELETE", "USER_UPDATE", "PERMISSION_READ"]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::delete().uri("/roles/2?reassignTo=1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
+
+    assert_eq!(error.code, Some("role.reassign_privileged".to_string()));
+    assert!(app_state.context.roles.find_by_id(&2).await.is_ok());
+}
+
+#[sqlx::test]
+pub async fn delete_role_with_id_returns_forbidden_when_reassigning_to_a_system_role(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &["ROLE_DELETE", "USER_UPDATE"]).await.unwrap();
+    let system_role = app_state.context.roles.find_by_name("SUPER_ADMIN").await.unwrap();
+    let role = app_state.context.roles.create(&CreateRole { name: "SUPPORT".to_string() }).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::delete().uri(&format!("/roles/{}?reassignTo={}", role.role_id, system_role.role_id))
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+    assert!(app_state.context.roles.find_by_id(&role.role_id).await.is_ok());
+}
+
+#[sqlx::test]
+pub async fn delete_role_with_id_returns_forbidden_for_system_role(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::delete().uri("/roles/1?force=true")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
+
+    assert_eq!(error.error, "System role 1 cannot be deleted or renamed!");
+}
+
+#[sqlx::test]
+pub async fn update_role_returns_forbidden_when_renaming_system_role(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    let payload = json!(UpdateRole { name: "ROOT".to_string(), description: None });
+
+    // when
+    let request = test::TestRequest::put().uri("/roles/1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&payload)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test]
+pub async fn update_role_permissions_returns_forbidden_when_stripping_core_permissions(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_role_handler),
+    )
+    .await;
+
+    // given
+    let body = json!({ "permissionIds": [] });
+
+    // when
+    let request = test::TestRequest::put().uri("/roles/1/permissions")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+
+    let permissions = app_state.context.role_permissions.find_role_permissions(&1).await.unwrap();
+    assert!(!permissions.is_empty());
 }
\ No newline at end of file
@@ -1,9 +1,9 @@
 use actix_web::{http, test, App};
-use bulk_sms_api::{model::{app_response::AppResponse, pagination::PaginatedResult, user::{CreateUser, UpdateUser}, user_credentials::{CreateUserCredential, UpdateUserCredential}}, entity::{user::User, user_credential::UserCredential}, error::AppResponseError, handler, util};
+use bulk_sms_api::{model::{app_response::AppResponse, pagination::PaginatedResult, user::{CreateUser, UpdateUser}, user_credentials::{CreateUserCredential, UpdateUserCredential}}, entity::{permission::Permission, user::User, user_credential::UserCredential}, error::AppResponseError, handler, util};
 use sqlx::Pool;
 use serde_json::json;
 
-use crate::handler_tests::{generate_token, init_app_state};
+use crate::handler_tests::{activate_user, generate_token, generate_token_with_permissions, init_app_state, init_app_state_with_account_config};
 
 #[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
 pub async fn get_user_by_id_returns_ok_when_id_exists(pool: Pool<sqlx::Postgres>) {
@@ -148,7 +148,8 @@ pub async fn create_user_returns_ok(pool: Pool<sqlx::Postgres>) {
         surname: "Doe".to_string(),
         email_address: "jsmith@test.com".to_string(),
         mobile_number: None,
-        role_id: 1
+        role_id: 1,
+        locale: None,
     };
 
     let payload = json!(body);
@@ -194,7 +195,8 @@ pub async fn create_user_returns_bad_request_when_email_address_exists(pool: Poo
         surname: "Doe".to_string(),
         email_address: "jsmith@test.com".to_string(),
         mobile_number: None,
-        role_id: 1
+        role_id: 1,
+        locale: None,
     };
 
     let payload = json!(body);
@@ -236,7 +238,8 @@ pub async fn update_user_returns_ok(pool: Pool<sqlx::Postgres>) {
         mobile_number: Some("0700000000".to_string()),
         enabled: false,
         email_confirmed: false,
-        role_id: 1
+        role_id: 1,
+        locale: None,
     };
 
     let payload = json!(body);
@@ -262,6 +265,256 @@ pub async fn update_user_returns_ok(pool: Pool<sqlx::Postgres>) {
     assert_eq!(user.mobile_number.unwrap(), "0700000000");
 }
 
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn update_user_revokes_tokens_when_user_is_disabled(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    activate_user(&app_state, 1).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    // given
+    let body = UpdateUser {
+        first_name: "John".to_string(),
+        middle_name: None,
+        surname: "Smith".to_string(),
+        mobile_number: None,
+        enabled: false,
+        email_confirmed: true,
+        role_id: 1,
+        locale: None,
+    };
+
+    // when
+    let request = test::TestRequest::put().uri("/users/1")
+        .set_json(&json!(body))
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = test::TestRequest::get().uri("/users/1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn update_user_keeps_tokens_when_revocation_on_status_change_is_disabled(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state_with_account_config(pool, |config| config.revoke_tokens_on_status_change = false).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    activate_user(&app_state, 1).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    // given
+    let body = UpdateUser {
+        first_name: "John".to_string(),
+        middle_name: None,
+        surname: "Smith".to_string(),
+        mobile_number: None,
+        enabled: false,
+        email_confirmed: true,
+        role_id: 1,
+        locale: None,
+    };
+
+    // when
+    let request = test::TestRequest::put().uri("/users/1")
+        .set_json(&json!(body))
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    test::call_service(&mut app, request).await;
+
+    // then
+    let request = test::TestRequest::get().uri("/users/1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::OK);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn update_user_revokes_tokens_when_role_changes(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state_with_account_config(pool, |config| config.revoke_tokens_on_status_change = false).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    activate_user(&app_state, 1).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    // given
+    let body = UpdateUser {
+        first_name: "John".to_string(),
+        middle_name: None,
+        surname: "Smith".to_string(),
+        mobile_number: None,
+        enabled: true,
+        email_confirmed: true,
+        role_id: 3,
+        locale: None,
+    };
+
+    // when
+    let request = test::TestRequest::put().uri("/users/1")
+        .set_json(&json!(body))
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = test::TestRequest::get().uri("/users/1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "permission", "user")))]
+pub async fn update_user_returns_forbidden_when_moving_to_a_role_with_permissions_the_caller_lacks(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &["USER_UPDATE"]).await.unwrap();
+
+    app_state.context.role_permissions.add_role_permission(&3, &1).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    // given
+    let body = UpdateUser {
+        first_name: "John".to_string(),
+        middle_name: None,
+        surname: "Smith".to_string(),
+        mobile_number: None,
+        enabled: false,
+        email_confirmed: false,
+        role_id: 3,
+        locale: None,
+    };
+
+    // when
+    let request = test::TestRequest::put().uri("/users/1")
+        .set_json(&json!(body))
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+
+    let body = test::read_body(response).await;
+    let error: AppResponseError = serde_json::from_slice(&body).expect("Failed to deserialize error");
+
+    assert_eq!(error.code, Some("role.reassign_privileged".to_string()));
+    assert_eq!(app_state.context.users.find_by_id(&1).await.unwrap().role_id, 1);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn guard_rejects_tokens_of_users_disabled_outside_the_server(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state_with_account_config(pool.clone(), |config| config.revocation_cache_ttl = 0).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    activate_user(&app_state, 1).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    // given
+    let request = test::TestRequest::get().uri("/users/1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    // when, as the admin binary or an operator would
+    sqlx::query!(r#"UPDATE "SMS_GATEWAY_USER"."USER" SET enabled = false WHERE user_id = 1 "#)
+        .execute(&pool)
+        .await.unwrap();
+
+    // then
+    let request = test::TestRequest::get().uri("/users/1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn guard_rejects_tokens_revoked_outside_the_server(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state_with_account_config(pool, |config| config.revocation_cache_ttl = 0).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    // given
+    let request = test::TestRequest::get().uri("/users/1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    // when, bypassing the revocation store of this server
+    app_state.context.user_token_revocations.upsert(&1).await.unwrap();
+
+    // then
+    let request = test::TestRequest::get().uri("/users/1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+}
+
 #[sqlx::test]
 pub async fn update_user_returns_not_found_when_id_does_not_exist(pool: Pool<sqlx::Postgres>) {
     let app_state = init_app_state(pool).await;
@@ -282,7 +535,8 @@ pub async fn update_user_returns_not_found_when_id_does_not_exist(pool: Pool<sql
         mobile_number: Some("0700000000".to_string()),
         enabled: false,
         email_confirmed: false,
-        role_id: 1
+        role_id: 1,
+        locale: None,
     };
 
     let payload = json!(body);
@@ -498,7 +752,9 @@ pub async fn update_user_credential_returns_ok(pool: Pool<sqlx::Postgres>) {
     dbg!(":?", &response);
 
     assert_eq!(response.message, "Successfully updated!");
-    // assert!(util::verify_password(&update_user.password, &"newpassword".to_string()).await.unwrap());
+
+    let credential = app_state.context.user_credentials.find_by_user_id(&1).await.unwrap();
+    assert!(util::verify_password(&credential.password, "newpassword").await.unwrap());
 }
 
 #[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
@@ -582,3 +838,201 @@ pub async fn update_user_credential_returns_error_when_user_and_credential_do_no
 
     assert_eq!(response.error, "Credential does not exist!");
 }
+
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn revoke_user_tokens_returns_ok_and_rejects_existing_tokens(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    // when
+    let request = test::TestRequest::post()
+        .uri("/users/1/revoke-tokens")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri("/users/1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn revoke_user_tokens_accepts_tokens_issued_right_after(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/users/1/revoke-tokens")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    // when
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let request = test::TestRequest::get()
+        .uri("/users/1")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+}
+
+#[sqlx::test]
+pub async fn revoke_user_tokens_returns_not_found_when_user_does_not_exist(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token(&app_state.jwt_config).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/users/2001/revoke-tokens")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn delete_user_with_id_returns_forbidden_when_permission_is_missing(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &["USER_READ"]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    let request = test::TestRequest::delete()
+        .uri("/users/2")
+        .insert_header(("Authorization", format!("Bearer {}", jwt)))
+        .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user", "permission", "role_permission")))]
+pub async fn get_my_permissions_returns_permissions_of_callers_role(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &[]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::get()
+    .uri("/me/permissions")
+    .insert_header(("Authorization", format!("Bearer {}", jwt)))
+    .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let permissions: Vec<Permission> = serde_json::from_slice(&body).expect("Failed to deserialize permissions");
+
+    assert_eq!(permissions.len(), 4);
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user", "user_credential", "permission", "role_permission")))]
+pub async fn get_my_permissions_returns_nothing_while_password_change_is_required(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+    let jwt = generate_token_with_permissions(&app_state.jwt_config, &[]).await.unwrap();
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    // given
+    app_state.context.user_credentials.require_password_change(&1).await.unwrap();
+
+    // when
+    let request = test::TestRequest::get()
+    .uri("/me/permissions")
+    .insert_header(("Authorization", format!("Bearer {}", jwt)))
+    .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let permissions: Vec<Permission> = serde_json::from_slice(&body).expect("Failed to deserialize permissions");
+
+    assert!(permissions.is_empty());
+}
+
+#[sqlx::test(fixtures(path = "../fixtures", scripts("role", "user")))]
+pub async fn get_my_permissions_returns_unauthorized_without_token(pool: Pool<sqlx::Postgres>) {
+    let app_state = init_app_state(pool).await;
+
+    let mut app = test::init_service(
+        App::new()
+            .app_data(app_state.clone())
+            .configure(handler::init_user_handler),
+    )
+    .await;
+
+    // given
+    // when
+    let request = test::TestRequest::get()
+    .uri("/me/permissions")
+    .to_request();
+
+    let response = test::call_service(&mut app, request).await;
+
+    // then
+    assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+}
\ No newline at end of file
@@ -1,4 +1,9 @@
+// the tests pass the service and request bodies by reference, as actix examples do
+#![allow(clippy::unnecessary_mut_passed, clippy::needless_borrows_for_generic_args, clippy::unnecessary_to_owned)]
+
 #[cfg(test)]
 mod dao_tests;
 #[cfg(test)]
-mod handler_tests;
\ No newline at end of file
+mod handler_tests;
+#[cfg(test)]
+mod admin_tests;
\ No newline at end of file